@@ -5,6 +5,7 @@ use super::Dnd5eapiError;
 use crate::getter::DataProvider;
 use crate::rules2014::background::{BackgroundBuilder, LanguageOption};
 use crate::rules2014::features::Feature;
+use crate::rules2014::items::Coins;
 use crate::rules2014::{background::Background, features::PresentedOption, stats::SkillType};
 use serde_json::Value;
 
@@ -66,9 +67,19 @@ pub async fn get_background(
     let language_options: Vec<LanguageOption> =
         vec![LanguageOption::UnnamedChoice, LanguageOption::UnnamedChoice];
 
+    // the api leaves out starting money, so it's hardcoded like the languages are.
+    let starting_coins = match name.as_str() {
+        "acolyte" => Coins {
+            gp: 15,
+            ..Default::default()
+        },
+        _ => Coins::default(),
+    };
+
     BackgroundBuilder::new(&name)
         .add_proficiencies(proficiencies.clone())
         .add_equipment_set(equipment.clone())
+        .set_starting_coins(starting_coins)
         .add_feature(feature)
         .add_personality_traits(personality_traits)
         .add_ideals(ideals)
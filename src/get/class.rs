@@ -9,6 +9,7 @@ use crate::{
         stats::{EquipmentProficiencies, SkillType, StatType},
     },
 };
+use futures::future::try_join_all;
 use heck::ToTitleCase;
 use serde_json::{Map, Value};
 use std::collections::HashMap;
@@ -56,16 +57,14 @@ pub async fn get_class(
 }
 
 async fn subclasses(map: &Value) -> Result<Vec<Subclass>, Dnd5eapiError> {
-    let subclass_val_array = map.get_array("subclasses")?;
-
-    let mut subclasses: Vec<Subclass> = Vec::with_capacity(subclass_val_array.len());
-    for subclass_val in subclass_val_array.iter() {
-        let subclass_index = subclass_val.get_str("index")?;
-        let subclass = get_subclass(&subclass_index).await?;
-        subclasses.push(subclass);
-    }
+    let subclass_indexes = map
+        .get_array("subclasses")?
+        .iter()
+        .map(|v| v.get_str("index"))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    Ok(subclasses)
+    // fetched all at once, since each subclass takes its own requests
+    try_join_all(subclass_indexes.iter().map(|index| get_subclass(index))).await
 }
 
 fn equipment_proficiencies_inner(proficiency_strings: Vec<String>) -> EquipmentProficiencies {
@@ -205,7 +204,7 @@ async fn items(
 
         let item = process_equipment(getter, &equipment_value["equipment"]).await?;
 
-        equipment.push(PresentedOption::Base(vec![(ItemCategory::Item(item), num)]));
+        equipment.push(PresentedOption::Base(vec![(ItemCategory::Item(Box::new(item)), num)]));
     }
 
     let equipment_options_arr = map.get_array("starting_equipment_options")?;
@@ -278,7 +277,7 @@ async fn class_item_choice(
 
                 let item = process_equipment(getter, equipment).await?;
 
-                new_vec.push((ItemCategory::Item(item), count));
+                new_vec.push((ItemCategory::Item(Box::new(item)), count));
             }
 
             Ok(new_vec)
@@ -329,10 +328,12 @@ fn equipment_category(map: &Value) -> Result<ItemCategory, Dnd5eapiError> {
         name: equipment_name.clone(),
         description: None,
         item_type: ItemType::Misc,
+        weight: None,
+        attunement: None,
         features: vec![],
     };
 
-    Ok(ItemCategory::Item(item))
+    Ok(ItemCategory::Item(Box::new(item)))
 }
 
 async fn process_equipment(
@@ -346,11 +347,9 @@ async fn process_equipment(
 async fn class_features(
     levels_arr: [&Value; 20],
 ) -> Result<[Vec<PresentedOption<Feature>>; 20], Dnd5eapiError> {
-    let mut levels_vec = Vec::with_capacity(20);
-
-    for level in levels_arr.iter() {
-        levels_vec.push(get_features_from_class_level(level).await?);
-    }
+    // try_join_all keeps the order, so each level's features stay at that level
+    let levels_vec =
+        try_join_all(levels_arr.iter().map(|l| get_features_from_class_level(l))).await?;
 
     levels_vec.try_into().map_err(|v: Vec<_>| {
         Dnd5eapiError::mismatch(
@@ -364,17 +363,15 @@ async fn class_features(
 async fn get_features_from_class_level(
     level: &Value,
 ) -> Result<Vec<PresentedOption<Feature>>, Dnd5eapiError> {
-    let features_vals = level.get_array("features")?;
+    let feature_indexes = level
+        .get_array("features")?
+        .iter()
+        .map(|f| f.get_str("index"))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let mut features_vec = Vec::with_capacity(features_vals.len());
+    let features = try_join_all(feature_indexes.iter().map(|index| get_feature(index))).await?;
 
-    for f in features_vals {
-        let feature_index = f.get_str("index")?;
-        let feature = get_feature(&feature_index).await?;
-        features_vec.push(PresentedOption::Base(feature));
-    }
-
-    Ok(features_vec)
+    Ok(features.into_iter().map(PresentedOption::Base).collect())
 }
 
 fn spell_slots_from_map(json: &Value) -> Result<usize, Dnd5eapiError> {
@@ -403,6 +400,11 @@ fn spell_slots_from_map(json: &Value) -> Result<usize, Dnd5eapiError> {
     Ok(slot_vals[0])
 }
 
+// warlocks can only cast rituals with an invocation, so they aren't included here.
+fn ritual_casting(name: &str) -> bool {
+    matches!(name, "bard" | "cleric" | "druid" | "wizard")
+}
+
 fn preperation_type(name: &str) -> Option<SpellCastingPreperation> {
     use SpellCastingPreperation::{Known, Prepared};
     match name {
@@ -454,6 +456,16 @@ fn spell_slots(levels_arr: [&Value; 20]) -> Result<[usize; 20], Dnd5eapiError> {
     Ok(cantrip_slots)
 }
 
+// the number of spells known at each level, for classes that know their spells.
+fn spells_known(levels_arr: [&Value; 20]) -> Option<[usize; 20]> {
+    let known = levels_arr
+        .iter()
+        .map(|level| level.get("spellcasting")?.get("spells_known")?.as_u64())
+        .map(|v| v.map(|v| v as usize))
+        .collect::<Option<Vec<usize>>>()?;
+    known.try_into().ok()
+}
+
 fn spellcasting_ability(val: &Value) -> Result<Option<StatType>, Dnd5eapiError> {
     if val.get("spellcasting").is_none() {
         return Ok(None);
@@ -608,6 +620,7 @@ async fn process_spellcasting(
             let spell_list = process_spell_list(spells)?;
             // This just returns the cantrips, since spell slots are handled elsewhere
             let cantrips_per_level = spell_slots(levels_arr)?;
+            let spells_known_per_level = spells_known(levels_arr);
             let preperation_type = preperation_type(name.as_ref()).ok_or_else(|| {
                 Dnd5eapiError::mismatch(
                     "spellcaster preperation type",
@@ -622,6 +635,9 @@ async fn process_spellcasting(
                 spell_list,
                 spellcaster_type,
                 preperation_type,
+                spells_known_per_level,
+                prepares_from_spellbook: name == "wizard",
+                ritual_casting: ritual_casting(name.as_ref()),
             })
         })
         .transpose()
@@ -682,6 +698,26 @@ fn multiclassing_prerequisites(name: &str) -> (HashMap<StatType, usize>, bool) {
     (prerequisites_map, or_flag)
 }
 
+// the key abilities for each class, from most to least important.
+fn ability_priority(name: &str) -> Vec<StatType> {
+    use StatType::*;
+    match name {
+        "barbarian" => vec![Strength, Constitution, Dexterity],
+        "bard" => vec![Charisma, Dexterity, Constitution],
+        "cleric" => vec![Wisdom, Constitution, Strength],
+        "druid" => vec![Wisdom, Constitution, Dexterity],
+        "fighter" => vec![Strength, Constitution, Dexterity],
+        "monk" => vec![Dexterity, Wisdom, Constitution],
+        "paladin" => vec![Strength, Charisma, Constitution],
+        "ranger" => vec![Dexterity, Wisdom, Constitution],
+        "rogue" => vec![Dexterity, Constitution, Wisdom],
+        "sorcerer" => vec![Charisma, Constitution, Dexterity],
+        "warlock" => vec![Charisma, Constitution, Dexterity],
+        "wizard" => vec![Intelligence, Constitution, Dexterity],
+        _ => vec![],
+    }
+}
+
 fn multiclassing_proficiencies(json: &Value) -> Result<EquipmentProficiencies, Dnd5eapiError> {
     let multiclassing_map = json.get_map("multi_classing")?;
     let proficiency_strings = multiclassing_map
@@ -702,6 +738,7 @@ fn etc_class_field_option(name: &str) -> Option<TrackedField> {
             level_up: false,
             class_specific_max: Some("rage count".to_string()),
             hard_max: None,
+            class_specific_die: None,
         }),
         "druid" => Some(TrackedField {
             name: "Wildshape".to_string(),
@@ -710,6 +747,16 @@ fn etc_class_field_option(name: &str) -> Option<TrackedField> {
             level_up: false,
             class_specific_max: None,
             hard_max: Some(2),
+            class_specific_die: None,
+        }),
+        "fighter" => Some(TrackedField {
+            name: "Action Surge".to_string(),
+            long_rest: true,
+            short_rest: true,
+            level_up: false,
+            class_specific_max: Some("action surges".to_string()),
+            hard_max: None,
+            class_specific_die: None,
         }),
         "sorcerer" => Some(TrackedField {
             name: "Sorcery Points".to_string(),
@@ -718,14 +765,16 @@ fn etc_class_field_option(name: &str) -> Option<TrackedField> {
             level_up: false,
             class_specific_max: Some("sorcery points".to_string()),
             hard_max: None,
+            class_specific_die: None,
         }),
         "monk" => Some(TrackedField {
             name: "Ki Points".to_string(),
             long_rest: true,
-            short_rest: false,
+            short_rest: true,
             level_up: false,
             class_specific_max: Some("ki points".to_string()),
             hard_max: None,
+            class_specific_die: None,
         }),
         _ => None,
     }
@@ -786,6 +835,8 @@ async fn json_to_class(
         .map(|v| vec![v])
         .unwrap_or_default();
 
+    let ability_priority = ability_priority(&name);
+
     ClassBuilder::new()
         .name(name)
         .add_subclasses(subclasses)
@@ -801,6 +852,7 @@ async fn json_to_class(
         .set_multiclassing_prerequisites_or(multiclassing_prerequisites_or)
         .add_multiclassing_proficiency(multiclassing_proficiency_gain)
         .add_tracked_fields(etc_fields)
+        .set_ability_priority(ability_priority)
         .build()
         .map_err(|v| {
             Dnd5eapiError::mismatch(
@@ -3,8 +3,10 @@ use crate::{
     rules2014::{
         class::{Class, ItemCategory},
         features::PresentedOption,
-        stats::SkillType,
+        items::DamageType,
+        stats::{SkillType, Stats},
     },
+    Character,
 };
 use super::Dnd5eapiError;
 
@@ -73,7 +75,7 @@ async fn wizard_items(class: &Class) {
     let spellbook_item = get_item("spellbook").await.unwrap();
 
     let spellbook_choice_entry =
-        PresentedOption::Base(vec![(ItemCategory::Item(spellbook_item), 1)]);
+        PresentedOption::Base(vec![(ItemCategory::Item(Box::new(spellbook_item)), 1)]);
     assert_eq!(
         class.beginning_items().first().cloned(),
         Some(spellbook_choice_entry)
@@ -94,8 +96,8 @@ async fn wizard_items(class: &Class) {
         .await
         .expect("Couldn't get quarterstaff");
     let dagger = get_item("dagger").await.expect("Couldn't get dagger");
-    assert_eq!(first_choice[0], vec![(ItemCategory::Item(quarterstaff), 1)]);
-    assert_eq!(first_choice[1], vec![(ItemCategory::Item(dagger), 1)]);
+    assert_eq!(first_choice[0], vec![(ItemCategory::Item(Box::new(quarterstaff)), 1)]);
+    assert_eq!(first_choice[1], vec![(ItemCategory::Item(Box::new(dagger)), 1)]);
 }
 
 async fn wizard_features(class: &Class) {
@@ -218,6 +220,35 @@ async fn fighter_items() {
     assert_eq!(*n, 2);
 }
 
+#[tokio::test]
+async fn champion_has_no_superiority_dice() {
+    let provider = provider();
+    let fighter = provider
+        .get_class("fighter")
+        .await
+        .expect("failed to get fighter class from api");
+    let acolyte = provider
+        .get_background("acolyte")
+        .await
+        .expect("failed to get acolyte background from api");
+    let human = provider
+        .get_race("human")
+        .await
+        .expect("failed to get human race from api");
+
+    let mut john = Character::new_at_level(
+        "john".to_string(),
+        &fighter,
+        &acolyte,
+        &human,
+        Stats::default(),
+        3,
+    )
+    .unwrap();
+    assert!(john.choose_subclass_by_name(0, "champion"));
+    assert_eq!(john.use_maneuver(DamageType::Slashing), None);
+}
+
 async fn get_with_class_context(
     class_name: &str,
     provider: &Dnd5eapiGetter,
@@ -10,11 +10,18 @@ pub enum Dnd5eapiError {
     #[error("failed to parse: {0}")]
     Parse(#[from] serde_json::Error),
 
-    /// The api didn't have a required field
-    #[error("Value not found: expected {val_type} named {name}")]
+    /// The api didn't have a required field, or nothing with the requested name exists.
+    ///
+    /// When a name was looked up and not found, `suggestions` has the closest names that do
+    /// exist.
+    #[error(
+        "Value not found: expected {val_type} named {name}{}",
+        did_you_mean(suggestions)
+    )]
     NotFound {
         val_type: &'static str,
         name: String,
+        suggestions: Vec<String>,
     },
 
     /// The api returned a field of an unexpected type
@@ -30,10 +37,18 @@ impl Dnd5eapiError {
     /// Adds context by prefixing the `ValueMismatch` message.
     pub fn prepend(self, s: &str) -> Dnd5eapiError {
         match self {
-            Dnd5eapiError::NotFound { val_type, name } => {
+            Dnd5eapiError::NotFound {
+                val_type,
+                name,
+                suggestions,
+            } => {
                 let mut s = s.to_string();
                 s.push_str(&name);
-                Dnd5eapiError::NotFound { val_type, name: s }
+                Dnd5eapiError::NotFound {
+                    val_type,
+                    name: s,
+                    suggestions,
+                }
             }
             Dnd5eapiError::TypeMismatch {
                 field,
@@ -81,6 +96,22 @@ impl Dnd5eapiError {
         Dnd5eapiError::NotFound {
             val_type,
             name: name.to_string(),
+            suggestions: vec![],
+        }
+    }
+
+    /// Adds suggestions for names that do exist to a `NotFound`.
+    pub fn with_suggestions(mut self, new_suggestions: Vec<String>) -> Dnd5eapiError {
+        if let Dnd5eapiError::NotFound { suggestions, .. } = &mut self {
+            *suggestions = new_suggestions;
         }
+        self
+    }
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
     }
+    format!(" (did you mean {}?)", suggestions.join(", "))
 }
@@ -1,7 +1,10 @@
 use super::get_page::get_raw_json;
 use super::json_tools::{choice, parse_string, value_name, ValueExt};
+use super::spell::get_spell;
 use super::Dnd5eapiError;
 use crate::rules2014::features::{AbilityScoreIncrease, Feature, FeatureEffect, PresentedOption};
+use crate::rules2014::items::DamageType;
+use crate::rules2014::spells::RacialSpellUse;
 use crate::rules2014::stats::StatType;
 use regex::Regex;
 use serde_json::Value;
@@ -59,15 +62,43 @@ pub async fn get_feature_from_trait(
         .map(|v| v.as_string("description"))
         .collect::<Result<Vec<String>, Dnd5eapiError>>()?;
 
+    let mut effects = feature_effects(index_name);
+    effects.extend(racial_spell_effects(index_name).await?);
+
     let feature = Feature {
         name,
         description,
-        effects: feature_effects(index_name),
+        effects,
     };
 
     Ok(PresentedOption::Base(feature))
 }
 
+// traits that grant spells. These need to get the spells from the api, so they're seperate from
+// feature_effects.
+async fn racial_spell_effects(index_name: &str) -> Result<Vec<FeatureEffect>, Dnd5eapiError> {
+    let once = RacialSpellUse::OncePerLongRest { used: false };
+    let spells: &[(&str, StatType, RacialSpellUse, usize)] = match index_name {
+        "infernal-legacy" => &[
+            ("thaumaturgy", StatType::Charisma, RacialSpellUse::AtWill, 1),
+            ("hellish-rebuke", StatType::Charisma, once, 3),
+            ("darkness", StatType::Charisma, once, 5),
+        ],
+        _ => &[],
+    };
+
+    let mut effects = Vec::with_capacity(spells.len());
+    for (spell_name, ability, usage, level) in spells {
+        effects.push(FeatureEffect::RacialSpell {
+            spell: Box::new(get_spell(spell_name).await?),
+            ability: *ability,
+            usage: *usage,
+            level: *level,
+        });
+    }
+    Ok(effects)
+}
+
 async fn get_draconic_ancestry(
     json: Value,
 ) -> Result<PresentedOption<Feature>, Dnd5eapiError> {
@@ -147,6 +178,12 @@ fn feature_effects(index_name: &str) -> Vec<FeatureEffect> {
         ],
         "dwarven-toughness" => vec![FeatureEffect::LeveledHpIncrease],
         "unarmored-movement-1" => vec![FeatureEffect::UnarmoredMovement],
+        "jack-of-all-trades" => vec![FeatureEffect::JackOfAllTrades],
+        "remarkable-athlete" => vec![FeatureEffect::RemarkableAthlete],
+        "hellish-resistance" => vec![FeatureEffect::Resistance(DamageType::Fire)],
+        "dwarven-resilience" => vec![FeatureEffect::Resistance(DamageType::Poison)],
+        "darkvision" => vec![FeatureEffect::Darkvision(60)],
+        "superior-darkvision" => vec![FeatureEffect::Darkvision(120)],
         _ => vec![],
     }
 }
@@ -175,6 +212,7 @@ mod tests {
             PresentedOption::Choice(_) => panic!("Should just be one feature"),
         };
         assert_eq!(feature.name, "Darkvision");
+        assert_eq!(feature.effects, vec![FeatureEffect::Darkvision(60)]);
         assert_eq!(feature.description[0], "You have superior vision in dark and dim conditions. You can see in dim light within 60 feet of you as if it were bright light, and in darkness as if it were dim light. You cannot discern color in darkness, only shades of gray.");
     }
 
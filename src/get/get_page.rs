@@ -1,14 +1,84 @@
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
 use serde_json::Value;
 
-use super::Dnd5eapiError;
+use super::{Config, Dnd5eapiError};
+
+// The first retry waits this long, and each one after that waits twice as long as the last.
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The client and settings used for requests to the api.
+#[derive(Debug)]
+pub(super) struct Connection {
+    client: reqwest::Client,
+    max_retries: u32,
+    base_url: String,
+}
+
+impl Connection {
+    pub(super) fn new(config: Config) -> Connection {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .expect("failed to build http client");
+        let mut base_url = config.base_url;
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        Connection {
+            client,
+            max_retries: config.max_retries,
+            base_url,
+        }
+    }
+
+    /// Runs a future with this connection used for every request made inside it.
+    pub(super) async fn scope<F: Future>(self: &Arc<Self>, f: F) -> F::Output {
+        CONNECTION.scope(self.clone(), f).await
+    }
+}
+
+tokio::task_local! {
+    // The connection of the getter currently making requests.
+    static CONNECTION: Arc<Connection>;
+}
+
+// Requests made outside of a getter, like from the raw getters, use the default config.
+fn current_connection() -> Arc<Connection> {
+    static DEFAULT: OnceLock<Arc<Connection>> = OnceLock::new();
+    CONNECTION.try_with(Arc::clone).unwrap_or_else(|_| {
+        DEFAULT
+            .get_or_init(|| Arc::new(Connection::new(Config::default())))
+            .clone()
+    })
+}
 
 pub async fn get_page(path: String) -> Result<reqwest::Response, reqwest::Error> {
-    let total_path = format!("https://www.dnd5eapi.co/api/2014/{path}");
-    let response = reqwest::get(total_path).await?;
+    let connection = current_connection();
+    let total_path = format!("{}{path}", connection.base_url);
+    let response = connection.client.get(total_path).send().await?;
     Ok(response)
 }
 
 pub async fn get_raw_json(path: String) -> Result<serde_json::Value, Dnd5eapiError> {
+    let max_retries = current_connection().max_retries;
+    let mut backoff = RETRY_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        match fetch_json(path.clone()).await {
+            Err(Dnd5eapiError::Network(_)) if attempt < max_retries => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn fetch_json(path: String) -> Result<serde_json::Value, Dnd5eapiError> {
     let json = get_page(path).await?.json::<Value>().await?;
     Ok(json)
 }
@@ -31,4 +101,21 @@ mod tests {
             Value::String("/api/2014/classes/wizard".to_string())
         );
     }
+
+    #[tokio::test]
+    async fn retries_then_fails() {
+        // nothing listens on this port, so every attempt fails to connect.
+        let connection = Arc::new(Connection::new(Config {
+            timeout: Duration::from_secs(1),
+            max_retries: 2,
+            base_url: "http://127.0.0.1:9".to_string(),
+        }));
+        let start = std::time::Instant::now();
+        let result = connection
+            .scope(get_raw_json("classes/wizard".to_string()))
+            .await;
+        assert!(matches!(result, Err(Dnd5eapiError::Network(_))));
+        // two retries wait 500ms and then 1s
+        assert!(start.elapsed() >= Duration::from_millis(1500));
+    }
 }
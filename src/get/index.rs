@@ -0,0 +1,157 @@
+use super::get_page::get_raw_json;
+use super::json_tools::ValueExt;
+use super::Dnd5eapiError;
+
+/// The api's list of everything of one kind, e.g. every class. Used to find the index (url slug)
+/// for a name, since names like "Half-Elf" don't always turn into their index cleanly.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct ApiIndex {
+    // (name, index) pairs, like ("Half-Elf", "half-elf")
+    entries: Vec<(String, String)>,
+}
+
+impl ApiIndex {
+    /// Gets the index for an endpoint, like "classes" or "equipment".
+    pub(super) async fn fetch(endpoint: &str) -> Result<ApiIndex, Dnd5eapiError> {
+        let json = get_raw_json(endpoint.to_string()).await?;
+        let entries = json
+            .get_array("results")?
+            .iter()
+            .map(|v| Ok((v.get_str("name")?, v.get_str("index")?)))
+            .collect::<Result<Vec<_>, Dnd5eapiError>>()?;
+        Ok(ApiIndex { entries })
+    }
+
+    /// Finds the index for a name.
+    ///
+    /// Names are compared without casing, spaces, or punctuation, so "half elf" and "Half-Elf"
+    /// both match. If nothing matches exactly, a name that's the start of only one entry (like
+    /// "tief" for tiefling) matches that entry.
+    pub(super) fn resolve(&self, name: &str) -> Option<&str> {
+        let target = normalize(name);
+        if target.is_empty() {
+            return None;
+        }
+
+        let exact = self
+            .entries
+            .iter()
+            .find(|(n, index)| normalize(n) == target || normalize(index) == target);
+        if let Some((_, index)) = exact {
+            return Some(index);
+        }
+
+        let mut prefixed = self
+            .entries
+            .iter()
+            .filter(|(n, _)| normalize(n).starts_with(&target));
+        match (prefixed.next(), prefixed.next()) {
+            (Some((_, index)), None) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// The names of entries close to the given name, for when it doesn't resolve to one.
+    ///
+    /// These are entries that contain the name, along with entries within a few typos of it
+    /// (by Levenshtein distance), closest first.
+    pub(super) fn suggestions(&self, name: &str) -> Vec<&str> {
+        let target = normalize(name);
+        let max_distance = (target.len() / 3).max(1);
+
+        let mut close: Vec<(usize, &str)> = self
+            .entries
+            .iter()
+            .filter_map(|(n, _)| {
+                let normalized = normalize(n);
+                let distance = levenshtein(&normalized, &target);
+                let contains = !target.is_empty() && normalized.contains(&target);
+                (contains || distance <= max_distance).then_some((distance, n.as_str()))
+            })
+            .collect();
+        close.sort_by_key(|(distance, _)| *distance);
+        close.into_iter().take(5).map(|(_, n)| n).collect()
+    }
+}
+
+// the number of single character insertions, deletions, or substitutions to turn a into b.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// lowercase, without any spaces or punctuation
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiIndex;
+    use crate::get::Dnd5eapiError;
+
+    fn races() -> ApiIndex {
+        let entries = [
+            ("Elf", "elf"),
+            ("Half-Elf", "half-elf"),
+            ("Half-Orc", "half-orc"),
+            ("Tiefling", "tiefling"),
+        ];
+        ApiIndex {
+            entries: entries
+                .into_iter()
+                .map(|(n, i)| (n.to_string(), i.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn resolve() {
+        let races = races();
+        assert_eq!(races.resolve("Half-Elf"), Some("half-elf"));
+        assert_eq!(races.resolve("half elf"), Some("half-elf"));
+        assert_eq!(races.resolve("HALF_ORC"), Some("half-orc"));
+        assert_eq!(races.resolve("elf"), Some("elf"));
+        assert_eq!(races.resolve("tief"), Some("tiefling"));
+        // both half-elf and half-orc start with "half"
+        assert_eq!(races.resolve("half"), None);
+        assert_eq!(races.resolve("gnome"), None);
+        assert_eq!(races.resolve(""), None);
+
+        assert_eq!(races.suggestions("half"), vec!["Half-Elf", "Half-Orc"]);
+    }
+
+    #[test]
+    fn suggestions() {
+        let classes = ApiIndex {
+            entries: vec![
+                ("Fighter".to_string(), "fighter".to_string()),
+                ("Monk".to_string(), "monk".to_string()),
+                ("Wizard".to_string(), "wizard".to_string()),
+            ],
+        };
+        assert_eq!(classes.resolve("figter"), None);
+        assert_eq!(classes.suggestions("figter"), vec!["Fighter"]);
+        assert_eq!(classes.suggestions("wizzard"), vec!["Wizard"]);
+        assert!(classes.suggestions("paladin").is_empty());
+
+        let err = Dnd5eapiError::not_found("class", "figter")
+            .with_suggestions(vec!["Fighter".to_string()]);
+        assert_eq!(
+            err.to_string(),
+            "Value not found: expected class named figter (did you mean Fighter?)"
+        );
+    }
+}
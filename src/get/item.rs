@@ -19,12 +19,19 @@ async fn get_item_raw(index_name: String) -> Result<Item, Dnd5eapiError> {
 
     let name = item_json.get_str("name")?;
     let catagory = item_json.get_map("equipment_category")?.get_str("index")?;
+    // not every item lists a weight, like some adventuring gear
+    let weight = item_json
+        .get("weight")
+        .and_then(Value::as_f64)
+        .map(|w| w as f32);
 
     if name == "Shield" {
         return Ok(Item {
             name: "Shield".to_string(),
             description: None,
             item_type: ItemType::Shield,
+            weight,
+            attunement: None,
             features: vec![],
         });
     }
@@ -39,6 +46,8 @@ async fn get_item_raw(index_name: String) -> Result<Item, Dnd5eapiError> {
         name,
         description: None,
         item_type,
+        weight,
+        attunement: None,
         features: vec![],
     };
 
@@ -90,11 +99,22 @@ fn weapon(map: &Value) -> Result<Weapon, Dnd5eapiError> {
         attack_roll_bonus: 0,
         properties,
         weapon_type,
+        range: range(map, "range"),
+        thrown_range: range(map, "throw_range"),
     };
 
     Ok(weapon)
 }
 
+// ranges are listed as {"normal": 80, "long": 320}. Melee weapons list a range too, but without a
+// long range, so those are skipped.
+fn range(map: &Value, key: &str) -> Option<(u32, u32)> {
+    let range = map.get_map(key).ok()?;
+    let normal = range.get_usize("normal").ok()?;
+    let long = range.get_usize("long").ok()?;
+    Some((normal as u32, long as u32))
+}
+
 fn properties(
     map: &Value,
     damage_type: DamageType,
@@ -191,6 +211,18 @@ mod tests {
         assert_eq!(weapon.weapon_type, WeaponType::Martial);
     }
 
+    #[tokio::test]
+    async fn shortbow_retrieval() {
+        let v = get_item("shortbow").await.expect("Failed to get shortbow");
+        let weapon = match v.item_type {
+            ItemType::Weapon(w) => w,
+            _ => panic!("Shortbow should be a weapon!"),
+        };
+
+        assert_eq!(weapon.range, Some((80, 320)));
+        assert_eq!(weapon.thrown_range, None);
+    }
+
     #[tokio::test]
     async fn studded_leather_retrieval() {
         let v = get_item("studded leather armor")
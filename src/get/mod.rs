@@ -9,8 +9,10 @@ mod background;
 mod class;
 mod feature;
 mod get_page;
+mod index;
 mod item;
 mod json_tools;
+mod monster;
 mod race;
 mod spell;
 mod subclass;
@@ -30,6 +32,7 @@ pub mod raw_getters {
     pub use super::class::get_class as get_class_raw;
     pub use super::feature::get_feature as get_feature_raw;
     pub use super::item::get_item as get_item_raw;
+    pub use super::monster::get_monster as get_monster_raw;
     pub use super::race::get_race as get_race_raw;
     pub use super::spell::get_spell as get_spell_raw;
 }
@@ -41,19 +44,24 @@ mod datastore_tests;
 
 use async_trait::async_trait;
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use background::get_background as get_background_inner;
 use class::get_class as get_class_inner;
 use feature::get_feature as get_feature_inner;
+use get_page::Connection;
+use index::ApiIndex;
 use item::get_item as get_item_inner;
+use monster::get_monster as get_monster_inner;
 use race::get_race as get_race_inner;
 use spell::get_spell as get_spell_inner;
 
 use crate::{
     prelude::Race,
     rules2014::{
-        background::Background, class::Class, features::Feature, items::Item, spells::Spell,
+        background::Background, class::Class, features::Feature, items::Item,
+        monster::StatBlock, spells::Spell,
     },
 };
 
@@ -67,6 +75,39 @@ pub use item_list::ITEM_NAMES;
 mod error;
 pub use error::Dnd5eapiError;
 
+/// Settings for how a [Dnd5eapiGetter] connects to the api.
+///
+/// ```
+/// use std::time::Duration;
+/// use dnd_lib::get::{Config, Dnd5eapiGetter};
+///
+/// let getter = Dnd5eapiGetter::with_config(Config {
+///     timeout: Duration::from_secs(60),
+///     max_retries: 5,
+///     ..Config::default()
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// How long a single request can take before it fails.
+    pub timeout: Duration,
+    /// How many times a request that fails to connect is tried again. Each retry waits twice as
+    /// long as the last, starting at half a second.
+    pub max_retries: u32,
+    /// The url the api is at, which can be changed to use a mirror of dnd5eapi.co.
+    pub base_url: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            base_url: "https://www.dnd5eapi.co/api/2014/".to_string(),
+        }
+    }
+}
+
 /// Gets D&D data from dnd5eapi.co
 ///
 /// The following are availible from this api:
@@ -95,18 +136,38 @@ pub use error::Dnd5eapiError;
 /// }
 /// ```
 /// Do note that this getter can be quite slow, as it needs to make multiple network requests to
-/// get all the data.
-/// Caching is implemented for items, classes, and backgrounds to help with this.
+/// get all the data. Requests that fail to connect are retried, and the timeout and number of
+/// retries can be set with [Dnd5eapiGetter::with_config].
+/// Everything it gets is cached to help with this, and [Dnd5eapiGetter::clear_cache] frees that
+/// memory again.
+///
+/// Names are looked up in the api's index of each kind of data, which is fetched once and then
+/// cached. This means names don't need to match the api's urls exactly: "Half-Elf", "half elf",
+/// and "half-elf" all find the same race. If a name isn't found, the error lists any close
+/// matches.
 pub struct Dnd5eapiGetter {
     item_cache: Mutex<HashMap<String, Item>>,
     class_cache: Mutex<HashMap<String, Class>>,
     background_cache: Mutex<HashMap<String, Background>>,
+    spell_cache: Mutex<HashMap<String, Spell>>,
+    race_cache: Mutex<HashMap<String, Race>>,
+    index_cache: Mutex<HashMap<&'static str, ApiIndex>>,
+    connection: Arc<Connection>,
 }
 
 #[async_trait]
 impl crate::getter::DataProvider<Dnd5eapiError> for Dnd5eapiGetter {
     async fn get_race(&self, name: &str) -> Result<Race, Dnd5eapiError> {
-        get_race_inner(name).await
+        if let Some(cached) = self.race_cache.lock().unwrap().get(name) {
+            return Ok(cached.clone());
+        }
+        let index = self.resolve_index("races", "race", name).await?;
+        let race = self.connection.scope(get_race_inner(&index)).await?;
+        self.race_cache
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), race.clone());
+        Ok(race)
     }
     async fn get_background(
         &self,
@@ -115,7 +176,11 @@ impl crate::getter::DataProvider<Dnd5eapiError> for Dnd5eapiGetter {
         if let Some(cached) = self.background_cache.lock().unwrap().get(name) {
             return Ok(cached.clone());
         }
-        let background = get_background_inner(self, name).await?;
+        let index = self.resolve_index("backgrounds", "background", name).await?;
+        let background = self
+            .connection
+            .scope(get_background_inner(self, &index))
+            .await?;
         self.background_cache
             .lock()
             .unwrap()
@@ -126,7 +191,8 @@ impl crate::getter::DataProvider<Dnd5eapiError> for Dnd5eapiGetter {
         if let Some(cached) = self.class_cache.lock().unwrap().get(name) {
             return Ok(cached.clone());
         }
-        let class = get_class_inner(self, name).await?;
+        let index = self.resolve_index("classes", "class", name).await?;
+        let class = self.connection.scope(get_class_inner(self, &index)).await?;
         self.class_cache
             .lock()
             .unwrap()
@@ -137,7 +203,8 @@ impl crate::getter::DataProvider<Dnd5eapiError> for Dnd5eapiGetter {
         if let Some(cached) = self.item_cache.lock().unwrap().get(name) {
             return Ok(cached.clone());
         }
-        let mut item = get_item_inner(name).await?;
+        let index = self.resolve_index("equipment", "item", name).await?;
+        let mut item = self.connection.scope(get_item_inner(&index)).await?;
         capitalize(&mut item.name);
         self.item_cache
             .lock()
@@ -146,33 +213,91 @@ impl crate::getter::DataProvider<Dnd5eapiError> for Dnd5eapiGetter {
         Ok(item)
     }
     async fn get_spell(&self, name: &str) -> Result<Spell, Dnd5eapiError> {
-        let mut s = get_spell_inner(name).await?;
+        if let Some(cached) = self.spell_cache.lock().unwrap().get(name) {
+            return Ok(cached.clone());
+        }
+        let index = self.resolve_index("spells", "spell", name).await?;
+        let mut s = self.connection.scope(get_spell_inner(&index)).await?;
         capitalize(&mut s.name);
+        self.spell_cache
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), s.clone());
         Ok(s)
     }
 }
 
 impl Dnd5eapiGetter {
+    /// Creates a getter with the default [Config].
     pub fn new() -> Dnd5eapiGetter {
+        Dnd5eapiGetter::with_config(Config::default())
+    }
+
+    /// Creates a getter that connects to the api with the given settings.
+    ///
+    /// # Panics
+    /// Panics if the http client can't be created, like [reqwest::Client::new].
+    pub fn with_config(config: Config) -> Dnd5eapiGetter {
         Dnd5eapiGetter {
             item_cache: Mutex::new(HashMap::new()),
             class_cache: Mutex::new(HashMap::new()),
             background_cache: Mutex::new(HashMap::new()),
+            spell_cache: Mutex::new(HashMap::new()),
+            race_cache: Mutex::new(HashMap::new()),
+            index_cache: Mutex::new(HashMap::new()),
+            connection: Arc::new(Connection::new(config)),
         }
     }
 
+    /// Forgets everything that's been cached, including the api's lists of names. Everything will
+    /// be fetched again the next time it's requested.
+    pub fn clear_cache(&self) {
+        self.item_cache.lock().unwrap().clear();
+        self.class_cache.lock().unwrap().clear();
+        self.background_cache.lock().unwrap().clear();
+        self.spell_cache.lock().unwrap().clear();
+        self.race_cache.lock().unwrap().clear();
+        self.index_cache.lock().unwrap().clear();
+    }
+
     pub async fn get_feature(&self, name: &str) -> Result<Feature, Dnd5eapiError> {
-        get_feature_inner(name).await
+        self.connection.scope(get_feature_inner(name)).await
+    }
+
+    /// Gets a monster's [StatBlock], e.g. "goblin" or "adult red dragon".
+    pub async fn get_monster(&self, name: &str) -> Result<StatBlock, Dnd5eapiError> {
+        let index = self.resolve_index("monsters", "monster", name).await?;
+        self.connection.scope(get_monster_inner(&index)).await
+    }
+
+    // Finds the api's index for a name, fetching the endpoint's list of indexes the first time.
+    async fn resolve_index(
+        &self,
+        endpoint: &'static str,
+        val_type: &'static str,
+        name: &str,
+    ) -> Result<String, Dnd5eapiError> {
+        if let Some(index) = self.index_cache.lock().unwrap().get(endpoint) {
+            return resolved(index, val_type, name);
+        }
+        let index = self.connection.scope(ApiIndex::fetch(endpoint)).await?;
+        let result = resolved(&index, val_type, name);
+        self.index_cache.lock().unwrap().insert(endpoint, index);
+        result
     }
 }
 
+fn resolved(index: &ApiIndex, val_type: &'static str, name: &str) -> Result<String, Dnd5eapiError> {
+    if let Some(resolved) = index.resolve(name) {
+        return Ok(resolved.to_string());
+    }
+    let suggestions = index.suggestions(name).into_iter().map(String::from).collect();
+    Err(Dnd5eapiError::not_found(val_type, name).with_suggestions(suggestions))
+}
+
 impl Default for Dnd5eapiGetter {
     fn default() -> Self {
-        Dnd5eapiGetter {
-            item_cache: Mutex::new(HashMap::new()),
-            class_cache: Mutex::new(HashMap::new()),
-            background_cache: Mutex::new(HashMap::new()),
-        }
+        Dnd5eapiGetter::new()
     }
 }
 
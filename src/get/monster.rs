@@ -0,0 +1,167 @@
+use super::get_page::get_raw_json;
+use super::json_tools::{parse_string, ValueExt};
+use super::race::process_size;
+use super::Dnd5eapiError;
+use crate::rules2014::items::{DamageDefenses, DamageRoll, DamageType, WeaponAction};
+use crate::rules2014::monster::StatBlock;
+use crate::rules2014::stats::{SkillType, Speeds, StatType, Stats};
+use serde_json::Value;
+
+pub async fn get_monster(name: &str) -> Result<StatBlock, Dnd5eapiError> {
+    let index = parse_string(name);
+    let json = get_raw_json(format!("monsters/{index}")).await?;
+
+    let name = json.get_str("name")?;
+    let size_name = json.get_str("size")?;
+    let size = process_size(&size_name)
+        .ok_or_else(|| Dnd5eapiError::mismatch("monster size", "Valid size", &size_name))?;
+
+    let stats = Stats {
+        strength: json.get_usize("strength")? as isize,
+        dexterity: json.get_usize("dexterity")? as isize,
+        constitution: json.get_usize("constitution")? as isize,
+        intelligence: json.get_usize("intelligence")? as isize,
+        wisdom: json.get_usize("wisdom")? as isize,
+        charisma: json.get_usize("charisma")? as isize,
+    };
+
+    // a monster can list multiple ac values (e.g. with and without a spell). we use the first.
+    let ac = json
+        .get_array("armor_class")?
+        .first()
+        .ok_or_else(|| Dnd5eapiError::not_found("Map", "armor_class"))?
+        .get_usize("value")? as isize;
+    let max_hp = json.get_usize("hit_points")?;
+    let speeds = monster_speeds(json.get_map("speed")?);
+
+    let challenge_rating = json
+        .get("challenge_rating")
+        .and_then(|v| v.as_f64())
+        .ok_or_else(|| Dnd5eapiError::not_found("Number", "challenge_rating"))?;
+
+    let mut saving_throws = vec![];
+    let mut skills = vec![];
+    for proficiency in json.get_array("proficiencies")? {
+        let bonus = proficiency
+            .get("value")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| Dnd5eapiError::not_found("Number", "proficiency value"))?;
+        let index = proficiency.get_map("proficiency")?.get_str("index")?;
+        if let Some(stat) = index.strip_prefix("saving-throw-") {
+            if let Some(stat) = StatType::from_shorthand(stat) {
+                saving_throws.push((stat, bonus as isize));
+            }
+        } else if let Some(skill) = index.strip_prefix("skill-") {
+            if let Some(skill) = SkillType::from_name(&skill.replace('-', " ")) {
+                skills.push((skill, bonus as isize));
+            }
+        }
+    }
+
+    let defenses = DamageDefenses {
+        resistances: damage_types(json.get_array("damage_resistances")?),
+        immunities: damage_types(json.get_array("damage_immunities")?),
+        vulnerabilities: damage_types(json.get_array("damage_vulnerabilities")?),
+    };
+
+    let attacks = json
+        .get_array("actions")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(monster_attack)
+        .collect();
+
+    Ok(StatBlock {
+        name,
+        size,
+        stats,
+        ac,
+        max_hp,
+        hp: max_hp,
+        temp_hp: 0,
+        speeds,
+        attacks,
+        saving_throws,
+        skills,
+        challenge_rating: challenge_rating as f32,
+        defenses,
+    })
+}
+
+// speeds are listed as strings, like "30 ft.", except for hovering which is a bool.
+fn monster_speeds(json: &Value) -> Speeds {
+    let speed = |key: &str| {
+        json.get(key)
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.split_whitespace().next())
+            .and_then(|v| v.parse().ok())
+    };
+    let flying = speed("fly");
+    let hovers = json.get("hover").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    Speeds {
+        walking: speed("walk"),
+        flying,
+        hovering: if hovers { flying } else { None },
+        burrowing: speed("burrow"),
+        climbing: speed("climb"),
+        swimming: speed("swim"),
+    }
+}
+
+// only plain damage types are kept. Conditional ones like "bludgeoning, piercing, and slashing
+// from nonmagical attacks" can't be represented, and are skipped.
+fn damage_types(arr: &[Value]) -> Vec<DamageType> {
+    arr.iter()
+        .filter_map(|v| v.as_str())
+        .filter_map(|v| v.parse().ok())
+        .collect()
+}
+
+// actions without an attack bonus (like multiattack or a breath weapon) aren't attacks, and only
+// the first damage roll of an attack is kept.
+fn monster_attack(json: &Value) -> Option<WeaponAction> {
+    let name = json.get_str("name").ok()?;
+    let attack_bonus = json.get("attack_bonus")?.as_i64()? as isize;
+    let damage_roll = json.get_array("damage").ok()?.iter().find_map(|damage| {
+        let damage_type: DamageType = damage
+            .get_map("damage_type")
+            .ok()?
+            .get_str("name")
+            .ok()?
+            .parse()
+            .ok()?;
+        let dice = damage.get_str("damage_dice").ok()?;
+        DamageRoll::from_str(&dice, damage_type)
+    })?;
+
+    Some(WeaponAction {
+        name,
+        attack_bonus,
+        damage_roll,
+        two_handed: false,
+        second_attack: false,
+        // the api only describes an attack's reach or range in its description
+        range: None,
+        reach: Some(5),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_monster;
+    use crate::rules2014::stats::{Size, SkillType};
+
+    #[tokio::test]
+    async fn goblin_retrieval() {
+        let goblin = get_monster("goblin").await.expect("failed to get goblin");
+        assert_eq!(goblin.name, "Goblin");
+        assert_eq!(goblin.size, Size::Small);
+        assert_eq!(goblin.ac, 15);
+        assert_eq!(goblin.max_hp, 7);
+        assert_eq!(goblin.speeds.walking, Some(30));
+        assert_eq!(goblin.skill_mod(SkillType::Stealth), 6);
+        assert_eq!(goblin.attacks[0].name, "Scimitar");
+        assert_eq!(goblin.attacks[0].attack_bonus, 4);
+    }
+}
@@ -104,7 +104,7 @@ async fn process_subraces(arr: &[Value]) -> Result<Vec<Subrace>, Dnd5eapiError>
     Ok(subraces)
 }
 
-fn process_size(s: &str) -> Option<Size> {
+pub(super) fn process_size(s: &str) -> Option<Size> {
     match s {
         "Tiny" => Some(Size::Tiny),
         "Small" => Some(Size::Small),
@@ -3,7 +3,8 @@ use super::json_tools::{string_array, value_name, ValueExt};
 use crate::get::json_tools::parse_string;
 use super::Dnd5eapiError;
 use crate::rules2014::items::{DamageRoll, DamageType};
-use crate::rules2014::spells::Spell;
+use crate::rules2014::spells::{Spell, SpellMechanic};
+use crate::rules2014::stats::StatType;
 use serde_json::Value;
 
 type StandardDamage = Vec<Vec<DamageRoll>>;
@@ -41,6 +42,7 @@ pub async fn get_spell(name: &str) -> Result<Spell, Dnd5eapiError> {
     let (damage, leveled_damage) =
         spell_damage(json.get_map("damage").ok()).unwrap_or((None, None));
     let duration = json.get_str("duration")?;
+    let mechanic = spell_mechanic(&json)?;
 
     Ok(Spell {
         name,
@@ -57,9 +59,30 @@ pub async fn get_spell(name: &str) -> Result<Spell, Dnd5eapiError> {
         material,
         damage,
         leveled_damage,
+        mechanic,
     })
 }
 
+fn spell_mechanic(json: &Value) -> Result<SpellMechanic, Dnd5eapiError> {
+    if let Ok(dc) = json.get_map("dc") {
+        let stat_name = dc.get_map("dc_type")?.get_str("index")?;
+        let stat = StatType::from_shorthand(&stat_name).ok_or_else(|| {
+            Dnd5eapiError::mismatch("spell dc type", "stat shorthand", &stat_name)
+        })?;
+        let half_on_success = dc.get_str("dc_success").is_ok_and(|v| v == "half");
+        return Ok(SpellMechanic::Save {
+            stat,
+            half_on_success,
+        });
+    }
+
+    if json.get_str("attack_type").is_ok() {
+        return Ok(SpellMechanic::Attack);
+    }
+
+    Ok(SpellMechanic::Automatic)
+}
+
 fn spell_damage(
     v: Option<&Value>,
 ) -> Result<(Option<StandardDamage>, Option<LeveledDamage>), Dnd5eapiError> {
@@ -85,7 +108,7 @@ fn spell_damage(
             .transpose()?
             // sorts the damage by level
             .map(|mut v| {
-                v.sort_by(|a, b| a.0.cmp(&b.0));
+                v.sort_by_key(|a| a.0);
                 v
             });
 
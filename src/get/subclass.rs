@@ -4,6 +4,8 @@ use super::{
 };
 use crate::get::json_tools::value_name;
 use super::Dnd5eapiError;
+use super::feature::get_feature;
+use futures::future::try_join_all;
 use crate::rules2014::features::Feature;
 use crate::rules2014::{class::Subclass, features::PresentedOption};
 
@@ -12,8 +14,6 @@ pub async fn get_subclass(name: &str) -> Result<Subclass, Dnd5eapiError> {
     let json = get_raw_json(format!("subclasses/{index}")).await?;
     let levels = get_raw_json(format!("subclasses/{index}/levels")).await?;
 
-    let api_getter = super::Dnd5eapiGetter::new();
-
     let name = json.get_str("name")?;
     let description = string_array(json.get_array("desc")?)?;
 
@@ -25,16 +25,18 @@ pub async fn get_subclass(name: &str) -> Result<Subclass, Dnd5eapiError> {
 
     for level_object in levels_arr.iter() {
         let level_number = level_object.get_usize("level")?;
-        let features_arr = level_object.get_array("features")?;
-
-        let mut features_vec = Vec::with_capacity(features_arr.len());
-        for feature_obj in features_arr {
-            let index = feature_obj.get_str("index")?;
-            let feature = api_getter.get_feature(&index).await?;
-            features_vec.push(PresentedOption::Base(feature));
-        }
+        let feature_indexes = level_object
+            .get_array("features")?
+            .iter()
+            .map(|f| f.get_str("index"))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        features[level_number - 1] = features_vec;
+        let features_vec =
+            try_join_all(feature_indexes.iter().map(|index| get_feature(index))).await?;
+        features[level_number - 1] = features_vec
+            .into_iter()
+            .map(PresentedOption::Base)
+            .collect();
     }
 
     Ok(Subclass {
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use super::DataProvider;
+use crate::rules2014::{background::Background, class::Class, items::Item, spells::Spell, Race};
+
+/// Wraps another [DataProvider], remembering everything it successfully gets.
+///
+/// The first request for a name goes to the inner provider, and every request for that same
+/// name after that is answered from memory. Errors aren't cached, so a failed request is tried
+/// again next time.
+///
+/// ```
+/// use dnd_lib::{CachingProvider, DataProvider};
+/// use dnd_lib::local::LocalDataProvider;
+///
+/// let provider = CachingProvider::new(LocalDataProvider::new("./dnd_data"));
+/// assert_eq!(provider.inner().root(), std::path::Path::new("./dnd_data"));
+/// ```
+pub struct CachingProvider<P> {
+    inner: P,
+    races: Mutex<HashMap<String, Race>>,
+    backgrounds: Mutex<HashMap<String, Background>>,
+    items: Mutex<HashMap<String, Item>>,
+    classes: Mutex<HashMap<String, Class>>,
+    spells: Mutex<HashMap<String, Spell>>,
+}
+
+impl<P> CachingProvider<P> {
+    pub fn new(inner: P) -> CachingProvider<P> {
+        CachingProvider {
+            inner,
+            races: Mutex::new(HashMap::new()),
+            backgrounds: Mutex::new(HashMap::new()),
+            items: Mutex::new(HashMap::new()),
+            classes: Mutex::new(HashMap::new()),
+            spells: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The provider that cache misses are sent to.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Forgets everything that's been cached.
+    pub fn clear_cache(&self) {
+        self.races.lock().unwrap().clear();
+        self.backgrounds.lock().unwrap().clear();
+        self.items.lock().unwrap().clear();
+        self.classes.lock().unwrap().clear();
+        self.spells.lock().unwrap().clear();
+    }
+}
+
+// gets a value out of the cache, or from `fetch` if it isn't there yet.
+async fn cached<T, E, F>(cache: &Mutex<HashMap<String, T>>, name: &str, fetch: F) -> Result<T, E>
+where
+    T: Clone,
+    F: std::future::Future<Output = Result<T, E>>,
+{
+    if let Some(value) = cache.lock().unwrap().get(name) {
+        return Ok(value.clone());
+    }
+    let value = fetch.await?;
+    cache
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), value.clone());
+    Ok(value)
+}
+
+#[async_trait]
+impl<P, E> DataProvider<E> for CachingProvider<P>
+where
+    P: DataProvider<E>,
+    E: Error + Send,
+{
+    async fn get_race(&self, name: &str) -> Result<Race, E> {
+        cached(&self.races, name, self.inner.get_race(name)).await
+    }
+    async fn get_background(&self, name: &str) -> Result<Background, E> {
+        cached(&self.backgrounds, name, self.inner.get_background(name)).await
+    }
+    async fn get_item(&self, name: &str) -> Result<Item, E> {
+        cached(&self.items, name, self.inner.get_item(name)).await
+    }
+    async fn get_class(&self, name: &str) -> Result<Class, E> {
+        cached(&self.classes, name, self.inner.get_class(name)).await
+    }
+    async fn get_spell(&self, name: &str) -> Result<Spell, E> {
+        cached(&self.spells, name, self.inner.get_spell(name)).await
+    }
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+use super::DataProvider;
+use crate::rules2014::{background::Background, class::Class, items::Item, spells::Spell, Race};
+
+/// An error that can tell whether it was caused by the requested data not existing.
+///
+/// This is what lets a [FallbackProvider] know when to move on to its second provider.
+pub trait LookupError: Error {
+    /// Whether the requested name wasn't found, as opposed to something going wrong while
+    /// looking for it.
+    fn is_not_found(&self) -> bool;
+}
+
+impl LookupError for crate::local::LocalDataError {
+    fn is_not_found(&self) -> bool {
+        matches!(self, crate::local::LocalDataError::NotFound { .. })
+    }
+}
+
+#[cfg(feature = "dnd5eapi")]
+impl LookupError for crate::get::Dnd5eapiError {
+    fn is_not_found(&self) -> bool {
+        matches!(self, crate::get::Dnd5eapiError::NotFound { .. })
+    }
+}
+
+/// An error from a [FallbackProvider].
+#[derive(Debug, Error)]
+pub enum FallbackError<A: Error, B: Error> {
+    /// The first provider failed for a reason other than the data not existing, so the second
+    /// one wasn't tried.
+    #[error(transparent)]
+    Primary(A),
+    /// The first provider didn't have the data, and the second one failed too.
+    #[error(transparent)]
+    Fallback(B),
+}
+
+/// Gets data from one provider, and if it isn't there, from another.
+///
+/// This is useful for chaining a local source in front of a network one, so only what's missing
+/// locally is fetched. Only a "not found" error from the first provider moves on to the second;
+/// any other error is returned as is.
+///
+/// ```
+/// use dnd_lib::{CachingProvider, FallbackProvider};
+/// use dnd_lib::local::LocalDataProvider;
+///
+/// let provider = FallbackProvider::new(
+///     LocalDataProvider::new("./homebrew"),
+///     CachingProvider::new(LocalDataProvider::new("./dnd_data")),
+/// );
+/// ```
+pub struct FallbackProvider<A, B> {
+    primary: A,
+    fallback: B,
+}
+
+impl<A, B> FallbackProvider<A, B> {
+    pub fn new(primary: A, fallback: B) -> FallbackProvider<A, B> {
+        FallbackProvider { primary, fallback }
+    }
+
+    /// The provider that's tried first.
+    pub fn primary(&self) -> &A {
+        &self.primary
+    }
+
+    /// The provider that's tried when the first one doesn't have the data.
+    pub fn fallback(&self) -> &B {
+        &self.fallback
+    }
+}
+
+// turns the result of the first provider into the final result, trying the second provider
+// only if the first didn't find anything.
+async fn fall_back<T, EA, EB, F>(
+    primary: Result<T, EA>,
+    fallback: impl FnOnce() -> F,
+) -> Result<T, FallbackError<EA, EB>>
+where
+    EA: LookupError,
+    EB: Error,
+    F: std::future::Future<Output = Result<T, EB>>,
+{
+    match primary {
+        Ok(value) => Ok(value),
+        Err(e) if e.is_not_found() => fallback().await.map_err(FallbackError::Fallback),
+        Err(e) => Err(FallbackError::Primary(e)),
+    }
+}
+
+#[async_trait]
+impl<A, B, EA, EB> DataProvider<FallbackError<EA, EB>> for FallbackProvider<A, B>
+where
+    A: DataProvider<EA>,
+    B: DataProvider<EB>,
+    EA: LookupError + Send,
+    EB: Error + Send,
+{
+    async fn get_race(&self, name: &str) -> Result<Race, FallbackError<EA, EB>> {
+        let first = self.primary.get_race(name).await;
+        fall_back(first, || self.fallback.get_race(name)).await
+    }
+    async fn get_background(&self, name: &str) -> Result<Background, FallbackError<EA, EB>> {
+        let first = self.primary.get_background(name).await;
+        fall_back(first, || self.fallback.get_background(name)).await
+    }
+    async fn get_item(&self, name: &str) -> Result<Item, FallbackError<EA, EB>> {
+        let first = self.primary.get_item(name).await;
+        fall_back(first, || self.fallback.get_item(name)).await
+    }
+    async fn get_class(&self, name: &str) -> Result<Class, FallbackError<EA, EB>> {
+        let first = self.primary.get_class(name).await;
+        fall_back(first, || self.fallback.get_class(name)).await
+    }
+    async fn get_spell(&self, name: &str) -> Result<Spell, FallbackError<EA, EB>> {
+        let first = self.primary.get_spell(name).await;
+        fall_back(first, || self.fallback.get_spell(name)).await
+    }
+}
@@ -5,6 +5,12 @@ use async_trait::async_trait;
 
 use crate::rules2014::{background::Background, class::Class, items::Item, spells::Spell, Race};
 
+mod caching;
+mod fallback;
+
+pub use caching::CachingProvider;
+pub use fallback::{FallbackError, FallbackProvider, LookupError};
+
 /// A trait representing a source capable of retrieving D&D data, e.g. from an api.
 ///
 /// This trait's definition looks imposing, but most of that is caused by the async implementation.
@@ -60,3 +66,120 @@ pub trait DataProvider<E: Error>: Send + Sync {
     async fn get_spell(&self, name: &str) -> Result<Spell, E>;
 }
 
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::rules2014::test_fixtures::{fighter, longsword, HomebrewProvider, Missing};
+    use futures::executor::block_on;
+
+    // counts how many times the homebrew provider is actually asked for an item
+    struct Counting(AtomicUsize);
+
+    #[async_trait]
+    impl DataProvider<Missing> for Counting {
+        async fn get_race(&self, name: &str) -> Result<Race, Missing> {
+            HomebrewProvider.get_race(name).await
+        }
+        async fn get_background(&self, name: &str) -> Result<Background, Missing> {
+            HomebrewProvider.get_background(name).await
+        }
+        async fn get_item(&self, name: &str) -> Result<Item, Missing> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            HomebrewProvider.get_item(name).await
+        }
+        async fn get_class(&self, name: &str) -> Result<Class, Missing> {
+            HomebrewProvider.get_class(name).await
+        }
+        async fn get_spell(&self, name: &str) -> Result<Spell, Missing> {
+            HomebrewProvider.get_spell(name).await
+        }
+    }
+
+    #[test]
+    fn caching_provider() {
+        let provider = CachingProvider::new(Counting(AtomicUsize::new(0)));
+        let calls = || provider.inner().0.load(Ordering::SeqCst);
+
+        assert_eq!(
+            block_on(provider.get_item("Longsword")).unwrap(),
+            longsword()
+        );
+        assert_eq!(
+            block_on(provider.get_item("Longsword")).unwrap(),
+            longsword()
+        );
+        assert_eq!(calls(), 1);
+
+        // errors are tried again each time
+        assert!(block_on(provider.get_item("Dagger")).is_err());
+        assert!(block_on(provider.get_item("Dagger")).is_err());
+        assert_eq!(calls(), 3);
+
+        provider.clear_cache();
+        block_on(provider.get_item("Longsword")).unwrap();
+        assert_eq!(calls(), 4);
+    }
+
+    // never has anything, and breaks when asked for items
+    struct Broken;
+
+    #[derive(Debug, thiserror::Error)]
+    enum BrokenError {
+        #[error("not found")]
+        NotFound,
+        #[error("broken")]
+        Broken,
+    }
+
+    impl LookupError for BrokenError {
+        fn is_not_found(&self) -> bool {
+            matches!(self, BrokenError::NotFound)
+        }
+    }
+
+    #[async_trait]
+    impl DataProvider<BrokenError> for Broken {
+        async fn get_race(&self, _: &str) -> Result<Race, BrokenError> {
+            Err(BrokenError::NotFound)
+        }
+        async fn get_background(&self, _: &str) -> Result<Background, BrokenError> {
+            Err(BrokenError::NotFound)
+        }
+        async fn get_item(&self, _: &str) -> Result<Item, BrokenError> {
+            Err(BrokenError::Broken)
+        }
+        async fn get_class(&self, _: &str) -> Result<Class, BrokenError> {
+            Err(BrokenError::NotFound)
+        }
+        async fn get_spell(&self, _: &str) -> Result<Spell, BrokenError> {
+            Err(BrokenError::NotFound)
+        }
+    }
+
+    #[test]
+    fn fallback_provider() {
+        let provider = FallbackProvider::new(Broken, HomebrewProvider);
+        assert_eq!(
+            block_on(provider.get_class("Fighter")).unwrap().name(),
+            fighter().name()
+        );
+        assert!(matches!(
+            block_on(provider.get_race("Elf")),
+            Err(FallbackError::Fallback(_))
+        ));
+        // anything other than not found stops at the first provider
+        assert!(matches!(
+            block_on(provider.get_item("Longsword")),
+            Err(FallbackError::Primary(BrokenError::Broken))
+        ));
+
+        // the first provider wins when it has the data
+        let provider = FallbackProvider::new(HomebrewProvider, Broken);
+        assert_eq!(
+            block_on(provider.get_item("Longsword")).unwrap(),
+            longsword()
+        );
+    }
+}
@@ -8,8 +8,8 @@
 //! # #[cfg(feature = "dnd5eapi")] {
 //! #[tokio::main]
 //! async fn main() {
-//!     use rand::Rng;
 //!     use dnd_lib::prelude::*;
+//!     use dnd_lib::rules2014::items::AdvantageState;
 //!     let mut rng = rand::thread_rng();
 //!
 //!     // first, we construct the api getter.
@@ -38,9 +38,9 @@
 //!     // Now it's John's turn. He readies his dagger.
 //!     let dagger_attack = &john.weapon_actions()[0];
 //!     // John tries to attack...
-//!     let attack_roll = rng.random_range(1..=20) as isize + dagger_attack.attack_bonus;
+//!     let attack_roll = dagger_attack.roll_attack(&mut rng, AdvantageState::Normal);
 //!     // And it hits!
-//!     let damage_roll = dagger_attack.damage_roll;
+//!     let damage = dagger_attack.roll_damage(&mut rng, attack_roll.critical);
 //!     // It does enough damage to kill the monster immediately!
 //!
 //!     // With the xp from that fight, john levels up.
@@ -58,6 +58,7 @@
 //!
 //! This is what [get::Dnd5eapiGetter] is for. You first get the required rules (class, background,
 //! race) from the api, then you build a character with that.
+//! To work offline, [local::LocalDataProvider] reads those same rules from json files on disk.
 //!
 //! ## Feature flags
 //!
@@ -70,11 +71,12 @@
 #[cfg(feature = "dnd5eapi")]
 pub mod get;
 mod getter;
+pub mod local;
 pub mod rules2014;
 pub mod save;
 
 // re-export trait
-pub use getter::DataProvider;
+pub use getter::{CachingProvider, DataProvider, FallbackError, FallbackProvider, LookupError};
 pub use rules2014::player_character::{Character, CharacterBuilder};
 
 #[cfg_attr(not(test), allow(dead_code))]
@@ -0,0 +1,309 @@
+//! Gets D&D data from json files on disk, for working offline.
+//!
+//! The main feature of this module is the [LocalDataProvider], which implements the
+//! [DataProvider] trait by reading files out of a directory. [dump_to_dir] fills that directory
+//! from another provider, so data can be fetched from an api once and then used offline.
+//!
+//! ```no_run
+//! # #[cfg(feature = "dnd5eapi")] {
+//! use dnd_lib::get::Dnd5eapiGetter;
+//! use dnd_lib::local::{dump_to_dir, DataKind};
+//! use dnd_lib::DataProvider;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     // fetch what's needed once,
+//!     let wanted = [(DataKind::Class, "rogue"), (DataKind::Race, "human")];
+//!     dump_to_dir(&Dnd5eapiGetter::new(), "./dnd_data", &wanted)
+//!         .await
+//!         .unwrap();
+//!
+//!     // and from then on, it can be read from disk.
+//!     let local = dnd_lib::local::LocalDataProvider::new("./dnd_data");
+//!     let rogue = local.get_class("rogue").await.unwrap();
+//!     assert_eq!(rogue.name(), "rogue");
+//! }
+//! # }
+//! ```
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::getter::DataProvider;
+use crate::rules2014::{background::Background, class::Class, items::Item, spells::Spell, Race};
+
+/// A kind of data a [DataProvider] gets, which decides the folder it's stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataKind {
+    Race,
+    Background,
+    Item,
+    Class,
+    Spell,
+}
+
+impl DataKind {
+    /// The name of the folder this kind of data is stored in, like "classes".
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            DataKind::Race => "races",
+            DataKind::Background => "backgrounds",
+            DataKind::Item => "items",
+            DataKind::Class => "classes",
+            DataKind::Spell => "spells",
+        }
+    }
+}
+
+/// Errors from reading or writing data with a [LocalDataProvider].
+#[derive(Debug, Error)]
+pub enum LocalDataError {
+    /// There's no file for the requested name.
+    #[error("no {kind:?} named {name} at {}", path.display())]
+    NotFound {
+        kind: DataKind,
+        name: String,
+        path: PathBuf,
+    },
+    /// The name has a path separator or `..` in it, so it could point outside of its folder.
+    #[error("{name:?} isn't a valid {kind:?} name")]
+    InvalidName { kind: DataKind, name: String },
+    /// The file exists, but couldn't be read or written.
+    #[error("failed to access {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    /// The file doesn't hold valid data of the requested kind.
+    #[error("failed to parse {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+/// Gets D&D data from json files in a directory.
+///
+/// Each piece of data is stored at `{root}/{kind}/{name}.json`, where the kind is the
+/// [DataKind::dir_name] and the name is lowercase with dashes instead of spaces. For example,
+/// the half-elf race would be at `races/half-elf.json`, and the chain mail item would be at
+/// `items/chain-mail.json`.
+///
+/// The files hold each type serialized with serde_json, like
+/// [save_serialized](crate::save::save_serialized) writes them. [LocalDataProvider::save] and
+/// [dump_to_dir] write them out in the right place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalDataProvider {
+    root: PathBuf,
+}
+
+impl LocalDataProvider {
+    /// Creates a provider that reads from the given directory. Nothing is read until data is
+    /// requested, so the directory doesn't need to exist yet.
+    pub fn new(root: impl Into<PathBuf>) -> LocalDataProvider {
+        LocalDataProvider { root: root.into() }
+    }
+
+    /// The directory this provider reads from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The path a piece of data with this name is stored at.
+    ///
+    /// Names with a path separator or `..` in them are rejected with
+    /// [LocalDataError::InvalidName], so a name can't reach outside of its kind's folder.
+    pub fn path_for(&self, kind: DataKind, name: &str) -> Result<PathBuf, LocalDataError> {
+        if name.contains(['/', '\\']) || name.contains("..") {
+            return Err(LocalDataError::InvalidName {
+                kind,
+                name: name.to_string(),
+            });
+        }
+        let file_name = format!("{}.json", name.trim().to_lowercase().replace(' ', "-"));
+        Ok(self.root.join(kind.dir_name()).join(file_name))
+    }
+
+    /// Saves a piece of data under a name, creating its folder if needed. It can then be read
+    /// back with the matching [DataProvider] method.
+    pub fn save<T: Serialize>(
+        &self,
+        kind: DataKind,
+        name: &str,
+        value: &T,
+    ) -> Result<(), LocalDataError> {
+        let path = self.path_for(kind, name)?;
+        let io_error = |source| LocalDataError::Io {
+            path: path.clone(),
+            source,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let json = serde_json::to_string(value).map_err(|source| LocalDataError::Parse {
+            path: path.clone(),
+            source,
+        })?;
+        fs::write(&path, json).map_err(io_error)
+    }
+
+    fn load<T: DeserializeOwned>(&self, kind: DataKind, name: &str) -> Result<T, LocalDataError> {
+        let path = self.path_for(kind, name)?;
+        let json = match fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(LocalDataError::NotFound {
+                    kind,
+                    name: name.to_string(),
+                    path,
+                })
+            }
+            Err(source) => return Err(LocalDataError::Io { path, source }),
+        };
+        serde_json::from_str(&json).map_err(|source| LocalDataError::Parse { path, source })
+    }
+}
+
+#[async_trait]
+impl DataProvider<LocalDataError> for LocalDataProvider {
+    async fn get_race(&self, name: &str) -> Result<Race, LocalDataError> {
+        self.load(DataKind::Race, name)
+    }
+    async fn get_background(&self, name: &str) -> Result<Background, LocalDataError> {
+        self.load(DataKind::Background, name)
+    }
+    async fn get_item(&self, name: &str) -> Result<Item, LocalDataError> {
+        self.load(DataKind::Item, name)
+    }
+    async fn get_class(&self, name: &str) -> Result<Class, LocalDataError> {
+        self.load(DataKind::Class, name)
+    }
+    async fn get_spell(&self, name: &str) -> Result<Spell, LocalDataError> {
+        self.load(DataKind::Spell, name)
+    }
+}
+
+/// An error from [dump_to_dir], either in getting the data or in writing it.
+#[derive(Debug, Error)]
+pub enum DumpError<E: Error> {
+    #[error(transparent)]
+    Provider(E),
+    #[error(transparent)]
+    Local(#[from] LocalDataError),
+}
+
+/// Gets each piece of data from a provider, and writes it out to a directory for a
+/// [LocalDataProvider] to read. Returns that provider.
+///
+/// Each piece is saved under the name it was requested with, so it can be gotten from the local
+/// provider with that same name.
+pub async fn dump_to_dir<E: Error>(
+    provider: &impl DataProvider<E>,
+    root: impl Into<PathBuf>,
+    wanted: &[(DataKind, &str)],
+) -> Result<LocalDataProvider, DumpError<E>> {
+    let local = LocalDataProvider::new(root);
+    for &(kind, name) in wanted {
+        match kind {
+            DataKind::Race => {
+                let race = provider.get_race(name).await.map_err(DumpError::Provider)?;
+                local.save(kind, name, &race)?;
+            }
+            DataKind::Background => {
+                let background = provider
+                    .get_background(name)
+                    .await
+                    .map_err(DumpError::Provider)?;
+                local.save(kind, name, &background)?;
+            }
+            DataKind::Item => {
+                let item = provider.get_item(name).await.map_err(DumpError::Provider)?;
+                local.save(kind, name, &item)?;
+            }
+            DataKind::Class => {
+                let class = provider
+                    .get_class(name)
+                    .await
+                    .map_err(DumpError::Provider)?;
+                local.save(kind, name, &class)?;
+            }
+            DataKind::Spell => {
+                let spell = provider
+                    .get_spell(name)
+                    .await
+                    .map_err(DumpError::Provider)?;
+                local.save(kind, name, &spell)?;
+            }
+        }
+    }
+    Ok(local)
+}
+
+#[cfg(test)]
+#[cfg(feature = "disk-tests")]
+mod tests {
+    use super::{dump_to_dir, DataKind, LocalDataError, LocalDataProvider};
+    use crate::getter::DataProvider;
+    use crate::rules2014::test_fixtures::{human, longsword, HomebrewProvider};
+    use futures::executor::block_on;
+
+    #[test]
+    fn local_round_trip() {
+        let root = std::env::temp_dir().join("dnd_lib_local_round_trip");
+        let _ = std::fs::remove_dir_all(&root);
+
+        let wanted = [
+            (DataKind::Race, "Human"),
+            (DataKind::Item, "Longsword"),
+            (DataKind::Class, "Fighter"),
+            (DataKind::Spell, "Magic Missile"),
+        ];
+        let local = block_on(dump_to_dir(&HomebrewProvider, &root, &wanted)).unwrap();
+        assert!(root.join("items").join("longsword.json").exists());
+        assert!(root.join("spells").join("magic-missile.json").exists());
+
+        assert_eq!(block_on(local.get_item("longsword")).unwrap(), longsword());
+        assert_eq!(
+            block_on(local.get_race("human")).unwrap().name(),
+            human().name()
+        );
+        assert_eq!(
+            block_on(local.get_class("Fighter")).unwrap().name(),
+            "Fighter"
+        );
+        let spell = block_on(local.get_spell("Magic Missile")).unwrap();
+        assert_eq!(spell.name, "Magic Missile");
+
+        let missing = block_on(local.get_background("Acolyte"));
+        assert!(matches!(
+            missing,
+            Err(LocalDataError::NotFound {
+                kind: DataKind::Background,
+                ..
+            })
+        ));
+
+        let elsewhere = LocalDataProvider::new(root.join("nothing here"));
+        assert!(block_on(elsewhere.get_item("longsword")).is_err());
+
+        // names can't reach outside of their folder
+        for name in ["../escape", "..", "items/longsword", "a\\b"] {
+            assert!(matches!(
+                local.path_for(DataKind::Item, name),
+                Err(LocalDataError::InvalidName { .. })
+            ));
+            assert!(matches!(
+                local.save(DataKind::Item, name, &longsword()),
+                Err(LocalDataError::InvalidName { .. })
+            ));
+        }
+        assert!(!root.join("escape.json").exists());
+        assert!(local.path_for(DataKind::Item, "Chain Mail").is_ok());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
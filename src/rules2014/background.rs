@@ -1,7 +1,7 @@
 use super::choice::PresentedOption;
 use super::features::Feature;
-use super::items::Item;
 use super::items::ItemCount;
+use super::items::{Coins, Item};
 use super::stats::SkillType;
 use heck::ToTitleCase;
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,8 @@ pub struct Background {
     ideals: Vec<String>,
     bonds: Vec<String>,
     flaws: Vec<String>,
+    #[serde(default)]
+    starting_coins: Coins,
 }
 
 impl Background {
@@ -49,6 +51,11 @@ impl Background {
         &self.equipment
     }
 
+    /// Gets the money the background starts with, like the acolyte's 15 gp.
+    pub fn starting_coins(&self) -> Coins {
+        self.starting_coins
+    }
+
     /// Returns the features granted by the background.
     pub fn features(&self) -> &Vec<Feature> {
         &self.features
@@ -228,6 +235,7 @@ impl BackgroundBuilder {
                 ideals: Vec::new(),
                 bonds: Vec::new(),
                 flaws: Vec::new(),
+                starting_coins: Coins::default(),
             },
         }
     }
@@ -269,6 +277,12 @@ impl BackgroundBuilder {
         self
     }
 
+    /// Sets the money the background starts with.
+    pub fn set_starting_coins(mut self, coins: Coins) -> Self {
+        self.background.starting_coins = coins;
+        self
+    }
+
     pub fn add_feature(mut self, feature: Feature) -> Self {
         self.background.features.push(feature);
         self
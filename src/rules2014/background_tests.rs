@@ -20,6 +20,8 @@ fn test_background_builder_success() {
         name: "Test Item".to_string(),
         description: None,
         item_type: super::items::ItemType::Misc,
+        weight: None,
+        attunement: None,
         features: vec![],
     };
 
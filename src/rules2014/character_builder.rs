@@ -63,15 +63,22 @@ type ItemChoice = PresentedOption<Vec<(ItemCategory, usize)>>;
 ///
 /// There isn't anything for reading which items there are directly. Instead, read them from
 /// the class using [Class::beginning_items].
+///
+/// Items start unequipped. To start the character with their best weapon and armor equipped, use
+/// [equip_starting_weapon](CharacterBuilder::equip_starting_weapon) and
+/// [equip_starting_armor](CharacterBuilder::equip_starting_armor).
 // the i stands for internal
 #[derive(Clone)]
 pub struct CharacterBuilder<'a, 'b, 'c> {
     name: String,
     iclass: Option<&'a Class>,
+    subclass: Option<String>,
     items: Option<(Vec<ItemChoice>, Vec<usize>)>,
     ibackground: Option<&'b Background>,
     irace: Option<&'c Race>,
     istats: Option<Stats>,
+    equip_weapon: bool,
+    equip_armor: bool,
 }
 
 impl<'a, 'b, 'c> CharacterBuilder<'a, 'b, 'c> {
@@ -79,10 +86,13 @@ impl<'a, 'b, 'c> CharacterBuilder<'a, 'b, 'c> {
         CharacterBuilder {
             name: name.to_string(),
             iclass: None,
+            subclass: None,
             items: None,
             ibackground: None,
             irace: None,
             istats: None,
+            equip_weapon: false,
+            equip_armor: false,
         }
     }
 
@@ -91,6 +101,15 @@ impl<'a, 'b, 'c> CharacterBuilder<'a, 'b, 'c> {
         self
     }
 
+    /// Chooses the class's subclass by name, ignoring case.
+    ///
+    /// See [Character::choose_subclass_by_name]. Building fails if the class doesn't have a
+    /// subclass with this name.
+    pub fn subclass(mut self, name: &str) -> Self {
+        self.subclass = Some(name.to_string());
+        self
+    }
+
     pub fn background(mut self, background: &'b Background) -> Self {
         self.ibackground = Some(background);
         self
@@ -182,7 +201,23 @@ impl<'a, 'b, 'c> CharacterBuilder<'a, 'b, 'c> {
             Some(v) => &mut v.0,
             _ => return self,
         };
-        *category = ItemCategory::Item(item);
+        *category = ItemCategory::Item(Box::new(item));
+        self
+    }
+
+    /// Equips the character's best weapon when building, after their items are chosen.
+    ///
+    /// See [Character::equip_best_weapon].
+    pub fn equip_starting_weapon(mut self) -> Self {
+        self.equip_weapon = true;
+        self
+    }
+
+    /// Equips the character's best armor when building, after their items are chosen.
+    ///
+    /// See [Character::equip_best_armor].
+    pub fn equip_starting_armor(mut self) -> Self {
+        self.equip_armor = true;
         self
     }
 
@@ -194,10 +229,21 @@ impl<'a, 'b, 'c> CharacterBuilder<'a, 'b, 'c> {
         let stats = self.istats.ok_or("Missing stats")?;
 
         let mut character = Character::new(self.name, class, background, race, stats);
+        if let Some(subclass) = self.subclass {
+            if !character.choose_subclass_by_name(0, &subclass) {
+                return Err("Unknown subclass");
+            }
+        }
         if let Some(items) = self.items {
             character.unchosen_items = items.0;
             character.add_chosen_items();
         }
+        if self.equip_weapon {
+            character.equip_best_weapon();
+        }
+        if self.equip_armor {
+            character.equip_best_armor();
+        }
         Ok(character)
     }
 }
@@ -0,0 +1,410 @@
+//! A smaller save format for characters, holding only the choices made for them.
+use std::error::Error;
+
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::choice::PresentedOption;
+use super::features::{AbilityScoreIncrease, Feat, Feature};
+use super::items::{Coins, HeldEquipment};
+use super::player_character::{Character, CharacterDescriptors, CharacterStory};
+use super::stats::{SkillType, StatType, Stats};
+use crate::getter::DataProvider;
+
+/// The parts of a [Character] that are chosen rather than calculated, made with
+/// [Character::to_editable].
+///
+/// Serializing a [Character] saves everything, including values that are derived from the rules
+/// like spell slots or max hp. This only keeps the inputs, so it's much smaller and easier to
+/// edit by hand. Since the rules are fetched again in [Character::from_editable], changes to a
+/// class or spell show up in the loaded character as well.
+///
+/// Things are stored by name, so the provider used to load the character has to be able to find
+/// every class, race, background, spell, and item in it. Homebrew feats and features don't have a
+/// getter, so they're stored in full.
+///
+/// State that changes during play, like the current hp, spent spell slots, or hit dice, isn't
+/// kept. A loaded character starts fully rested.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditableCharacter {
+    pub name: String,
+    pub race: String,
+    #[serde(default)]
+    pub subrace: Option<String>,
+    /// The stat picked for each of the race's ability bonuses, in order. Fixed bonuses are listed
+    /// as well, but only the ones the race leaves open are used when loading.
+    #[serde(default)]
+    pub race_ability_choices: Vec<Option<StatType>>,
+    /// The languages picked for the race's wildcard languages.
+    #[serde(default)]
+    pub race_languages: Vec<Option<String>>,
+    pub background: String,
+    #[serde(default)]
+    pub background_skills: Vec<SkillType>,
+    #[serde(default)]
+    pub background_languages: Vec<String>,
+    /// The character's classes, with the first being the one they started as.
+    pub classes: Vec<EditableClass>,
+    pub base_stats: Stats,
+    #[serde(default)]
+    pub class_skills: Vec<SkillType>,
+    /// Every ability score increase from the character's classes, in the same order as
+    /// [Character::ability_score_increases].
+    #[serde(default)]
+    pub ability_score_increases: Vec<AbilityScoreIncrease>,
+    #[serde(default)]
+    pub mystic_arcana: Vec<String>,
+    #[serde(default)]
+    pub items: Vec<EditableItem>,
+    #[serde(default)]
+    pub feats: Vec<Feat>,
+    #[serde(default)]
+    pub bonus_features: Vec<Feature>,
+    #[serde(default)]
+    pub coins: Coins,
+    #[serde(default)]
+    pub xp: usize,
+    #[serde(default)]
+    pub story: CharacterStory,
+    #[serde(default)]
+    pub descriptors: CharacterDescriptors,
+}
+
+/// A class in an [EditableCharacter].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditableClass {
+    pub name: String,
+    pub level: usize,
+    #[serde(default)]
+    pub subclass: Option<String>,
+    /// The names of the class's chosen features. When the class offers a choice of features, the
+    /// one listed here is picked.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// The names of the spells the class has prepared or known.
+    #[serde(default)]
+    pub spells: Vec<String>,
+}
+
+/// An item in an [EditableCharacter].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditableItem {
+    pub name: String,
+    pub quantity: usize,
+    #[serde(default)]
+    pub equipped: bool,
+    /// Whether the character is attuned to the item, for items that need attunement.
+    #[serde(default)]
+    pub attuned: bool,
+}
+
+/// An error in loading a character with [Character::from_editable].
+#[derive(Debug, Error)]
+pub enum EditableCharacterError<E: Error> {
+    /// The provider couldn't get something the character needs.
+    #[error(transparent)]
+    Provider(E),
+    #[error("Character must have at least one class")]
+    NoClasses,
+    #[error("Character couldn't reach level {level} in {class}")]
+    LevelUp { class: String, level: usize },
+    /// A choice in the save doesn't exist for the character, like a subclass that isn't part of
+    /// the class.
+    #[error("{0} isn't a valid choice for this character")]
+    InvalidChoice(String),
+}
+
+impl Character {
+    /// Gets the choices made for the character, without anything derived from them. See
+    /// [EditableCharacter].
+    pub fn to_editable(&self) -> EditableCharacter {
+        let classes = self
+            .classes
+            .iter()
+            .map(|class| EditableClass {
+                name: class.class.clone(),
+                level: class.level,
+                subclass: class.subclass.as_base().map(|s| s.name.clone()),
+                features: class
+                    .current_class_features
+                    .iter()
+                    .flatten()
+                    .filter_map(|f| f.as_base())
+                    .map(|f| f.name.clone())
+                    .collect(),
+                spells: class
+                    .spellcasting
+                    .iter()
+                    .flat_map(|(_, spells)| spells.iter())
+                    .map(|s| s.name.clone())
+                    .collect(),
+            })
+            .collect();
+
+        EditableCharacter {
+            name: self.name.clone(),
+            race: self.race.name().to_string(),
+            subrace: self.race.subrace().map(|s| s.name().to_string()),
+            race_ability_choices: self
+                .race
+                .ability_bonuses()
+                .iter()
+                .map(|(s, _)| *s)
+                .collect(),
+            race_languages: self.race.wildcard_languages().clone(),
+            background: self.background.name().to_string(),
+            background_skills: self
+                .background
+                .proficiencies()
+                .into_iter()
+                .filter_map(|p| p.as_base().copied())
+                .collect(),
+            background_languages: self
+                .background
+                .languages()
+                .into_iter()
+                .map(|l| l.to_string())
+                .collect(),
+            classes,
+            base_stats: self.base_stats(),
+            class_skills: self
+                .class_skill_proficiencies
+                .iter()
+                .filter_map(|p| p.as_base().copied())
+                .collect(),
+            ability_score_increases: self
+                .ability_score_increases()
+                .into_iter()
+                .cloned()
+                .collect(),
+            mystic_arcana: self
+                .mystic_arcana()
+                .into_iter()
+                .filter_map(|(_, spell)| Some(spell?.name.clone()))
+                .collect(),
+            items: self
+                .items
+                .iter()
+                .map(|held| EditableItem {
+                    name: held.item.name.clone(),
+                    quantity: held.quantity,
+                    equipped: held.equipped,
+                    attuned: held.item.attunement == Some(true),
+                })
+                .collect(),
+            feats: self.feats.clone(),
+            bonus_features: self.bonus_features.clone(),
+            coins: self.coins,
+            xp: self.xp,
+            story: self.story.clone(),
+            descriptors: self.descriptors.clone(),
+        }
+    }
+
+    /// Rebuilds a character from an [EditableCharacter], getting its rules from the provider.
+    ///
+    /// The character is leveled up class by class, making the saved choices as they become
+    /// available, and then has its hp, spell slots, and class resources filled.
+    pub async fn from_editable<E: Error>(
+        editable: EditableCharacter,
+        provider: &impl DataProvider<E>,
+    ) -> Result<Character, EditableCharacterError<E>> {
+        use EditableCharacterError::*;
+
+        if editable.classes.is_empty() {
+            return Err(NoClasses);
+        }
+        let class_futures = editable.classes.iter().map(|c| provider.get_class(&c.name));
+        let spell_futures = editable
+            .classes
+            .iter()
+            .map(|c| try_join_all(c.spells.iter().map(|s| provider.get_spell(s))));
+        let arcanum_futures = editable.mystic_arcana.iter().map(|s| provider.get_spell(s));
+        let item_futures = editable.items.iter().map(|i| provider.get_item(&i.name));
+
+        let classes = try_join_all(class_futures).await.map_err(Provider)?;
+        let race = provider.get_race(&editable.race).await.map_err(Provider)?;
+        let background = provider
+            .get_background(&editable.background)
+            .await
+            .map_err(Provider)?;
+        let spells = try_join_all(spell_futures).await.map_err(Provider)?;
+        let arcana = try_join_all(arcanum_futures).await.map_err(Provider)?;
+        let items = try_join_all(item_futures).await.map_err(Provider)?;
+
+        let mut character = Character::new(
+            editable.name,
+            &classes[0],
+            &background,
+            &race,
+            editable.base_stats,
+        );
+
+        // race
+        if let Some(subrace) = &editable.subrace {
+            let subraces = character.race.subraces_mut();
+            if !subraces.choose_where(|s| s.name() == subrace) {
+                return Err(InvalidChoice(subrace.clone()));
+            }
+        }
+        let open_bonuses = race.ability_bonuses().iter().map(|(s, _)| s.is_none());
+        let picked_bonuses = editable
+            .race_ability_choices
+            .iter()
+            .zip(open_bonuses)
+            .filter_map(|(stat, open)| open.then_some(*stat));
+        for ((slot, _), stat) in character
+            .race
+            .ability_bonuses_unchosen()
+            .into_iter()
+            .zip(picked_bonuses)
+        {
+            *slot = stat;
+        }
+        for (index, lang) in editable.race_languages.iter().enumerate() {
+            if let Some(lang) = lang {
+                character.race.choose_wildcard_language(index, lang);
+            }
+        }
+
+        // background
+        let mut background_skills = editable.background_skills;
+        for index in character.pending_background_proficiencies() {
+            let choice = character.background.proficiencies()[index]
+                .choices()
+                .and_then(|choices| take_wanted(choices, &mut background_skills, |a, b| a == b));
+            if let Some(choice) = choice {
+                character.choose_background_proficiency(index, choice);
+            }
+        }
+        let unchosen_languages: Vec<usize> = character
+            .background
+            .unchosen_language_options()
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+        for index in unchosen_languages {
+            for lang in &editable.background_languages {
+                let known = character.background.languages().contains(&lang.as_str());
+                if !known && character.background.choose_language_option(index, lang) {
+                    break;
+                }
+            }
+        }
+
+        let mut class_skills = editable.class_skills;
+        for option in character.class_skill_proficiencies.iter_mut() {
+            choose_wanted(option, &mut class_skills, |a, b| a == b);
+        }
+
+        // features and feats can count towards multiclassing prerequisites, so they're added
+        // before leveling
+        character.feats = editable.feats;
+        character.bonus_features = editable.bonus_features;
+
+        // classes, in the order they were taken
+        let mut increases = editable.ability_score_increases.into_iter();
+        let mut increases_applied = 0;
+        for (index, (saved, class)) in editable.classes.iter().zip(&classes).enumerate() {
+            let times = if index == 0 {
+                saved.level - 1
+            } else {
+                saved.level
+            };
+            if times > 0 {
+                character
+                    .level_up_multiple(class, times)
+                    .filter(|level| *level == saved.level)
+                    .ok_or_else(|| LevelUp {
+                        class: saved.name.clone(),
+                        level: saved.level,
+                    })?;
+            }
+
+            let specced = &mut character.classes[index];
+            if let Some(subclass) = &saved.subclass {
+                if !specced.subclass.choose_where(|s| &s.name == subclass) {
+                    return Err(InvalidChoice(subclass.clone()));
+                }
+            }
+
+            let mut features = saved.features.clone();
+            for option in specced.current_class_features.iter_mut().flatten() {
+                choose_wanted(option, &mut features, |f, name| &f.name == name);
+            }
+
+            if let Some((_, spells_known)) = specced.spellcasting.as_mut() {
+                spells_known.clone_from(&spells[index]);
+            } else if let Some(spell) = spells[index].first() {
+                return Err(InvalidChoice(spell.name.clone()));
+            }
+
+            // ability score increases are listed by class, so the new ones are always at the end
+            for slot in character
+                .ability_score_increases_mut()
+                .into_iter()
+                .skip(increases_applied)
+            {
+                let Some(increase) = increases.next() else {
+                    break;
+                };
+                *slot = increase;
+                increases_applied += 1;
+            }
+        }
+
+        for spell in arcana {
+            let name = spell.name.clone();
+            if !character.set_mystic_arcanum(spell) {
+                return Err(InvalidChoice(name));
+            }
+        }
+
+        character.items = items
+            .into_iter()
+            .zip(&editable.items)
+            .map(|(mut item, saved)| {
+                if item.attunement.is_some() {
+                    item.attunement = Some(saved.attuned);
+                }
+                HeldEquipment::new(item, saved.quantity, saved.equipped)
+            })
+            .collect();
+        character.unchosen_items.clear();
+        character.coins = editable.coins;
+
+        character.xp = editable.xp;
+        character.story = editable.story;
+        character.descriptors = editable.descriptors;
+
+        character.long_rest();
+        Ok(character)
+    }
+}
+
+// Chooses the option's first choice that matches something in `wanted`, taking that out of the
+// list so it isn't picked twice.
+fn choose_wanted<T, U>(
+    option: &mut PresentedOption<T>,
+    wanted: &mut Vec<U>,
+    matches: impl Fn(&T, &U) -> bool,
+) -> bool {
+    let choice = option
+        .choices()
+        .and_then(|choices| take_wanted(choices, wanted, matches));
+    choice.is_some_and(|choice| option.choose_in_place(choice))
+}
+
+// The index of the first choice that matches something in `wanted`, removing it from `wanted`.
+fn take_wanted<T, U>(
+    choices: &[T],
+    wanted: &mut Vec<U>,
+    matches: impl Fn(&T, &U) -> bool,
+) -> Option<usize> {
+    choices.iter().enumerate().find_map(|(n, choice)| {
+        let position = wanted.iter().position(|w| matches(choice, w))?;
+        wanted.remove(position);
+        Some(n)
+    })
+}
@@ -0,0 +1,382 @@
+//! Exporting characters to formats used by other tools.
+use std::collections::BTreeMap;
+
+use heck::ToSnakeCase;
+use serde_json::{json, Map, Value};
+use strum::IntoEnumIterator;
+
+use super::choice::PresentedOption;
+use super::player_character::Character;
+use super::stats::{SkillType, StatType};
+
+impl Character {
+    /// A plain text character sheet, for printing to a terminal or a log.
+    ///
+    /// This has the character's race, classes, and background, their AC, HP, proficiency bonus
+    /// and speeds, their ability scores, saving throws and skills with modifiers, their equipped
+    /// items, and their known and prepared spells grouped by level. Saving throws and skills the
+    /// character is proficient in are marked with `*`, and skills with expertise with `**`.
+    ///
+    /// ```text
+    /// John
+    /// Human | Fighter 1 | Soldier
+    ///
+    /// AC 16 | HP 12/12 | Proficiency +2 | Speed 30 ft.
+    ///
+    /// Ability Scores
+    ///   STR 16 (+3)
+    ///   ...
+    /// Saving Throws
+    ///   * Strength +5
+    ///     Dexterity +1
+    ///   ...
+    /// ```
+    pub fn sheet(&self) -> String {
+        let stats = self.stats();
+        let modifiers = stats.modifiers();
+        let saves = self.saves();
+        let save_mods = self.save_mods();
+        let skills = self.skills();
+        let skill_modifiers = self.skill_modifiers();
+
+        let mut lines = vec![self.name.clone()];
+
+        let race = match self.race.subrace() {
+            Some(subrace) => format!("{} ({})", self.race.name(), subrace.name()),
+            None => self.race.name().to_string(),
+        };
+        let classes = self
+            .classes
+            .iter()
+            .map(|c| match &c.subclass {
+                PresentedOption::Base(subclass) => {
+                    format!("{} {} ({})", c.class, c.level, subclass.name)
+                }
+                PresentedOption::Choice(_) => format!("{} {}", c.class, c.level),
+            })
+            .collect::<Vec<_>>()
+            .join(" / ");
+        lines.push(format!("{race} | {classes} | {}", self.background.name()));
+        lines.push(String::new());
+
+        let speeds = self.speeds();
+        let speeds = [
+            ("", speeds.walking),
+            ("fly ", speeds.flying),
+            ("hover ", speeds.hovering),
+            ("burrow ", speeds.burrowing),
+            ("climb ", speeds.climbing),
+            ("swim ", speeds.swimming),
+        ]
+        .into_iter()
+        .filter_map(|(name, speed)| Some(format!("{name}{} ft.", speed?)))
+        .collect::<Vec<_>>()
+        .join(", ");
+        let temp_hp = match self.temp_hp {
+            0 => String::new(),
+            n => format!(" (+{n} temp)"),
+        };
+        lines.push(format!(
+            "AC {} | HP {}/{}{temp_hp} | Proficiency {:+} | Speed {speeds}",
+            self.ac(),
+            self.hp,
+            self.max_hp(),
+            self.proficiency_bonus(),
+        ));
+        lines.push(String::new());
+
+        lines.push("Ability Scores".to_string());
+        for stat in StatType::iter() {
+            let abbreviation = stat.to_string()[..3].to_uppercase();
+            lines.push(format!(
+                "  {abbreviation} {} ({:+})",
+                stats[stat], modifiers.stats[stat]
+            ));
+        }
+
+        lines.push("Saving Throws".to_string());
+        for stat in StatType::iter() {
+            let marker = if saves.is_proficient(stat) {
+                "* "
+            } else {
+                "  "
+            };
+            lines.push(format!("  {marker}{stat} {:+}", save_mods.stats[stat]));
+        }
+
+        lines.push("Skills".to_string());
+        for skill in SkillType::iter() {
+            let proficiency = skills.get_from_type(skill);
+            let marker = match (proficiency.proficiency, proficiency.expertise) {
+                (_, true) => "**",
+                (true, false) => "* ",
+                (false, false) => "  ",
+            };
+            lines.push(format!("  {marker}{skill} {:+}", skill_modifiers[skill]));
+        }
+
+        let equipped = self.equipped_items();
+        if !equipped.is_empty() {
+            lines.push("Equipment".to_string());
+            for item in equipped {
+                match item.count {
+                    1 => lines.push(format!("  {}", item.item.name)),
+                    n => lines.push(format!("  {} x{n}", item.item.name)),
+                }
+            }
+        }
+
+        let mut spells_by_level: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+        for spell in self.unique_spells() {
+            spells_by_level
+                .entry(spell.level)
+                .or_default()
+                .push(&spell.name);
+        }
+        if !spells_by_level.is_empty() {
+            lines.push("Spells".to_string());
+            for (level, names) in spells_by_level {
+                let level = match level {
+                    0 => "Cantrips".to_string(),
+                    n => format!("{} level", ordinal(n)),
+                };
+                lines.push(format!("  {level}: {}", names.join(", ")));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// Exports the character as an [Open5e](https://open5e.com) creature.
+    ///
+    /// Open5e's creature schema is the one used by its `/monsters/` endpoint, and is the format
+    /// most community tools (VTT importers, initiative trackers) can read. Since it's built for
+    /// monsters, a few character-only details don't have a place in it and aren't exported, like
+    /// items, story, or unspent resources. Unlike serializing the [Character] with serde, this
+    /// can't be turned back into a full character.
+    ///
+    /// ## Field mapping
+    ///
+    /// | Open5e field | Source |
+    /// |---|---|
+    /// | `name`, `slug` | [Character::name] |
+    /// | `size` | [CharacterDescriptors::size](super::player_character::CharacterDescriptors) |
+    /// | `type`, `subtype` | always `"humanoid"`, and the race name in lowercase |
+    /// | `alignment` | the character's alignment in lowercase, or `"unaligned"` |
+    /// | `armor_class` | [Character::ac] |
+    /// | `hit_points` | [Character::max_hp] |
+    /// | `hit_dice` | each class's levels and hit die, e.g. `"3d10 + 2d6"` |
+    /// | `speed` | [Character::speeds], as `walk`, `fly`, `hover`, `burrow`, `climb`, and `swim` |
+    /// | `strength`..`charisma` | [Character::stats] |
+    /// | `strength_save`..`charisma_save` | [Character::save_mods] if proficient, otherwise `null` |
+    /// | `perception` | the perception modifier from [Character::skill_modifiers] |
+    /// | `skills` | proficient skills in snake case, with their modifiers |
+    /// | `senses` | [Character::senses] and the passive perception, e.g. `"darkvision 60 ft., passive Perception 12"` |
+    /// | `languages` | [Character::languages], comma separated |
+    /// | `actions` | [Character::weapon_actions], with `name`, `desc`, `attack_bonus`, `damage_dice`, and `damage_bonus` |
+    /// | `spell_list` | Open5e spell urls for every spell in [Character::spells] |
+    /// | `spells` | the same spells as Open5e spell objects, with `name`, `slug`, `level_int`, `level`, `school`, `concentration`, `ritual`, and `components` |
+    ///
+    /// For example, a human fighter would export to something like:
+    ///
+    /// ```json
+    /// {
+    ///   "name": "John",
+    ///   "slug": "john",
+    ///   "size": "Medium",
+    ///   "type": "humanoid",
+    ///   "subtype": "human",
+    ///   "armor_class": 16,
+    ///   "hit_points": 12,
+    ///   "hit_dice": "1d10",
+    ///   "speed": { "walk": 30 },
+    ///   "strength": 16,
+    ///   "strength_save": 5,
+    ///   "dexterity_save": null,
+    ///   "skills": { "athletics": 5 },
+    ///   "actions": [
+    ///     {
+    ///       "name": "Longsword",
+    ///       "desc": "Weapon Attack: +5 to hit. Hit: 1d8 + 3 slashing damage.",
+    ///       "attack_bonus": 5,
+    ///       "damage_dice": "1d8",
+    ///       "damage_bonus": 3
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    pub fn to_open5e_json(&self) -> Value {
+        let stats = self.stats();
+        let saves = self.saves();
+        let save_mods = self.save_mods();
+        let skills = self.skills();
+        let skill_modifiers = self.skill_modifiers();
+
+        let mut creature = Map::new();
+        creature.insert("name".to_string(), json!(self.name));
+        creature.insert("slug".to_string(), json!(open5e_slug(&self.name)));
+        creature.insert("size".to_string(), json!(self.descriptors.size.to_string()));
+        creature.insert("type".to_string(), json!("humanoid"));
+        creature.insert(
+            "subtype".to_string(),
+            json!(self.race.name().to_lowercase()),
+        );
+        let alignment = self
+            .descriptors
+            .alignment
+            .map(|a| a.to_string().to_lowercase())
+            .unwrap_or_else(|| "unaligned".to_string());
+        creature.insert("alignment".to_string(), json!(alignment));
+        creature.insert("armor_class".to_string(), json!(self.ac()));
+        creature.insert("hit_points".to_string(), json!(self.max_hp()));
+        let hit_dice = self
+            .classes
+            .iter()
+            .map(|c| format!("{}d{}", c.level, c.hit_die))
+            .collect::<Vec<_>>()
+            .join(" + ");
+        creature.insert("hit_dice".to_string(), json!(hit_dice));
+
+        let speeds = self.speeds();
+        let mut speed = Map::new();
+        for (name, value) in [
+            ("walk", speeds.walking),
+            ("fly", speeds.flying),
+            ("hover", speeds.hovering),
+            ("burrow", speeds.burrowing),
+            ("climb", speeds.climbing),
+            ("swim", speeds.swimming),
+        ] {
+            if let Some(v) = value {
+                speed.insert(name.to_string(), json!(v));
+            }
+        }
+        creature.insert("speed".to_string(), Value::Object(speed));
+
+        for stat in StatType::iter() {
+            let name = stat.to_string().to_lowercase();
+            let save = saves.is_proficient(stat).then(|| save_mods.stats[stat]);
+            creature.insert(format!("{name}_save"), json!(save));
+            creature.insert(name, json!(stats[stat]));
+        }
+
+        let perception = skill_modifiers[SkillType::Perception];
+        creature.insert("perception".to_string(), json!(perception));
+        let proficient_skills: Map<String, Value> = skills
+            .skills_with_proficiency()
+            .into_iter()
+            .map(|(skill, _)| {
+                (
+                    skill.to_string().to_snake_case(),
+                    json!(skill_modifiers[skill]),
+                )
+            })
+            .collect();
+        creature.insert("skills".to_string(), Value::Object(proficient_skills));
+        let senses = self.senses();
+        let ranged_senses = [
+            ("darkvision", senses.darkvision),
+            ("blindsight", senses.blindsight),
+            ("tremorsense", senses.tremorsense),
+        ];
+        let mut senses: Vec<String> = ranged_senses
+            .into_iter()
+            .filter_map(|(name, range)| Some(format!("{name} {} ft.", range?)))
+            .collect();
+        senses.push(format!("passive Perception {}", 10 + perception));
+        creature.insert("senses".to_string(), json!(senses.join(", ")));
+
+        let languages = self.languages().join(", ");
+        creature.insert("languages".to_string(), json!(languages));
+
+        let actions: Vec<Value> = self
+            .weapon_actions()
+            .into_iter()
+            .map(|action| {
+                let roll = action.damage_roll;
+                let damage_bonus = if roll.bonus == 0 {
+                    String::new()
+                } else if roll.bonus > 0 {
+                    format!(" + {}", roll.bonus)
+                } else {
+                    format!(" - {}", -roll.bonus)
+                };
+                json!({
+                    "name": action.name,
+                    "desc": format!(
+                        "Weapon Attack: {:+} to hit. Hit: {}d{}{} {} damage.",
+                        action.attack_bonus,
+                        roll.number,
+                        roll.dice,
+                        damage_bonus,
+                        roll.damage_type.to_string().to_lowercase()
+                    ),
+                    "attack_bonus": action.attack_bonus,
+                    "damage_dice": format!("{}d{}", roll.number, roll.dice),
+                    "damage_bonus": roll.bonus,
+                })
+            })
+            .collect();
+        creature.insert("actions".to_string(), json!(actions));
+
+        let spells = self.spells();
+        let spell_list: Vec<String> = spells
+            .iter()
+            .map(|(spell, _)| {
+                format!(
+                    "https://api.open5e.com/v1/spells/{}/",
+                    open5e_slug(&spell.name)
+                )
+            })
+            .collect();
+        let spell_objects: Vec<Value> = spells
+            .iter()
+            .map(|(spell, _)| {
+                let level = match spell.level {
+                    0 => "Cantrip".to_string(),
+                    n => format!("{}-level", ordinal(n)),
+                };
+                let yes_no = |b: bool| if b { "yes" } else { "no" };
+                let components = spell
+                    .components
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                json!({
+                    "name": spell.name,
+                    "slug": open5e_slug(&spell.name),
+                    "level_int": spell.level,
+                    "level": level,
+                    "school": spell.school.to_string().to_lowercase(),
+                    "concentration": yes_no(spell.concentration),
+                    "ritual": yes_no(spell.ritual),
+                    "components": components,
+                })
+            })
+            .collect();
+        creature.insert("spell_list".to_string(), json!(spell_list));
+        creature.insert("spells".to_string(), json!(spell_objects));
+
+        Value::Object(creature)
+    }
+}
+
+// open5e slugs are lowercase, with words seperated by dashes. e.g. "Acid Arrow" -> "acid-arrow"
+fn open5e_slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// e.g. 1 -> "1st", 2 -> "2nd". Only used for spell levels, so there's no need to handle 11-13.
+fn ordinal(n: usize) -> String {
+    match n {
+        1 => "1st".to_string(),
+        2 => "2nd".to_string(),
+        3 => "3rd".to_string(),
+        n => format!("{n}th"),
+    }
+}
@@ -214,6 +214,7 @@ async fn druid_wildshape() {
             level_up: false,
             class_specific_max: None,
             hard_max: Some(2),
+            class_specific_die: None,
         }
     );
 }
@@ -252,6 +253,7 @@ async fn barbarian_rage() {
             level_up: false,
             class_specific_max: Some("rage count".to_string()),
             hard_max: None,
+            class_specific_die: None,
         }
     );
 
@@ -352,3 +354,32 @@ async fn builder_test() {
         "Building character without stats should fail"
     );
 }
+
+#[tokio::test]
+async fn tiefling_racial_spells() {
+    let provider = provider();
+    let fighter = provider.get_class("fighter").await.unwrap();
+    let acolyte = provider.get_background("acolyte").await.unwrap();
+    let tiefling = provider.get_race("tiefling").await.unwrap();
+
+    let mut john = Character::new(
+        String::from("john"),
+        &fighter,
+        &acolyte,
+        &tiefling,
+        Stats::default(),
+    );
+    let names = |c: &Character| -> Vec<String> {
+        c.racial_spells.iter().map(|(s, _, _)| s.name.clone()).collect()
+    };
+    assert_eq!(names(&john), vec!["Thaumaturgy"]);
+
+    john.level_up_to_level(&fighter, 10);
+    assert_eq!(names(&john), vec!["Thaumaturgy", "Hellish Rebuke", "Darkness"]);
+    assert!(john.racial_spells.iter().all(|(_, s, _)| *s == StatType::Charisma));
+
+    assert!(john.cast_racial_spell("hellish rebuke"));
+    assert!(!john.cast_racial_spell("hellish rebuke"));
+    john.long_rest();
+    assert!(john.cast_racial_spell("hellish rebuke"));
+}
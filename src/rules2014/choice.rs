@@ -65,6 +65,26 @@ impl<T> PresentedOption<T> {
         }
     }
 
+    /// Replaces this `Choice` with the first child that matches the predicate.
+    ///
+    /// This is the same as [PresentedOption::choose_in_place], but doesn't depend on the order of
+    /// the options. Returns `false` if no child matches, or if this is already a `Base`.
+    ///
+    /// ```
+    /// use dnd_lib::rules2014::features::PresentedOption;
+    ///
+    /// let mut choice = PresentedOption::Choice(vec!["Apples", "Bananas", "Oranges"]);
+    /// assert!(!choice.choose_where(|fruit| fruit.starts_with('P')));
+    /// assert!(choice.choose_where(|fruit| fruit.starts_with('O')));
+    /// assert_eq!(choice, PresentedOption::Base("Oranges"));
+    /// ```
+    pub fn choose_where(&mut self, predicate: impl Fn(&T) -> bool) -> bool {
+        let index = self
+            .choices()
+            .and_then(|choices| choices.iter().position(predicate));
+        index.is_some_and(|index| self.choose_in_place(index))
+    }
+
     /// Returns the contained value if self is a `Base`, otherwise returns [None].
     pub fn as_base(&self) -> Option<&T> {
         match self {
@@ -97,6 +117,32 @@ impl<T> PresentedOption<T> {
         }
     }
 
+    /// Returns `true` if this is a `Base`, meaning there's nothing left to choose.
+    pub fn is_chosen(&self) -> bool {
+        matches!(self, PresentedOption::Base(_))
+    }
+
+    /// Returns the chosen value if this is a `Base`, otherwise returns [None].
+    ///
+    /// This is the same as [PresentedOption::as_base], named to pair with
+    /// [PresentedOption::is_chosen] and [PresentedOption::choices].
+    ///
+    /// ```
+    /// use dnd_lib::rules2014::features::PresentedOption;
+    ///
+    /// let mut choice = PresentedOption::Choice(vec!["Apples", "Bananas"]);
+    /// assert!(!choice.is_chosen());
+    /// assert_eq!(choice.choices(), Some(["Apples", "Bananas"].as_slice()));
+    ///
+    /// choice.choose_in_place(0);
+    /// assert!(choice.is_chosen());
+    /// assert_eq!(choice.chosen_value(), Some(&"Apples"));
+    /// assert_eq!(choice.choices(), None);
+    /// ```
+    pub fn chosen_value(&self) -> Option<&T> {
+        self.as_base()
+    }
+
     /// Maps a `PresentedOption<T>` to a `PresentedOption<U>`.
     pub fn map<U, F>(self, mut map_closure: F) -> PresentedOption<U>
     where
@@ -1,4 +1,5 @@
 use super::choice::{chosen, chosen_ref, split, PresentedOption};
+use super::stats::{SkillType, StatType};
 
 #[test]
 fn test_chosen() {
@@ -74,6 +75,23 @@ fn choose() {
     assert!(!result, "expected choose_in_place to fail on Base option");
 }
 
+#[test]
+fn choose_where() {
+    let mut skills = PresentedOption::Choice(vec![
+        SkillType::Acrobatics,
+        SkillType::Athletics,
+        SkillType::Stealth,
+    ]);
+    assert!(!skills.choose_where(|s| *s == SkillType::Arcana));
+    assert!(!skills.is_chosen());
+
+    assert!(skills.choose_where(|s| s.stat_type() == StatType::Strength));
+    assert_eq!(skills, PresentedOption::Base(SkillType::Athletics));
+
+    // already chosen
+    assert!(!skills.choose_where(|_| true));
+}
+
 #[test]
 fn as_base_tests() {
     let option1 = PresentedOption::Base("Base Option");
@@ -103,6 +121,21 @@ fn as_base_tests() {
     assert_eq!(option4.choices(), Some(vec![15, 20, 30].as_slice()));
 }
 
+#[test]
+fn chosen_value() {
+    let option1 = PresentedOption::Base("Base Option");
+    assert!(option1.is_chosen());
+    assert_eq!(option1.chosen_value(), Some(&"Base Option"));
+
+    let mut option2 = PresentedOption::Choice(vec!["Choice 1", "Choice 2"]);
+    assert!(!option2.is_chosen());
+    assert_eq!(option2.chosen_value(), None);
+
+    option2.choose_in_place(1);
+    assert!(option2.is_chosen());
+    assert_eq!(option2.chosen_value(), Some(&"Choice 2"));
+}
+
 #[test]
 fn map_base() {
     let option = PresentedOption::Base(10);
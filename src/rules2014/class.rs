@@ -5,6 +5,7 @@ use super::stats::{EquipmentProficiencies, SkillType, StatType};
 use heck::ToTitleCase;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use thiserror::Error;
 
 // unarmored movement
 pub(crate) const UNARMORED_MOVEMENT: [usize; 20] = [
@@ -32,6 +33,8 @@ pub struct Class {
     multiclassing_prerequisites_or: bool,
     multiclassing_proficiency_gain: EquipmentProficiencies,
     tracked_fields: Vec<TrackedField>,
+    #[serde(default)]
+    ability_priority: Vec<StatType>,
 }
 
 impl Class {
@@ -112,6 +115,14 @@ impl Class {
     pub fn tracked_fields(&self) -> &Vec<TrackedField> {
         &self.tracked_fields
     }
+    /// The class's key abilities, from most to least important.
+    ///
+    /// This is only a suggestion for where to put a character's best scores, and is used by
+    /// [Stats::assign_for_class](crate::rules2014::stats::Stats::assign_for_class). It may be
+    /// empty, or not list every stat.
+    pub fn ability_priority(&self) -> &Vec<StatType> {
+        &self.ability_priority
+    }
 
     /// gets the class's features up until a specific level.
     /// this returns every feature a class would have at the specified level
@@ -148,7 +159,7 @@ pub struct Subclass {
 /// Can be filled in by the user into the base ([ItemCategory::Item]) type.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ItemCategory {
-    Item(Item),
+    Item(Box<Item>),
     Weapon(WeaponType),
     Armor(ArmorCategory),
 }
@@ -187,6 +198,13 @@ pub struct TrackedField {
     /// A hard set maximum. If both class_specific_max and hard_max are set, then hard_max takes
     /// precedent.
     pub hard_max: Option<usize>,
+    /// A class specific field that gives the size of the die this resource is spent as, for
+    /// resources like superiority dice.
+    ///
+    /// For example, with Some("superiority die") and a class specific field of "superiority die"
+    /// that's "8" at level 3 and "10" at level 10, the resource is spent as a d8 and then a d10.
+    #[serde(default)]
+    pub class_specific_die: Option<String>,
 }
 
 impl TrackedField {
@@ -204,14 +222,57 @@ impl TrackedField {
     }
 }
 
+/// An error in building a [Class] with a [ClassBuilder].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum ClassBuildError {
+    #[error("Class name is required")]
+    MissingName,
+    #[error("Class features are required")]
+    MissingFeatures,
+    #[error("Class needs at least one feature at level 1")]
+    MissingLevelOneFeatures,
+    #[error("Class feature level must be between 1 and 20, not {0}")]
+    InvalidFeatureLevel(usize),
+    #[error("Class hit die is required")]
+    MissingHitDie,
+    #[error("Class hit die must be a d4, d6, d8, d10 or d12, not a d{0}")]
+    InvalidHitDie(usize),
+    #[error("Class skill proficiency choices are required")]
+    MissingSkillProficiencyChoices,
+}
+
 /// A builder for [Class].
 ///
 /// The following fields are required before building:
 /// - name
-/// - features
+/// - features, either with [ClassBuilder::set_features] or [ClassBuilder::add_feature_at_level],
+///   including at least one at level 1
 /// - hit_die
 /// - skill_proficiency_choices
-///  
+///
+/// ```
+/// use dnd_lib::rules2014::class::ClassBuilder;
+/// use dnd_lib::rules2014::features::Feature;
+/// use dnd_lib::rules2014::stats::SkillType;
+///
+/// let feature = |name: &str| Feature {
+///     name: name.to_string(),
+///     description: vec![],
+///     effects: vec![],
+/// };
+///
+/// let brawler = ClassBuilder::new()
+///     .name("brawler".to_string())
+///     .set_hit_die(10)
+///     .set_skill_proficiency_choices(1, vec![SkillType::Athletics])
+///     .add_feature_at_level(1, feature("Brawl"))
+///     .add_feature_at_level(5, feature("Extra Attack"))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(brawler.get_specific_features_at_level(5).len(), 1);
+/// assert!(brawler.get_specific_features_at_level(2).is_empty());
+/// ```
 pub struct ClassBuilder {
     name: Option<String>,
     subclasses: Vec<Subclass>,
@@ -227,6 +288,8 @@ pub struct ClassBuilder {
     multiclassing_prerequisites_or: bool,
     multiclassing_proficiency_gain: EquipmentProficiencies,
     tracked_fields: Vec<TrackedField>,
+    ability_priority: Vec<StatType>,
+    invalid_feature_level: Option<usize>,
 }
 
 impl ClassBuilder {
@@ -246,6 +309,8 @@ impl ClassBuilder {
             multiclassing_prerequisites_or: false,
             multiclassing_proficiency_gain: EquipmentProficiencies::default(),
             tracked_fields: vec![],
+            ability_priority: vec![],
+            invalid_feature_level: None,
         }
     }
 
@@ -274,6 +339,31 @@ impl ClassBuilder {
         self
     }
 
+    /// Adds a feature gained at the given level, from 1 to 20.
+    ///
+    /// Levels without any features added are left empty. If the level isn't between 1 and 20, the
+    /// feature isn't added and [ClassBuilder::build] returns
+    /// [ClassBuildError::InvalidFeatureLevel].
+    pub fn add_feature_at_level(self, level: usize, feature: Feature) -> Self {
+        self.add_feature_option_at_level(level, PresentedOption::Base(feature))
+    }
+
+    /// Adds a feature, or a choice between features, gained at the given level, from 1 to 20.
+    ///
+    /// See [ClassBuilder::add_feature_at_level] for what happens with an invalid level.
+    pub fn add_feature_option_at_level(
+        mut self,
+        level: usize,
+        feature: PresentedOption<Feature>,
+    ) -> Self {
+        if !(1..=20).contains(&level) {
+            self.invalid_feature_level.get_or_insert(level);
+            return self;
+        }
+        self.features.get_or_insert_with(Default::default)[level - 1].push(feature);
+        self
+    }
+
     /// Adds a beginning item to the list of beginning items.
     pub fn add_beginning_item(mut self, item: PresentedOption<Vec<(ItemCategory, usize)>>) -> Self {
         self.beginning_items.push(item);
@@ -386,17 +476,46 @@ impl ClassBuilder {
         self
     }
 
-    pub fn build(self) -> Result<Class, String> {
+    /// Sets the class's key abilities, from most to least important.
+    ///
+    /// See [Class::ability_priority].
+    pub fn set_ability_priority<T>(mut self, priority: T) -> Self
+    where
+        T: IntoIterator<Item = StatType>,
+    {
+        self.ability_priority = priority.into_iter().collect();
+        self
+    }
+
+    /// Builds the class, consuming the builder.
+    ///
+    /// # Errors
+    /// Returns a [ClassBuildError] if any required fields are missing or invalid.
+    pub fn build(self) -> Result<Class, ClassBuildError> {
+        use ClassBuildError::*;
+        let name = self.name.ok_or(MissingName)?;
+        if let Some(level) = self.invalid_feature_level {
+            return Err(InvalidFeatureLevel(level));
+        }
+        let features = self.features.ok_or(MissingFeatures)?;
+        if features[0].is_empty() {
+            return Err(MissingLevelOneFeatures);
+        }
+        let hit_die = self.hit_die.ok_or(MissingHitDie)?;
+        if !matches!(hit_die, 4 | 6 | 8 | 10 | 12) {
+            return Err(InvalidHitDie(hit_die));
+        }
+
         Ok(Class {
-            name: self.name.ok_or("Class name is required")?,
+            name,
             subclasses: self.subclasses,
-            features: self.features.ok_or("Class features are required")?,
+            features,
             beginning_items: self.beginning_items,
             saving_throw_proficiencies: self.saving_throw_proficiencies,
-            hit_die: self.hit_die.ok_or("Class hit die is required")?,
+            hit_die,
             skill_proficiency_choices: self
                 .skill_proficiency_choices
-                .ok_or("Class skill proficiency choices are required")?,
+                .ok_or(MissingSkillProficiencyChoices)?,
             equipment_proficiencies: self.equipment_proficiencies,
             spellcasting: self.spellcasting,
             class_specific_leveled: self.class_specific_leveled,
@@ -404,6 +523,7 @@ impl ClassBuilder {
             multiclassing_prerequisites_or: self.multiclassing_prerequisites_or,
             multiclassing_proficiency_gain: self.multiclassing_proficiency_gain,
             tracked_fields: self.tracked_fields,
+            ability_priority: self.ability_priority,
         })
     }
 }
@@ -454,6 +574,7 @@ mod tests {
             multiclassing_prerequisites_or: false,
             multiclassing_proficiency_gain: EquipmentProficiencies::default(),
             tracked_fields: vec![],
+            ability_priority: vec![],
         };
 
         let error_msg: &str = "failed to get correct class features";
@@ -486,14 +607,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn class_builder_errors() {
+        let feature = Feature {
+            name: "feature".to_string(),
+            description: vec![],
+            effects: vec![],
+        };
+        let builder = || {
+            ClassBuilder::new()
+                .name("test class".to_string())
+                .set_hit_die(8)
+                .set_skill_proficiency_choices(1, vec![SkillType::Investigation])
+        };
+
+        let class = builder()
+            .add_feature_at_level(1, feature.clone())
+            .add_feature_at_level(3, feature.clone())
+            .build()
+            .unwrap();
+        assert_eq!(class.get_all_features_at_level(20).len(), 2);
+        assert_eq!(
+            class.get_specific_features_at_level(3),
+            &vec![PresentedOption::Base(feature.clone())]
+        );
+
+        assert_eq!(
+            builder().build().unwrap_err(),
+            ClassBuildError::MissingFeatures
+        );
+        assert_eq!(
+            builder()
+                .add_feature_at_level(1, feature.clone())
+                .set_hit_die(7)
+                .build()
+                .unwrap_err(),
+            ClassBuildError::InvalidHitDie(7)
+        );
+        assert_eq!(
+            builder()
+                .add_feature_at_level(3, feature.clone())
+                .build()
+                .unwrap_err(),
+            ClassBuildError::MissingLevelOneFeatures
+        );
+        assert_eq!(
+            builder()
+                .add_feature_at_level(1, feature.clone())
+                .add_feature_at_level(21, feature.clone())
+                .add_feature_at_level(0, feature.clone())
+                .build()
+                .unwrap_err(),
+            ClassBuildError::InvalidFeatureLevel(21)
+        );
+        assert_eq!(
+            ClassBuilder::new()
+                .add_feature_at_level(1, feature)
+                .build()
+                .unwrap_err(),
+            ClassBuildError::MissingName
+        );
+    }
+
     #[test]
     fn item_formatting() {
-        let longbow = ItemCategory::Item(Item {
+        let longbow = ItemCategory::Item(Box::new(Item {
             name: "Longbow".to_string(),
             description: None,
+            weight: None,
+            attunement: None,
             features: vec![],
             item_type: ItemType::Misc,
-        });
+        }));
         let simple_weapon = ItemCategory::Weapon(WeaponType::Simple);
         let light_armor = ItemCategory::Armor(ArmorCategory::Light);
 
@@ -10,7 +10,8 @@
 use super::background::LanguageOption;
 
 use super::{
-    items::{Action, ArmorCategory, DamageRoll, WeaponType},
+    items::{Action, ArmorCategory, DamageRoll, DamageType, WeaponType},
+    spells::{RacialSpellUse, Spell},
     stats::{SkillType, StatType},
 };
 use serde::{Deserialize, Serialize};
@@ -60,6 +61,58 @@ impl AbilityScoreIncrease {
     pub fn set_stat_increase(&mut self, first: StatType, second: Option<StatType>) {
         *self = AbilityScoreIncrease::StatIncrease(Some(first), second);
     }
+
+    /// Returns `true` if a stat increase or a feature has been chosen.
+    pub fn is_chosen(&self) -> bool {
+        matches!(
+            self,
+            AbilityScoreIncrease::StatIncrease(Some(_), _)
+                | AbilityScoreIncrease::AddedFeature(Some(_))
+        )
+    }
+}
+
+/// Where a character's [Feature] came from. See
+/// [Character::features_with_source](crate::Character::features_with_source).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FeatureSource {
+    Class,
+    Subclass,
+    Race,
+    Subrace,
+    /// An equipped item.
+    Item,
+    /// A [Feat], or a feature taken in place of an ability score increase.
+    Feat,
+    /// The character's [bonus features](crate::Character::bonus_features).
+    Bonus,
+}
+
+/// A feat, like Grappler or War Caster.
+///
+/// A feat's mechanical effects are held in its [Feature]s. A character can only take a feat if
+/// they meet all of its [prerequisites](Prerequisite), see
+/// [Character::meets_prerequisites](crate::Character::meets_prerequisites).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feat {
+    pub name: String,
+    /// Requirements the character needs to meet to take the feat. Most feats don't have any.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The features the feat grants.
+    pub features: Vec<Feature>,
+}
+
+/// A requirement for taking a [Feat].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Prerequisite {
+    /// An ability score of at least this much, e.g. Strength 13 for Grappler.
+    MinAbility(StatType, usize),
+    /// Proficiency with an armor category, e.g. heavy armor for Heavy Armor Master.
+    Proficiency(ArmorCategory),
+    /// The ability to cast at least one spell.
+    Spellcasting,
+    /// A character level of at least this much.
+    MinLevel(usize),
 }
 
 /// An action granted by a feature.
@@ -81,6 +134,10 @@ pub struct CustomAction {
     pub damage_bonus_stats: Vec<StatType>,
     /// If proficiency is added to the damage
     pub add_prof_to_damage: bool,
+    /// A class resource spent to use the action, as the name of the
+    /// [TrackedField](super::class::TrackedField) and how much of it. e.g. `("Ki Points", 1)`.
+    #[serde(default)]
+    pub resource_cost: Option<(String, usize)>,
 }
 
 impl PartialEq for CustomAction {
@@ -97,6 +154,9 @@ pub struct ComputedCustomAction {
     pub name: String,
     pub attack_bonus: isize,
     pub damage_roll: DamageRoll,
+    /// See [CustomAction::resource_cost].
+    #[serde(default)]
+    pub resource_cost: Option<(String, usize)>,
 }
 
 impl Action for ComputedCustomAction {
@@ -139,6 +199,8 @@ pub enum FeatureEffect {
     AddSkillProficiency(SkillType),
     /// Adds a flat modifier to a specific skill
     AddSkillModifier(SkillType, isize),
+    /// Adds a flat modifier to the passive score of a skill, but not to rolls with it
+    PassiveBonus(SkillType, isize),
     /// Gives a flat bonus to AC
     ACBonus(isize),
     /// An ability score increase
@@ -152,14 +214,47 @@ pub enum FeatureEffect {
     UnarmoredDefense(isize, StatType, Option<StatType>),
     /// Grants expertise (adding proficiency a second time) in up to two different skills.
     Expertise([Option<SkillType>; 2]),
+    /// Adds half the proficiency bonus (rounded down) to skills the character isn't proficient
+    /// in, like the bard's Jack of All Trades.
+    JackOfAllTrades,
+    /// Adds half the proficiency bonus (rounded up) to strength, dexterity, and constitution
+    /// skills the character isn't proficient in, like the champion's Remarkable Athlete.
+    RemarkableAthlete,
+    /// Adds a flat modifier to ability checks with a stat, including skill checks and initiative
+    /// for dexterity.
+    AddAbilityCheckModifier(StatType, isize),
+    /// Adds the proficiency bonus to plain ability checks with a stat, the ones not made with a
+    /// skill.
+    AbilityCheckProficiency(StatType),
     /// Adds +1 HP for every character level
     LeveledHpIncrease,
+    /// Gives advantage on constitution saves to keep concentration, like the War Caster feat.
+    ConcentrationAdvantage,
+    /// Adds a flat bonus to the save DC of the character's class spells.
+    ///
+    /// If a class name is given, only that class's spells get the bonus, like a Rod of the Pact
+    /// Keeper's `SpellSaveDcBonus(1, Some("Warlock".to_string()))`.
+    SpellSaveDcBonus(isize, Option<String>),
+    /// Adds a flat bonus to the character's class spell attack rolls, like a Wand of the War Mage.
+    ///
+    /// Like [FeatureEffect::SpellSaveDcBonus], this can be limited to one class's spells.
+    SpellAttackBonus(isize, Option<String>),
+    /// Adds a flat bonus to initiative, like the +5 from the Alert feat.
+    InitiativeBonus(isize),
+    /// Gives advantage on initiative rolls.
+    InitiativeAdvantage,
+    /// Gives an extra bonus action every turn.
+    ///
+    /// See [Character::action_economy](crate::Character::action_economy).
+    ExtraBonusAction,
 
     /// Implements monk unarmored movement
     /// Shouldn't be added outside of monk, as it depends on monk level.
     UnarmoredMovement,
     /// Adds a flat bonus to your speed
     SpeedBonus(usize),
+    /// Sets a minimum walking speed. Your speed can't be lowered below this.
+    SpeedFloor(usize),
     /// Adds a flying speed to the character
     FlyingSpeed(usize),
     /// Adds a hovering speed to the character
@@ -171,12 +266,41 @@ pub enum FeatureEffect {
     /// Adds a swimming speed to the character
     SwimmingSpeed(usize),
 
+    /// Grants darkvision out to a range in feet.
+    ///
+    /// See [Character::senses](crate::Character::senses).
+    Darkvision(usize),
+    /// Grants blindsight out to a range in feet.
+    Blindsight(usize),
+    /// Grants tremorsense out to a range in feet.
+    Tremorsense(usize),
+
     /// An extra damage roll added by a feature. It doesn't need to be a damage roll, it can just
     /// be an extra damage (e.g. bonus 1d6 poison damage on melee attack)
     CustomAction(CustomAction),
 
+    /// Takes half damage (rounded down) of a damage type.
+    ///
+    /// See [Character::damage_typed](crate::Character::damage_typed).
+    Resistance(DamageType),
+    /// Takes no damage of a damage type.
+    Immunity(DamageType),
+    /// Takes double damage of a damage type.
+    Vulnerability(DamageType),
+
     /// Grants an extra language
     AddedLanguage(LanguageOption),
+
+    /// Grants a spell that's cast with its own ability instead of through a class, like a
+    /// tiefling's Hellish Rebuke. The spell is granted once the character reaches `level`.
+    ///
+    /// See [Character::racial_spells](crate::Character::racial_spells).
+    RacialSpell {
+        spell: Box<Spell>,
+        ability: StatType,
+        usage: RacialSpellUse,
+        level: usize,
+    },
 }
 
 #[cfg(test)]
@@ -200,6 +324,7 @@ mod tests {
             },
             damage_bonus_stats: vec![],
             add_prof_to_damage: false,
+            resource_cost: None,
         }
     }
 
@@ -213,6 +338,7 @@ mod tests {
                 bonus: 0,
                 damage_type: DamageType::Cold,
             },
+            resource_cost: None,
         }
     }
 
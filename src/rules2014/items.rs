@@ -1,6 +1,7 @@
 //! D&D items, item types, and damage types.
 use std::cmp::PartialEq;
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use super::{features::Feature, stats::EquipmentProficiencies};
@@ -125,6 +126,44 @@ pub enum DamageType {
     Thunder,
 }
 
+/// The damage types a creature resists, is immune to, or is vulnerable to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DamageDefenses {
+    pub resistances: Vec<DamageType>,
+    pub immunities: Vec<DamageType>,
+    pub vulnerabilities: Vec<DamageType>,
+}
+
+impl DamageDefenses {
+    /// Adjusts an amount of incoming damage of the given type.
+    ///
+    /// Immunity takes the damage to 0, resistance halves it (rounding down), and vulnerability
+    /// doubles it. Resistance and vulnerability to the same type cancel out.
+    ///
+    /// ```
+    /// use dnd_lib::rules2014::items::{DamageDefenses, DamageType};
+    ///
+    /// let defenses = DamageDefenses {
+    ///     resistances: vec![DamageType::Fire],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(defenses.adjust(9, DamageType::Fire), 4);
+    /// assert_eq!(defenses.adjust(9, DamageType::Cold), 9);
+    /// ```
+    pub fn adjust(&self, amount: usize, damage_type: DamageType) -> usize {
+        if self.immunities.contains(&damage_type) {
+            return 0;
+        }
+        let resistant = self.resistances.contains(&damage_type);
+        let vulnerable = self.vulnerabilities.contains(&damage_type);
+        match (resistant, vulnerable) {
+            (true, false) => amount / 2,
+            (false, true) => amount * 2,
+            _ => amount,
+        }
+    }
+}
+
 /// A general type an item could be.
 ///
 /// Shields are distinct from Armor, since they're calculated differently, and you may only receive
@@ -134,9 +173,48 @@ pub enum ItemType {
     Weapon(Weapon),
     Armor(Armor),
     Shield,
+    /// An item that's used up, like a potion. See
+    /// [Character::use_consumable](crate::Character::use_consumable).
+    Consumable(ConsumableEffect),
     Misc,
 }
 
+/// What happens when a consumable item is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConsumableEffect {
+    /// Heals whoever uses it by a roll, like a potion of healing's 2d4 + 2.
+    Healing {
+        number: usize,
+        dice: usize,
+        bonus: usize,
+    },
+}
+
+impl ConsumableEffect {
+    /// The effect of one of the SRD's potions of healing, by name, or [None] if it isn't one.
+    ///
+    /// | Potion | Healing |
+    /// |---|---|
+    /// | Potion of Healing | 2d4 + 2 |
+    /// | Potion of Greater Healing | 4d4 + 4 |
+    /// | Potion of Superior Healing | 8d4 + 8 |
+    /// | Potion of Supreme Healing | 10d4 + 20 |
+    pub fn healing_potion(name: &str) -> Option<ConsumableEffect> {
+        let (number, bonus) = match name.to_lowercase().as_str() {
+            "potion of healing" => (2, 2),
+            "potion of greater healing" => (4, 4),
+            "potion of superior healing" => (8, 8),
+            "potion of supreme healing" => (10, 20),
+            _ => return None,
+        };
+        Some(ConsumableEffect::Healing {
+            number,
+            dice: 4,
+            bonus,
+        })
+    }
+}
+
 /// A single item.
 ///
 /// If you want to be able to store multiple of the same item, use [ItemCount].
@@ -147,10 +225,28 @@ pub struct Item {
     pub description: Option<String>,
     /// What type of item this is (weapon, armor, etc).
     pub item_type: ItemType,
+    /// How much the item weighs in pounds, if it's known.
+    #[serde(default)]
+    pub weight: Option<f32>,
+    /// Whether the item needs attunement for its features to work.
+    ///
+    /// [None] means it doesn't, `Some(false)` means it does but the character hasn't attuned to
+    /// it, and `Some(true)` means they have. See
+    /// [Character::try_attune](crate::Character::try_attune).
+    #[serde(default)]
+    pub attunement: Option<bool>,
     /// Any extra features/effects this item grants
     pub features: Vec<Feature>,
 }
 
+impl Item {
+    /// Whether the item's features can apply, meaning it either doesn't need attunement or is
+    /// attuned. It still has to be equipped for them to apply.
+    pub fn attunement_satisfied(&self) -> bool {
+        self.attunement != Some(false)
+    }
+}
+
 /// An item along with a count of how many of that item there are.
 /// For example, 20 arrows, or 1 potion of healing.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -228,6 +324,27 @@ pub struct Weapon {
     pub attack_roll_bonus: usize,
     pub weapon_type: WeaponType,
     pub properties: WeaponProperties,
+    /// The normal and long range of a ranged weapon in feet, e.g. (80, 320) for a shortbow. [None]
+    /// for melee weapons.
+    #[serde(default)]
+    pub range: Option<(u32, u32)>,
+    /// The normal and long range of a thrown weapon in feet, e.g. (20, 60) for a dagger.
+    #[serde(default)]
+    pub thrown_range: Option<(u32, u32)>,
+}
+
+impl Weapon {
+    /// How far away the weapon can hit in melee, in feet. This is 10 for weapons with the reach
+    /// property, and 5 otherwise.
+    ///
+    /// Ranged weapons return [None], since they can't make melee attacks.
+    pub fn melee_reach(&self) -> Option<u32> {
+        match (self.range, self.properties.reach) {
+            (Some(_), _) => None,
+            (None, true) => Some(10),
+            (None, false) => Some(5),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -329,6 +446,123 @@ pub struct WeaponAction {
     pub damage_roll: DamageRoll,
     pub two_handed: bool,
     pub second_attack: bool,
+    /// The normal and long range of the attack in feet, for ranged and thrown weapons.
+    #[serde(default)]
+    pub range: Option<(u32, u32)>,
+    /// How far away the attack can hit in melee, in feet. [None] for ranged weapons.
+    #[serde(default = "default_reach")]
+    pub reach: Option<u32>,
+}
+
+fn default_reach() -> Option<u32> {
+    Some(5)
+}
+
+impl WeaponAction {
+    /// Whether a target at `distance` feet away can be hit by the attack.
+    ///
+    /// Targets within the attack's reach or normal range can be attacked normally. Targets past
+    /// the normal range but within the long range can be attacked with disadvantage, and anything
+    /// further is out of range. A thrown weapon can be used in melee or thrown, whichever reaches.
+    pub fn range_penalty_at(&self, distance: u32) -> RangeStatus {
+        let in_reach = self.reach.is_some_and(|reach| distance <= reach);
+        match self.range {
+            _ if in_reach => RangeStatus::Normal,
+            Some((normal, _)) if distance <= normal => RangeStatus::Normal,
+            Some((_, long)) if distance <= long => RangeStatus::LongRange,
+            _ => RangeStatus::OutOfRange,
+        }
+    }
+
+    /// Rolls a d20 for the attack and adds the attack bonus.
+    ///
+    /// With advantage or disadvantage, two d20s are rolled and the higher or lower is kept.
+    pub fn roll_attack<R: Rng>(&self, rng: &mut R, advantage: AdvantageState) -> AttackRollResult {
+        let natural = advantage.roll_d20(rng);
+        AttackRollResult {
+            natural,
+            total: natural + self.attack_bonus,
+            critical: natural == 20,
+            critical_fail: natural == 1,
+        }
+    }
+
+    /// Rolls the attack's damage. A critical hit doubles the damage dice, as in
+    /// [DamageRoll::critical].
+    pub fn roll_damage<R: Rng>(&self, rng: &mut R, critical: bool) -> isize {
+        match critical {
+            true => self.damage_roll.critical().roll(rng),
+            false => self.damage_roll.roll(rng),
+        }
+    }
+}
+
+/// Whether a d20 roll has advantage or disadvantage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AdvantageState {
+    #[default]
+    Normal,
+    /// Roll twice and keep the higher roll.
+    Advantage,
+    /// Roll twice and keep the lower roll.
+    Disadvantage,
+}
+
+impl AdvantageState {
+    /// Gets the state from whether there's any source of advantage and any source of
+    /// disadvantage. Having both cancels out to a normal roll.
+    pub fn from_sources(advantage: bool, disadvantage: bool) -> AdvantageState {
+        match (advantage, disadvantage) {
+            (true, false) => AdvantageState::Advantage,
+            (false, true) => AdvantageState::Disadvantage,
+            _ => AdvantageState::Normal,
+        }
+    }
+
+    /// Rolls a d20, keeping the higher or lower of two rolls with advantage or disadvantage.
+    pub fn roll_d20<R: Rng>(&self, rng: &mut R) -> isize {
+        let mut roll = || rng.random_range(1..=20i32) as isize;
+        match self {
+            AdvantageState::Normal => roll(),
+            AdvantageState::Advantage => roll().max(roll()),
+            AdvantageState::Disadvantage => roll().min(roll()),
+        }
+    }
+}
+
+/// The result of rolling an attack with [WeaponAction::roll_attack].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AttackRollResult {
+    /// The number rolled on the d20, before any bonus.
+    pub natural: isize,
+    /// The natural roll plus the attack bonus.
+    pub total: isize,
+    /// A natural 20, which always hits and is a critical hit.
+    pub critical: bool,
+    /// A natural 1, which always misses.
+    pub critical_fail: bool,
+}
+
+impl AttackRollResult {
+    /// Whether the attack hits a target with the given armor class.
+    pub fn hits(&self, ac: isize) -> bool {
+        match (self.critical, self.critical_fail) {
+            (true, _) => true,
+            (_, true) => false,
+            _ => self.total >= ac,
+        }
+    }
+}
+
+/// Whether a target can be hit by an attack at some distance. See [WeaponAction::range_penalty_at].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RangeStatus {
+    /// The target is within the attack's reach or normal range.
+    Normal,
+    /// The target is within the attack's long range, so the attack roll has disadvantage.
+    LongRange,
+    /// The target can't be attacked from here.
+    OutOfRange,
 }
 
 impl Action for WeaponAction {
@@ -353,6 +587,34 @@ impl DamageRoll {
         }
     }
 
+    /// The average result of the roll, including the bonus.
+    ///
+    /// e.g. 2d6+3 averages to 10.
+    pub fn average(&self) -> f64 {
+        self.number as f64 * (self.dice as f64 + 1.0) / 2.0 + self.bonus as f64
+    }
+
+    /// The roll for a critical hit, which doubles the number of dice but not the bonus.
+    ///
+    /// e.g. 2d6+3 becomes 4d6+3.
+    pub fn critical(&self) -> DamageRoll {
+        DamageRoll {
+            number: self.number * 2,
+            ..*self
+        }
+    }
+
+    /// Rolls the dice and adds the bonus.
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> isize {
+        if self.dice == 0 {
+            return self.bonus;
+        }
+        let dice_total: usize = (0..self.number)
+            .map(|_| rng.random_range(1..=self.dice))
+            .sum();
+        dice_total as isize + self.bonus
+    }
+
     /// Parses a string of the form "XdY" into a DamageRoll.
     ///
     /// For example, "2d10" would be turned into a DamageRoll with 2 dice and 10 faces.
@@ -361,10 +623,11 @@ impl DamageRoll {
         let number = a.parse().ok()?;
         let dice;
         let bonus;
-        if b.contains('+') || b.contains('-') {
-            let (c, d) = b.split_once(['+', '-'])?;
+        if let Some(i) = b.find(['+', '-']) {
+            let (c, d) = b.split_at(i);
             dice = c.parse().ok()?;
-            bonus = d.parse().ok()?;
+            // keeps the sign, so "1d4-1" has a bonus of -1
+            bonus = d.trim_start_matches('+').parse().ok()?;
         } else {
             dice = b.parse().ok()?;
             bonus = 0;
@@ -377,6 +640,135 @@ impl DamageRoll {
             damage_type,
         })
     }
+
+    /// Parses a full damage roll, like "2d6+3 slashing" or "4d10 fire damage".
+    ///
+    /// Spaces around the bonus are allowed. If there's no damage type, the roll is bludgeoning,
+    /// like an unarmed strike. Returns None if the string isn't a damage roll.
+    ///
+    /// ```
+    /// use dnd_lib::rules2014::items::{DamageRoll, DamageType};
+    ///
+    /// let roll = DamageRoll::parse("2d6 + 3 Slashing").unwrap();
+    /// assert_eq!(roll, DamageRoll::new(2, 6, 3, DamageType::Slashing));
+    /// assert_eq!(DamageRoll::parse("fire"), None);
+    /// ```
+    pub fn parse(s: &str) -> Option<DamageRoll> {
+        let mut words: Vec<&str> = s.split_whitespace().collect();
+        if words
+            .last()
+            .is_some_and(|w| w.eq_ignore_ascii_case("damage"))
+        {
+            words.pop();
+        }
+        let damage_type = match words.last().and_then(|w| w.parse().ok()) {
+            Some(damage_type) => {
+                words.pop();
+                damage_type
+            }
+            None => DamageType::Bludgeoning,
+        };
+        DamageRoll::from_str(&words.concat(), damage_type)
+    }
+}
+
+/// A pile of coins, like a character's money.
+///
+/// ```
+/// use dnd_lib::rules2014::items::Coins;
+///
+/// let mut purse = Coins {
+///     pp: 1,
+///     ..Default::default()
+/// };
+/// let cost = Coins {
+///     gp: 5,
+///     ..Default::default()
+/// };
+/// assert!(purse.try_spend(cost));
+/// assert_eq!(purse.gp, 5);
+/// assert_eq!(purse.total_in_gp(), 5.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Coins {
+    /// Copper pieces, worth 1/100 of a gold piece.
+    pub cp: usize,
+    /// Silver pieces, worth 1/10 of a gold piece.
+    pub sp: usize,
+    /// Electrum pieces, worth 1/2 of a gold piece.
+    pub ep: usize,
+    pub gp: usize,
+    /// Platinum pieces, worth 10 gold pieces.
+    pub pp: usize,
+}
+
+impl Coins {
+    /// The total value of the coins, in gold pieces.
+    pub fn total_in_gp(&self) -> f64 {
+        self.total_in_cp() as f64 / 100.0
+    }
+
+    /// Adds another pile of coins to this one, without converting any of them.
+    pub fn add(&mut self, other: Coins) {
+        self.cp += other.cp;
+        self.sp += other.sp;
+        self.ep += other.ep;
+        self.gp += other.gp;
+        self.pp += other.pp;
+    }
+
+    /// Spends coins worth the cost, returning false without spending anything if there isn't
+    /// enough.
+    ///
+    /// Only the total value has to match, so a cost in gold can be paid with silver. The largest
+    /// coins are spent first, and if a coin needs to be broken to pay the cost exactly, the
+    /// change is given back in smaller coins.
+    pub fn try_spend(&mut self, cost: Coins) -> bool {
+        let mut remaining = cost.total_in_cp();
+        if remaining > self.total_in_cp() {
+            return false;
+        }
+
+        for (count, value) in self.counts_mut().into_iter().rev() {
+            let spent = (*count).min(remaining / value);
+            *count -= spent;
+            remaining -= spent * value;
+        }
+        if remaining == 0 {
+            return true;
+        }
+
+        // every coin left is worth more than what's still owed, so the smallest one is broken
+        let (count, value) = self
+            .counts_mut()
+            .into_iter()
+            .find(|(count, _)| **count > 0)
+            .expect("there should be enough coins left to cover the cost");
+        *count -= 1;
+        let mut change = value - remaining;
+        self.pp += change / 1000;
+        change %= 1000;
+        self.gp += change / 100;
+        change %= 100;
+        self.sp += change / 10;
+        self.cp += change % 10;
+        true
+    }
+
+    fn total_in_cp(&self) -> usize {
+        self.cp + self.sp * 10 + self.ep * 50 + self.gp * 100 + self.pp * 1000
+    }
+
+    // each kind of coin with its value in copper, smallest first
+    fn counts_mut(&mut self) -> [(&mut usize, usize); 5] {
+        [
+            (&mut self.cp, 1),
+            (&mut self.sp, 10),
+            (&mut self.ep, 50),
+            (&mut self.gp, 100),
+            (&mut self.pp, 1000),
+        ]
+    }
 }
 
 /// An item that a character is holding, along with whether or not it's equipped.
@@ -483,6 +875,8 @@ mod tests {
             name: "Test Item".to_string(),
             description: None,
             item_type: ItemType::Misc,
+            weight: None,
+            attunement: None,
             features: vec![],
         };
         let item_count = ItemCount::from(base_item.clone());
@@ -534,6 +928,22 @@ mod tests {
         assert!(result);
     }
 
+    #[test]
+    fn proficient_with_weapon() {
+        let mut proficiencies = EquipmentProficiencies {
+            simple_weapons: true,
+            ..Default::default()
+        };
+        assert!(proficiencies.is_proficient_with_weapon(&WeaponType::Simple, "Club"));
+        assert!(!proficiencies.is_proficient_with_weapon(&WeaponType::Martial, "Longsword"));
+
+        proficiencies.other.insert("Longswords".to_string());
+        proficiencies.other.insert("Sunblade".to_string());
+        assert!(proficiencies.is_proficient_with_weapon(&WeaponType::Martial, "Longsword"));
+        assert!(proficiencies.is_proficient_with_weapon(&WeaponType::Martial, "sunblade"));
+        assert!(!proficiencies.is_proficient_with_weapon(&WeaponType::Martial, "Rapier"));
+    }
+
     #[test]
     fn weapon_actions() {
         let action = WeaponAction {
@@ -547,6 +957,8 @@ mod tests {
             },
             two_handed: false,
             second_attack: false,
+            range: None,
+            reach: Some(5),
         };
 
         assert_eq!(action.name(), "Longsword Attack");
@@ -562,12 +974,164 @@ mod tests {
         );
     }
 
+    #[test]
+    fn roll_attack() {
+        let action = WeaponAction {
+            name: "Longsword Attack".to_string(),
+            attack_bonus: 5,
+            damage_roll: DamageRoll::new(1, 8, 3, DamageType::Slashing),
+            two_handed: false,
+            second_attack: false,
+            range: None,
+            reach: Some(5),
+        };
+
+        let mut rng = rand::rng();
+        for advantage in [
+            AdvantageState::Normal,
+            AdvantageState::Advantage,
+            AdvantageState::Disadvantage,
+        ] {
+            for _ in 0..100 {
+                let roll = action.roll_attack(&mut rng, advantage);
+                assert!((1..=20).contains(&roll.natural));
+                assert_eq!(roll.total, roll.natural + 5);
+                assert_eq!(roll.critical, roll.natural == 20);
+                assert_eq!(roll.critical_fail, roll.natural == 1);
+            }
+        }
+
+        // with enough rolls, advantage should average higher than disadvantage
+        let average = |advantage: AdvantageState, rng: &mut _| {
+            (0..1000).map(|_| advantage.roll_d20(rng)).sum::<isize>() / 1000
+        };
+        assert!(
+            average(AdvantageState::Advantage, &mut rng)
+                > average(AdvantageState::Disadvantage, &mut rng)
+        );
+        assert_eq!(
+            AdvantageState::from_sources(true, true),
+            AdvantageState::Normal
+        );
+        assert_eq!(
+            AdvantageState::from_sources(false, true),
+            AdvantageState::Disadvantage
+        );
+
+        let hit = |natural, total| AttackRollResult {
+            natural,
+            total,
+            critical: natural == 20,
+            critical_fail: natural == 1,
+        };
+        assert!(hit(20, 25).hits(30));
+        assert!(!hit(1, 6).hits(5));
+        assert!(hit(10, 15).hits(15));
+        assert!(!hit(9, 14).hits(15));
+
+        for _ in 0..100 {
+            assert!((4..=11).contains(&action.roll_damage(&mut rng, false)));
+            assert!((5..=19).contains(&action.roll_damage(&mut rng, true)));
+        }
+    }
+
+    #[test]
+    fn damage_roll() {
+        let roll = DamageRoll::new(2, 6, 3, DamageType::Fire);
+        let critical = roll.critical();
+        assert_eq!(critical, DamageRoll::new(4, 6, 3, DamageType::Fire));
+        assert_eq!(roll.average(), 10.0);
+        assert_eq!(critical.average(), 17.0);
+
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let rolled = critical.roll(&mut rng);
+            assert!((7..=27).contains(&rolled));
+        }
+        assert_eq!(DamageRoll::new(0, 0, 2, DamageType::Fire).roll(&mut rng), 2);
+    }
+
+    #[test]
+    fn parse_damage_roll() {
+        assert_eq!(
+            DamageRoll::parse("1d8"),
+            Some(DamageRoll::new(1, 8, 0, DamageType::Bludgeoning))
+        );
+        assert_eq!(
+            DamageRoll::parse("2d6+3 slashing"),
+            Some(DamageRoll::new(2, 6, 3, DamageType::Slashing))
+        );
+        assert_eq!(
+            DamageRoll::parse("4d10 fire damage"),
+            Some(DamageRoll::new(4, 10, 0, DamageType::Fire))
+        );
+        assert_eq!(
+            DamageRoll::parse("1d4 - 1 Piercing"),
+            Some(DamageRoll::new(1, 4, -1, DamageType::Piercing))
+        );
+
+        assert_eq!(DamageRoll::parse(""), None);
+        assert_eq!(DamageRoll::parse("slashing"), None);
+        assert_eq!(DamageRoll::parse("2d6 banana"), None);
+        assert_eq!(DamageRoll::parse("2d6+three fire"), None);
+    }
+
+    #[test]
+    fn coins() {
+        let mut purse = Coins {
+            cp: 5,
+            sp: 3,
+            gp: 2,
+            ..Default::default()
+        };
+        assert_eq!(purse.total_in_gp(), 2.35);
+        purse.add(Coins {
+            ep: 1,
+            ..Default::default()
+        });
+        assert_eq!(purse.total_in_gp(), 2.85);
+
+        // too expensive
+        let cost = Coins {
+            gp: 3,
+            ..Default::default()
+        };
+        assert!(!purse.try_spend(cost));
+        assert_eq!(purse.total_in_gp(), 2.85);
+
+        let cost = Coins {
+            sp: 7,
+            ..Default::default()
+        };
+        assert!(purse.try_spend(cost));
+        assert_eq!(purse.total_in_gp(), 2.15);
+
+        // the silver and copper isn't enough, so a gold piece is broken
+        let cost = Coins {
+            sp: 3,
+            ..Default::default()
+        };
+        assert!(purse.try_spend(cost));
+        assert_eq!(
+            purse,
+            Coins {
+                cp: 5,
+                sp: 8,
+                ep: 0,
+                gp: 1,
+                pp: 0,
+            }
+        );
+    }
+
     #[test]
     fn held_equipment() {
         let base_item = Item {
             name: "Shield".to_string(),
             description: None,
             item_type: ItemType::Shield,
+            weight: None,
+            attunement: None,
             features: vec![],
         };
 
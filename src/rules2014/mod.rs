@@ -6,12 +6,15 @@ pub mod background;
 mod choice;
 pub mod features;
 pub mod items;
+pub mod monster;
 mod race;
 pub mod spells;
 pub mod stats;
 pub use race::*;
 mod character_builder;
+mod character_editable;
 mod character_etc;
+mod character_export;
 pub mod class;
 pub mod player_character;
 
@@ -26,3 +29,9 @@ mod stats_tests;
 
 #[cfg(test)]
 mod choice_tests;
+
+#[cfg(test)]
+mod player_character_tests;
+
+#[cfg(test)]
+pub(crate) mod test_fixtures;
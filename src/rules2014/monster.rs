@@ -0,0 +1,157 @@
+//! Monsters and other creatures that aren't player characters.
+use serde::{Deserialize, Serialize};
+
+use super::items::{DamageDefenses, DamageType, WeaponAction};
+use super::stats::{Modifiers, Size, SkillProficiencies, SkillType, Speeds, StatType, Stats};
+
+/// A monster, or any other creature that isn't built from a class, race, and background.
+///
+/// Unlike a [Character](crate::Character), nothing here is calculated from features. A stat block
+/// is written out directly, the same way it is in the Monster Manual, so its attacks are stored as
+/// finished [WeaponAction]s and its saves and skills as flat bonuses.
+///
+/// ```
+/// use dnd_lib::rules2014::monster::StatBlock;
+/// use dnd_lib::rules2014::items::DamageType;
+/// use dnd_lib::rules2014::stats::Stats;
+///
+/// let mut goblin = StatBlock::new("Goblin", Stats::from(&[8, 14, 10, 10, 8, 8]), 15, 7);
+/// goblin.defenses.vulnerabilities.push(DamageType::Fire);
+///
+/// assert_eq!(goblin.initiative(), 2);
+/// assert!(goblin.damage_typed(4, DamageType::Fire));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatBlock {
+    pub name: String,
+    pub size: Size,
+    /// The creature's ability scores.
+    pub stats: Stats,
+    pub ac: isize,
+    pub max_hp: usize,
+    pub hp: usize,
+    pub temp_hp: usize,
+    pub speeds: Speeds,
+    /// The attacks the creature can make, with their bonuses already included.
+    pub attacks: Vec<WeaponAction>,
+    /// Saving throw bonuses, e.g. `(StatType::Dexterity, 5)`. Saves not listed here use the
+    /// ability modifier.
+    pub saving_throws: Vec<(StatType, isize)>,
+    /// Skill bonuses, e.g. `(SkillType::Stealth, 6)`. Skills not listed here use the ability
+    /// modifier.
+    pub skills: Vec<(SkillType, isize)>,
+    /// The challenge rating. Fractional ratings like 1/4 are stored as decimals.
+    pub challenge_rating: f32,
+    pub defenses: DamageDefenses,
+}
+
+impl StatBlock {
+    /// Creates a medium creature with no attacks, bonuses, or defenses, a walking speed of 30
+    /// feet, and a challenge rating of 0.
+    pub fn new(name: &str, stats: Stats, ac: isize, max_hp: usize) -> StatBlock {
+        StatBlock {
+            name: name.to_string(),
+            size: Size::default(),
+            stats,
+            ac,
+            max_hp,
+            hp: max_hp,
+            temp_hp: 0,
+            speeds: Speeds::default(),
+            attacks: vec![],
+            saving_throws: vec![],
+            skills: vec![],
+            challenge_rating: 0.0,
+            defenses: DamageDefenses::default(),
+        }
+    }
+
+    /// The creature's ability modifiers.
+    pub fn modifiers(&self) -> Modifiers {
+        self.stats.modifiers()
+    }
+
+    /// The proficiency bonus for the creature's challenge rating.
+    pub fn proficiency_bonus(&self) -> isize {
+        let cr = self.challenge_rating.max(0.0) as isize;
+        (2 + (cr - 1).max(0) / 4).min(9)
+    }
+
+    /// The modifier for a saving throw of the given type.
+    pub fn save_mod(&self, stat: StatType) -> isize {
+        self.saving_throws
+            .iter()
+            .find(|(s, _)| *s == stat)
+            .map(|(_, bonus)| *bonus)
+            .unwrap_or(self.modifiers().stats[stat])
+    }
+
+    /// The modifier for an ability check with the given skill.
+    pub fn skill_mod(&self, skill: SkillType) -> isize {
+        self.skills
+            .iter()
+            .find(|(s, _)| *s == skill)
+            .map(|(_, bonus)| *bonus)
+            .unwrap_or_else(|| SkillProficiencies::default().modifiers(&self.stats, 0)[skill])
+    }
+
+    /// The creature's initiative modifier, which is its dexterity modifier.
+    pub fn initiative(&self) -> isize {
+        self.modifiers().stats.dexterity
+    }
+
+    /// Processes the creature taking damage, without any damage type.
+    ///
+    /// Temporary hit points are spent first. If the creature's hp reaches 0, this returns true.
+    pub fn damage(&mut self, damage: usize) -> bool {
+        let absorbed = damage.min(self.temp_hp);
+        self.temp_hp -= absorbed;
+        self.hp = self.hp.saturating_sub(damage - absorbed);
+        self.hp == 0
+    }
+
+    /// Processes the creature taking damage of a type, after its resistances, immunities, and
+    /// vulnerabilities. See [DamageDefenses::adjust].
+    pub fn damage_typed(&mut self, damage: usize, damage_type: DamageType) -> bool {
+        self.damage(self.defenses.adjust(damage, damage_type))
+    }
+
+    /// Heals the creature, up to its maximum hp.
+    pub fn heal(&mut self, amount: usize) {
+        self.hp = (self.hp + amount).min(self.max_hp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stat_block() {
+        let mut ogre = StatBlock::new("Ogre", Stats::from(&[19, 8, 16, 5, 7, 7]), 11, 59);
+        ogre.challenge_rating = 2.0;
+        ogre.saving_throws.push((StatType::Constitution, 5));
+        ogre.defenses.resistances.push(DamageType::Cold);
+        ogre.defenses.vulnerabilities.push(DamageType::Cold);
+        ogre.defenses.immunities.push(DamageType::Poison);
+
+        assert_eq!(ogre.proficiency_bonus(), 2);
+        assert_eq!(ogre.save_mod(StatType::Constitution), 5);
+        assert_eq!(ogre.save_mod(StatType::Dexterity), -1);
+        assert_eq!(ogre.skill_mod(SkillType::Athletics), 4);
+        assert_eq!(ogre.initiative(), -1);
+
+        // resistance and vulnerability cancel out
+        ogre.damage_typed(10, DamageType::Cold);
+        assert_eq!(ogre.hp, 49);
+        ogre.damage_typed(10, DamageType::Poison);
+        assert_eq!(ogre.hp, 49);
+
+        ogre.temp_hp = 5;
+        assert!(!ogre.damage(10));
+        assert_eq!((ogre.hp, ogre.temp_hp), (44, 0));
+        ogre.heal(100);
+        assert_eq!(ogre.hp, 59);
+        assert!(ogre.damage(100));
+    }
+}
@@ -13,16 +13,24 @@
 //! to represent the number of "wildcard" languages, (language options that can be anything) while
 //! the [SpeccedRace] has a `Vec<Option<String>>` to represent the actual languages chosen.
 
-pub use super::{character_builder::CharacterBuilder, character_etc::*};
+pub use super::{
+    character_builder::CharacterBuilder,
+    character_editable::{EditableCharacter, EditableCharacterError, EditableClass, EditableItem},
+    character_etc::*,
+};
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use thiserror::Error;
 
 use super::{
     background::{Background, LanguageOption},
     choice::chosen_ref,
     class::ItemCategory,
-    items::{is_proficient_with, ArmorCategory, HeldEquipment, Item},
+    items::{ArmorCategory, ConsumableEffect, HeldEquipment, Item},
     spells::{SpellCastingPreperation, SpellsAvailable},
     Subrace,
 };
@@ -30,18 +38,23 @@ use super::{
 use super::choice::chosen;
 use super::class::{Class, Subclass, TrackedField, UNARMORED_MOVEMENT};
 use super::features::{
-    AbilityScoreIncrease, ComputedCustomAction, CustomAction, Feature, FeatureEffect,
-    PresentedOption,
+    AbilityScoreIncrease, ComputedCustomAction, CustomAction, Feat, Feature, FeatureEffect,
+    FeatureSource, Prerequisite, PresentedOption,
+};
+use super::items::{
+    Coins, DamageDefenses, DamageRoll, DamageType, ItemCount, ItemType, Weapon, WeaponAction,
+    WeaponType,
 };
-use super::items::{DamageRoll, DamageType, ItemCount, ItemType, Weapon, WeaponAction, WeaponType};
+use super::monster::StatBlock;
 use super::race::Race;
 use super::spells::{
-    PactSlots, Spell, SpellAction, SpellCasterType, SpellSlots, Spellcasting, CASTER_SLOTS,
-    PACT_CASTING_SLOTS,
+    MysticArcanum, PactSlots, RacialSpellUse, SlotPool, Spell, SpellAction, SpellCasterType,
+    SpellMechanic, SpellSlots, Spellcasting, CASTER_SLOTS, MYSTIC_ARCANUM_LEVELS,
+    PACT_CASTING_SLOTS, SORCERY_POINT_SLOT_COSTS,
 };
 use super::stats::{
-    EquipmentProficiencies, Modifiers, Saves, SkillModifiers, SkillProficiencies, SkillType,
-    Speeds, StatType, Stats, PROFICIENCY_BY_LEVEL,
+    EquipmentProficiencies, Modifiers, ProficiencyTier, Saves, Senses, SkillModifiers,
+    SkillProficiencies, SkillType, Speeds, StatType, Stats, PROFICIENCY_BY_LEVEL, XP_BY_LEVEL,
 };
 
 /// A struct to represent a Dungeons and Dragons character.
@@ -164,10 +177,30 @@ pub struct Character {
     pub available_spell_slots: Option<SpellSlots>,
     /// Lists active pact magic slots. These can be spent. Seperate from regular spell slots.
     pub available_pact_slots: Option<PactSlots>,
+    /// Mystic Arcanum for each spell level from 6 to 9. See [Character::mystic_arcana].
+    #[serde(default)]
+    mystic_arcana: [MysticArcanum; 4],
+    /// Spells the character can cast without a class, like a tiefling's Infernal Legacy, along
+    /// with the ability they're cast with and how often they can be cast.
+    ///
+    /// These are added from [FeatureEffect::RacialSpell]s as the character reaches the level
+    /// for them. See [Character::cast_racial_spell].
+    #[serde(default)]
+    pub racial_spells: Vec<(Spell, StatType, RacialSpellUse)>,
     base_stats: Stats,
+    /// Temporary changes to the character's ability scores, such as from the Enlarge/Reduce spell
+    /// or a poison. These are added after every other calculation in [Character::stats], and
+    /// aren't bound by the usual maximum of 20.
+    ///
+    /// See [Character::apply_temp_stat_change] and [Character::clear_temp_stat_changes].
+    #[serde(default = "Stats::zero")]
+    pub temporary_stat_modifiers: Stats,
     /// Extra features from etc sources that aren't listed otherwise. Feel free to append on any
     /// extra feature you want your character to have.
     pub bonus_features: Vec<Feature>,
+    /// The feats the character has taken. See [Character::add_feat].
+    #[serde(default)]
+    pub feats: Vec<Feat>,
     /// The items the character has.
     pub items: Vec<HeldEquipment>,
     /// See the [documentation for items](Character#items).
@@ -191,6 +224,49 @@ pub struct Character {
     /// hit dice. This is the amount spent. The total amount is equal to the level, or
     /// [Character::level()]
     pub spent_hit_dice: usize,
+    // which sizes of hit dice were spent, by the number of faces. See
+    // [Character::available_hit_dice].
+    #[serde(default)]
+    spent_hit_dice_by_size: HashMap<usize, usize>,
+
+    /// The character's total experience points. Only needed for campaigns that level by XP,
+    /// see [Character::level_up_with_xp].
+    #[serde(default)]
+    pub xp: usize,
+
+    /// What the character has done so far on their current turn. Reset by
+    /// [Character::start_turn].
+    #[serde(default)]
+    pub turn: TurnState,
+
+    /// The character's death saving throws while they're at 0 hp. See
+    /// [Character::record_death_save].
+    #[serde(default)]
+    pub death_saves: DeathSaves,
+
+    /// The name of the spell the character is concentrating on, if any. See
+    /// [Character::start_concentration].
+    #[serde(default)]
+    pub concentration: Option<String>,
+
+    /// The character's level of exhaustion, from 0 to 6. See [Character::add_exhaustion].
+    #[serde(default)]
+    pub exhaustion: u8,
+
+    /// The conditions the character currently has, like poisoned or prone. See
+    /// [Character::add_condition].
+    #[serde(default)]
+    pub conditions: HashSet<Condition>,
+
+    /// The character's money. Starts with whatever their background gives.
+    #[serde(default)]
+    pub coins: Coins,
+
+    /// Whether the optional variant encumbrance rules are used for the character. Being
+    /// [encumbered](Character::encumbrance_level) only has an effect when this is on, which it
+    /// isn't by default.
+    #[serde(default)]
+    pub variant_encumbrance: bool,
 }
 
 impl Character {
@@ -203,18 +279,55 @@ impl Character {
         background: &Background,
         race: &Race,
         base_stats: Stats,
+    ) -> Character {
+        Self::new_in_class(name, class, background, race, base_stats, 1)
+    }
+
+    /// Builds a character that starts at the given level in the class, with base equipment from
+    /// the class and background.
+    ///
+    /// This is the same as [Character::new] followed by [Character::level_up_to_level], but it
+    /// takes all of the class's levels at once instead of one at a time. HP, spell slots and
+    /// class resources are all set for the starting level.
+    ///
+    /// Returns [None] if the level isn't between 1 and 20.
+    pub fn new_at_level(
+        name: String,
+        class: &Class,
+        background: &Background,
+        race: &Race,
+        base_stats: Stats,
+        level: usize,
+    ) -> Option<Character> {
+        if !(1..=20).contains(&level) {
+            return None;
+        }
+        Some(Self::new_in_class(name, class, background, race, base_stats, level))
+    }
+
+    fn new_in_class(
+        name: String,
+        class: &Class,
+        background: &Background,
+        race: &Race,
+        base_stats: Stats,
+        level: usize,
     ) -> Character {
         let mut new_character = Character {
             name,
-            classes: vec![SpeccedClass::from_class(class, 1)],
+            classes: vec![SpeccedClass::from_class(class, level)],
             items: vec![],
             unchosen_items: class.beginning_items().clone(),
             equipment_proficiencies: class.equipment_proficiencies().clone(),
             race: SpeccedRace::new(race),
             base_stats,
+            temporary_stat_modifiers: Stats::zero(),
             bonus_features: vec![],
+            feats: vec![],
             available_spell_slots: None,
             available_pact_slots: None,
+            mystic_arcana: Default::default(),
+            racial_spells: vec![],
             class_skill_proficiencies: vec![
                 class.skill_proficiency_choices().1.clone();
                 class.skill_proficiency_choices().0
@@ -228,6 +341,15 @@ impl Character {
             descriptors: CharacterDescriptors::default(),
             inspiration: false,
             spent_hit_dice: 0,
+            spent_hit_dice_by_size: HashMap::new(),
+            xp: 0,
+            turn: TurnState::default(),
+            death_saves: DeathSaves::default(),
+            concentration: None,
+            exhaustion: 0,
+            conditions: HashSet::new(),
+            coins: background.starting_coins(),
+            variant_encumbrance: false,
         };
 
         // add background items
@@ -244,7 +366,19 @@ impl Character {
 
         new_character.available_spell_slots = new_character.spell_slots();
         new_character.available_pact_slots = new_character.pact_slots();
+        new_character.update_racial_spells();
+
+        new_character
+    }
 
+    /// Makes a copy of this character under a new name.
+    ///
+    /// Everything else about the character, down to their items and spent resources, is copied
+    /// over. The copy is completely independent of the original, so changing one won't affect the
+    /// other. Useful for making a group of identical NPCs, or using a character as a template.
+    pub fn duplicate(&self, new_name: &str) -> Character {
+        let mut new_character = self.clone();
+        new_character.name = new_name.to_string();
         new_character
     }
 
@@ -296,6 +430,18 @@ impl Character {
         }
     }
 
+    /// Chooses the subclass with the given name (ignoring case) for the class at `class_index`.
+    ///
+    /// Returns false if there's no class at that index, its subclass is already chosen, or none of
+    /// its subclasses have that name.
+    pub fn choose_subclass_by_name(&mut self, class_index: usize, name: &str) -> bool {
+        self.classes.get_mut(class_index).is_some_and(|class| {
+            class
+                .subclass
+                .choose_where(|s| s.name.eq_ignore_ascii_case(name))
+        })
+    }
+
     /// Gets the list of unchosen item categories. This returns a list of tuples, where the first
     /// field is the index of the unchosen item option, the second field is the index of the
     /// choice within that option, and the third field is a reference to the item category.
@@ -334,7 +480,7 @@ impl Character {
         if let ItemCategory::Item(_) = category {
             return false;
         }
-        *category = ItemCategory::Item(item);
+        *category = ItemCategory::Item(Box::new(item));
         true
     }
 
@@ -343,7 +489,7 @@ impl Character {
             .iter()
             .filter_map(|v| {
                 if let ItemCategory::Item(i) = &v.0 {
-                    Some((i.clone(), v.1).into())
+                    Some((i.as_ref().clone(), v.1).into())
                 } else {
                     None
                 }
@@ -370,6 +516,11 @@ impl Character {
         PROFICIENCY_BY_LEVEL[self.clamped_level() - 1]
     }
 
+    /// Returns the character's ability scores before any bonuses, as they were rolled or bought.
+    pub fn base_stats(&self) -> Stats {
+        self.base_stats
+    }
+
     /// Returns the character's ability scores.
     ///
     /// Note that this isn't modifiers, but rather base scores.
@@ -377,7 +528,43 @@ impl Character {
     /// This takes the character's base stats, adds any increase from racial bonuses, and finally
     /// adds on any bonus from class ability score increases.
     pub fn stats(&self) -> Stats {
+        let (mut new_stats, _) = self.capped_stat_totals();
+
+        // used for features that can increase the total over 20
+        let extra_modifiers = self
+            .bonus_features
+            .iter()
+            .chain(self.item_features())
+            .flat_map(|v| &v.effects)
+            .filter_map(|v| match v {
+                FeatureEffect::AddModifierUncapped(stat, amount) => Some((stat, amount)),
+                _ => None,
+            });
+
+        for (stat, amount) in extra_modifiers {
+            let stat = new_stats.get_stat_type_mut(stat);
+            *stat += amount;
+        }
+
+        // temporary changes come last, so nothing above can cap them
+        new_stats + self.temporary_stat_modifiers
+    }
+
+    /// Returns the abilities that are already at 20, where another ability score increase would
+    /// be wasted.
+    ///
+    /// Only sources bound by the maximum of 20 are counted, so a score that's only over 20 because
+    /// of a [FeatureEffect::AddModifierUncapped] or a temporary change isn't listed.
+    pub fn capped_stats(&self) -> Vec<StatType> {
+        let (stats, _) = self.capped_stat_totals();
+        StatType::iter().filter(|s| stats[*s] >= 20).collect()
+    }
+
+    // The ability scores from the base stats, race, and every source capped at 20, along with the
+    // stats of any ability score increases that were wasted because the stat was already at 20.
+    fn capped_stat_totals(&self) -> (Stats, Vec<StatType>) {
         let mut new_stats = self.base_stats;
+        let mut wasted = vec![];
 
         for (race_stat_change, amount) in self.race.ability_bonuses.iter() {
             if let Some(s) = race_stat_change {
@@ -399,6 +586,8 @@ impl Character {
         let feature_effects = self
             .class_features()
             .into_iter()
+            .chain(self.ability_score_increase_features())
+            .chain(self.feat_features())
             .chain(self.bonus_features.iter())
             .flat_map(|v| &v.effects);
 
@@ -410,6 +599,8 @@ impl Character {
                     // we don't want to go over 20 through this.
                     if *new_stats.get_stat_type(s) < 20 {
                         *new_stats.get_stat_type_mut(s) += 1;
+                    } else {
+                        wasted.push(*s);
                     }
                 }
             };
@@ -430,23 +621,20 @@ impl Character {
             }
         }
 
-        // used for features that can increase the total over 20
-        let extra_modifiers = self
-            .bonus_features
-            .iter()
-            .chain(self.item_features())
-            .flat_map(|v| &v.effects)
-            .filter_map(|v| match v {
-                FeatureEffect::AddModifierUncapped(stat, amount) => Some((stat, amount)),
-                _ => None,
-            });
+        (new_stats, wasted)
+    }
 
-        for (stat, amount) in extra_modifiers {
-            let stat = new_stats.get_stat_type_mut(stat);
-            *stat += amount;
-        }
+    /// Temporarily changes one of the character's ability scores by `amount`. This stacks with any
+    /// other temporary changes.
+    ///
+    /// See [Character::temporary_stat_modifiers].
+    pub fn apply_temp_stat_change(&mut self, stat: StatType, amount: isize) {
+        self.temporary_stat_modifiers[stat] += amount;
+    }
 
-        new_stats
+    /// Removes every temporary change to the character's ability scores.
+    pub fn clear_temp_stat_changes(&mut self) {
+        self.temporary_stat_modifiers = Stats::zero();
     }
 
     /// Mutable version of [Character::ability_score_increases]
@@ -465,7 +653,7 @@ impl Character {
     }
 
     /// A helper for getting the ability scores increases of a character
-    pub fn ability_score_increases(&mut self) -> Vec<&AbilityScoreIncrease> {
+    pub fn ability_score_increases(&self) -> Vec<&AbilityScoreIncrease> {
         self.classes.iter()
             // iter over each class's feature list
             .flat_map(|v| v.current_class_features.iter())
@@ -483,6 +671,302 @@ impl Character {
             .collect()
     }
 
+    /// Returns the feature choices that haven't been made yet for a class at a specific level.
+    ///
+    /// `level` is the level in that class, starting from 1. Only options that are still a
+    /// [PresentedOption::Choice] are returned; an empty list means there's nothing left to choose
+    /// at that level, or that the class or level doesn't exist.
+    ///
+    /// To make a choice, see [Character::choose_feature].
+    pub fn feature_choices_at_level(
+        &self,
+        class_index: usize,
+        level: usize,
+    ) -> Vec<&PresentedOption<Feature>> {
+        self.classes
+            .get(class_index)
+            .and_then(|class| class.current_class_features.get(level.checked_sub(1)?))
+            .into_iter()
+            .flatten()
+            .filter(|option| !option.is_chosen())
+            .collect()
+    }
+
+    /// Chooses a feature for a class at a specific level.
+    ///
+    /// `option_slot` is the index of the choice in the list given by
+    /// [Character::feature_choices_at_level], and `choice_index` is the index of the feature
+    /// within that choice.
+    ///
+    /// Returns false if any index is out of bounds.
+    pub fn choose_feature(
+        &mut self,
+        class_index: usize,
+        level: usize,
+        option_slot: usize,
+        choice_index: usize,
+    ) -> bool {
+        let Some(level_features) = self
+            .classes
+            .get_mut(class_index)
+            .and_then(|class| class.current_class_features.get_mut(level.checked_sub(1)?))
+        else {
+            return false;
+        };
+
+        level_features
+            .iter_mut()
+            .filter(|option| !option.is_chosen())
+            .nth(option_slot)
+            .is_some_and(|option| option.choose_in_place(choice_index))
+    }
+
+    /// Chooses every option that only has one thing to choose from, like a race with a single
+    /// subrace, returning how many were chosen.
+    ///
+    /// This covers the subrace, race traits, each class's subclass and features, class and
+    /// background skills, and unchosen items. Options with more than one choice are left alone,
+    /// and so is a subclass until the class reaches the level it's chosen at.
+    /// Chosen items still need to be added with [Character::add_chosen_items].
+    pub fn auto_resolve_single_choices(&mut self) -> usize {
+        let mut resolved = usize::from(resolve_single_choice(&mut self.race.subraces));
+        for option in self.race.traits.iter_mut() {
+            resolved += usize::from(resolve_single_choice(option));
+        }
+        for class in self.classes.iter_mut() {
+            if class.reached_subclass_level() {
+                resolved += usize::from(resolve_single_choice(&mut class.subclass));
+            }
+            for option in class.current_class_features.iter_mut().flatten() {
+                resolved += usize::from(resolve_single_choice(option));
+            }
+        }
+        let skills = self
+            .class_skill_proficiencies
+            .iter_mut()
+            .chain(self.background.proficiencies.iter_mut());
+        for option in skills {
+            resolved += usize::from(resolve_single_choice(option));
+        }
+        for option in self.unchosen_items.iter_mut() {
+            resolved += usize::from(resolve_single_choice(option));
+        }
+        resolved
+    }
+
+    /// Every choice the character still has to make, so it can be shown as a checklist while
+    /// building a character.
+    ///
+    /// This covers the race, subrace, class, subclass, and background, including ability score
+    /// increases, skills, items, languages, and personality. An empty list means the character is
+    /// fully built. Choices with nothing to choose from aren't included, and neither is a
+    /// subclass until the class reaches the level it's chosen at.
+    pub fn pending_choices(&self) -> Vec<PendingChoice> {
+        // a choice with no options can't be made
+        fn unchosen<T>(option: &PresentedOption<T>) -> bool {
+            option.choices().is_some_and(|c| !c.is_empty())
+        }
+        let mut pending = vec![];
+
+        if unchosen(&self.race.subraces) {
+            pending.push(PendingChoice::Subrace);
+        }
+        for (index, (stat, _)) in self.race.ability_bonuses.iter().enumerate() {
+            if stat.is_none() {
+                pending.push(PendingChoice::RaceAbilityBonus { index });
+            }
+        }
+        for (index, option) in self.race.traits.iter().enumerate() {
+            if unchosen(option) {
+                pending.push(PendingChoice::RaceTrait { index });
+            }
+        }
+
+        for (class_index, class) in self.classes.iter().enumerate() {
+            if class.reached_subclass_level() && unchosen(&class.subclass) {
+                pending.push(PendingChoice::Subclass { class_index });
+            }
+            for (n, options) in class.current_class_features.iter().enumerate() {
+                let level = n + 1;
+                let unchosen_options = options.iter().filter(|o| unchosen(o)).count();
+                pending.extend((0..unchosen_options).map(|option_slot| {
+                    PendingChoice::ClassFeature {
+                        class_index,
+                        level,
+                        option_slot,
+                    }
+                }));
+                for (feature_index, feature) in options.iter().enumerate() {
+                    let Some(feature) = feature.as_base() else {
+                        continue;
+                    };
+                    for effect in feature.effects.iter() {
+                        match effect {
+                            FeatureEffect::AbilityScoreIncrease(a) if !a.is_chosen() => pending
+                                .push(PendingChoice::AbilityScoreIncrease {
+                                    class_index,
+                                    level,
+                                    feature_index,
+                                }),
+                            FeatureEffect::Expertise([None, _] | [_, None]) => {
+                                pending.push(PendingChoice::Expertise {
+                                    class_index,
+                                    level,
+                                    feature_index,
+                                })
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+        }
+
+        for (slot, option) in self.class_skill_proficiencies.iter().enumerate() {
+            if unchosen(option) {
+                pending.push(PendingChoice::ClassSkill { slot });
+            }
+        }
+        for (index, option) in self.background.proficiencies.iter().enumerate() {
+            if unchosen(option) {
+                pending.push(PendingChoice::BackgroundSkill { index });
+            }
+        }
+
+        for (index, option) in self.unchosen_items.iter().enumerate() {
+            if unchosen(option) {
+                pending.push(PendingChoice::Item { index });
+            }
+        }
+        for (index, choice_index, category) in self.get_unchosen_categories() {
+            if !matches!(category, ItemCategory::Item(_)) {
+                pending.push(PendingChoice::ItemCategory {
+                    index,
+                    choice_index,
+                });
+            }
+        }
+
+        pending.extend(
+            self.unchosen_languages()
+                .into_iter()
+                .map(PendingChoice::Language),
+        );
+
+        for kind in [
+            Personality::Trait,
+            Personality::Ideal,
+            Personality::Bond,
+            Personality::Flaw,
+        ] {
+            if unchosen(self.background.personality(kind)) {
+                pending.push(PendingChoice::Personality(kind));
+            }
+        }
+
+        pending
+    }
+
+    /// Every [Feature] (usually a feat) taken in place of an ability score increase.
+    ///
+    /// See [AbilityScoreIncrease::AddedFeature]. Increases used up by [Character::apply_feat]
+    /// are left out, since their features are in [Character::feat_features].
+    pub fn ability_score_increase_features(&self) -> Vec<&Feature> {
+        use AbilityScoreIncrease::AddedFeature;
+        self.class_features()
+            .into_iter()
+            .flat_map(|v| v.effects.iter())
+            .filter_map(|v| match v {
+                FeatureEffect::AbilityScoreIncrease(AddedFeature(Some(f))) => Some(f),
+                _ => None,
+            })
+            .filter(|f| {
+                !(f.effects.is_empty() && self.feats.iter().any(|feat| feat.name == f.name))
+            })
+            .collect()
+    }
+
+    /// Every [Feature] granted by the character's [feats](Character::feats).
+    pub fn feat_features(&self) -> Vec<&Feature> {
+        self.feats.iter().flat_map(|f| f.features.iter()).collect()
+    }
+
+    /// Returns true if the character meets every prerequisite of a feat.
+    pub fn meets_prerequisites(&self, feat: &Feat) -> bool {
+        let stats = self.stats();
+        let proficiencies = self.equipment_proficiencies();
+        feat.prerequisites.iter().all(|prerequisite| match prerequisite {
+            Prerequisite::MinAbility(stat, min) => stats[*stat] >= *min as isize,
+            Prerequisite::Proficiency(category) => {
+                proficiencies.is_proficient_with_armor(*category)
+            }
+            Prerequisite::Spellcasting => {
+                self.classes.iter().any(|c| c.spellcasting.is_some())
+                    || !self.racial_spells.is_empty()
+            }
+            Prerequisite::MinLevel(level) => self.level() >= *level,
+        })
+    }
+
+    /// Gives the character a feat, if they meet its prerequisites. See
+    /// [Character::meets_prerequisites].
+    ///
+    /// Returns false, without adding the feat, if the character doesn't meet them.
+    pub fn add_feat(&mut self, feat: Feat) -> bool {
+        if !self.meets_prerequisites(&feat) {
+            return false;
+        }
+        self.feats.push(feat);
+        true
+    }
+
+    /// Takes a feat in place of one of a class's ability score increases.
+    ///
+    /// `feature_index` counts only the class's ability score increases, in level order, starting
+    /// from 0 and skipping every other feature. For a fighter, 0 is the increase at level 4, 1
+    /// the one at level 6, 2 the one at level 8, and so on. It has to still be unchosen.
+    ///
+    /// The feat is added to [Character::feats], and the ability score increase becomes an
+    /// [AbilityScoreIncrease::AddedFeature] with the feat's name and no effects. It isn't
+    /// returned by [Character::ability_score_increase_features], so the feat's features are only
+    /// counted once.
+    ///
+    /// Returns false without changing anything if there's no such ability score increase, it's
+    /// already been chosen, or the character doesn't meet the feat's prerequisites.
+    pub fn apply_feat(&mut self, class_index: usize, feature_index: usize, feat: Feat) -> bool {
+        if !self.meets_prerequisites(&feat) {
+            return false;
+        }
+        let Some(class) = self.classes.get_mut(class_index) else {
+            return false;
+        };
+        let increase = class
+            .current_class_features
+            .iter_mut()
+            .flatten()
+            .filter_map(|v| v.as_base_mut())
+            .flat_map(|v| v.effects.iter_mut())
+            .filter_map(|v| match v {
+                FeatureEffect::AbilityScoreIncrease(a) => Some(a),
+                _ => None,
+            })
+            .nth(feature_index);
+        let Some(increase) = increase else {
+            return false;
+        };
+        if increase.is_chosen() {
+            return false;
+        }
+
+        *increase = AbilityScoreIncrease::AddedFeature(Some(Feature {
+            name: feat.name.clone(),
+            description: vec![],
+            effects: vec![],
+        }));
+        self.feats.push(feat);
+        true
+    }
+
     /// Returns the proficiencies the character has in each saving throw.
     ///
     /// This is not saving throw modifiers. For that, see [Character::save_mods].
@@ -531,6 +1015,13 @@ impl Character {
         base
     }
 
+    /// Returns if the character is proficient in the given saving throw.
+    ///
+    /// This is a shorthand for `character.saves().is_proficient(stat)`.
+    pub fn is_proficient_in_save(&self, stat: StatType) -> bool {
+        self.saves().is_proficient(stat)
+    }
+
     /// Returns the modifiers the character has in each saving throw.
     pub fn save_mods(&self) -> Modifiers {
         let mut modifiers = self
@@ -550,6 +1041,173 @@ impl Character {
         modifiers
     }
 
+    /// Returns the bonus to a saving throw to keep concentration on a spell.
+    ///
+    /// Concentration saves are constitution saves, so this is the same as the constitution save
+    /// modifier, and includes proficiency from features like Resilient.
+    pub fn concentration_save_bonus(&self) -> isize {
+        self.save_mods().stats.constitution
+    }
+
+    /// Returns true if the character has advantage on saving throws to keep concentration, from a
+    /// feature with [FeatureEffect::ConcentrationAdvantage] like War Caster.
+    pub fn concentration_save_advantage(&self) -> bool {
+        self.total_features()
+            .iter()
+            .flat_map(|f| f.effects.iter())
+            .any(|e| matches!(e, FeatureEffect::ConcentrationAdvantage))
+    }
+
+    /// Starts concentrating on a spell, ending concentration on whatever the character was
+    /// concentrating on before.
+    ///
+    /// Returns false without changing anything if the spell doesn't need concentration.
+    pub fn start_concentration(&mut self, spell: &Spell) -> bool {
+        if !spell.concentration {
+            return false;
+        }
+        self.concentration = Some(spell.name.clone());
+        true
+    }
+
+    /// Ends the character's concentration, like after failing a concentration save.
+    pub fn break_concentration(&mut self) {
+        self.concentration = None;
+    }
+
+    /// The DC of the constitution save to keep concentration after taking an amount of damage,
+    /// which is half the damage (rounded down) or 10, whichever is higher.
+    ///
+    /// ```
+    /// use dnd_lib::Character;
+    ///
+    /// assert_eq!(Character::concentration_save_dc(7), 10);
+    /// assert_eq!(Character::concentration_save_dc(35), 17);
+    /// ```
+    pub fn concentration_save_dc(damage: usize) -> isize {
+        (damage / 2).max(10) as isize
+    }
+
+    /// Saves the character's current state, so it can be brought back with
+    /// [Character::restore].
+    ///
+    /// This clones the whole character, which is fast to take and to restore, but keeps every
+    /// class, feature, item, and spell alive in memory. If you're keeping a lot of checkpoints,
+    /// like for an undo history, [Character::checkpoint_compact] is smaller.
+    pub fn checkpoint(&self) -> CharacterCheckpoint {
+        CharacterCheckpoint::Cloned(Box::new(self.clone()))
+    }
+
+    /// Saves the character's current state as serialized bytes, so it can be brought back with
+    /// [Character::restore].
+    ///
+    /// This takes less memory than [Character::checkpoint], since it's a single buffer instead
+    /// of many separate allocations, but it's slower, as the character needs to be serialized
+    /// now and deserialized when restoring.
+    pub fn checkpoint_compact(&self) -> CharacterCheckpoint {
+        let bytes = serde_json::to_vec(self).expect("characters can always be serialized");
+        CharacterCheckpoint::Serialized(bytes)
+    }
+
+    /// Reverts the character to the state it was in when the checkpoint was taken.
+    ///
+    /// The checkpoint isn't consumed, so it can be restored more than once. This only fails if a
+    /// [CharacterCheckpoint::Serialized] checkpoint's bytes were changed and can't be parsed, in
+    /// which case the character is left as it is.
+    ///
+    /// ```
+    /// # use dnd_lib::Character;
+    /// # fn example(character: &mut Character) {
+    /// let before_fireball = character.checkpoint();
+    /// character.damage(28);
+    /// // oops, that fireball missed
+    /// character.restore(&before_fireball).unwrap();
+    /// # }
+    /// ```
+    pub fn restore(&mut self, checkpoint: &CharacterCheckpoint) -> Result<(), serde_json::Error> {
+        *self = match checkpoint {
+            CharacterCheckpoint::Cloned(character) => character.as_ref().clone(),
+            CharacterCheckpoint::Serialized(bytes) => serde_json::from_slice(bytes)?,
+        };
+        Ok(())
+    }
+
+    /// Returns the saving throw the character has the lowest modifier in.
+    ///
+    /// Ties go to whichever comes first in [StatType] order (strength first, charisma last).
+    pub fn weakest_save(&self) -> StatType {
+        let save_mods = self.save_mods();
+        StatType::iter()
+            .min_by_key(|stat| save_mods.stats[*stat])
+            .expect("StatType has variants")
+    }
+
+    /// Returns the saving throw the character has the highest modifier in.
+    ///
+    /// Ties go to whichever comes first in [StatType] order (strength first, charisma last).
+    pub fn strongest_save(&self) -> StatType {
+        let save_mods = self.save_mods();
+        StatType::iter()
+            .min_by_key(|stat| -save_mods.stats[*stat])
+            .expect("StatType has variants")
+    }
+
+    /// Returns the saving throw with the lowest total modifier across a group of characters,
+    /// e.g. to pick the save for a spell that targets the whole group.
+    ///
+    /// Ties go to whichever comes first in [StatType] order. With no characters, this returns
+    /// strength.
+    pub fn weakest_save_among(characters: &[&Character]) -> StatType {
+        let save_mods: Vec<Modifiers> = characters.iter().map(|c| c.save_mods()).collect();
+        StatType::iter()
+            .min_by_key(|stat| save_mods.iter().map(|m| m.stats[*stat]).sum::<isize>())
+            .expect("StatType has variants")
+    }
+
+    /// Returns the indexes of the background's skill proficiencies that are still a choice.
+    ///
+    /// The indexes are in relation to all of the background's proficiencies, as listed in
+    /// [SpeccedBackground::proficiencies]. A background with only fixed proficiencies, like the
+    /// acolyte, has none.
+    pub fn pending_background_proficiencies(&self) -> Vec<usize> {
+        self.background
+            .proficiencies
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.is_chosen())
+            .map(|(n, _)| n)
+            .collect()
+    }
+
+    /// Chooses a skill for one of the background's proficiency choices.
+    ///
+    /// `index` is the index of the proficiency, as given by
+    /// [Character::pending_background_proficiencies], and `choice` is the index of the skill in
+    /// that choice.
+    ///
+    /// Returns false if either index is out of bounds, or if the proficiency isn't a choice.
+    pub fn choose_background_proficiency(&mut self, index: usize, choice: usize) -> bool {
+        self.background
+            .proficiencies
+            .get_mut(index)
+            .is_some_and(|p| p.choose_in_place(choice))
+    }
+
+    /// Chooses a skill for the first unchosen class skill proficiency that offers it, without
+    /// needing to know its index.
+    ///
+    /// Returns false if the skill is already chosen, if it isn't one of the class's options, or
+    /// if every class skill has already been chosen.
+    pub fn choose_class_skill(&mut self, skill: SkillType) -> bool {
+        choose_skill(&mut self.class_skill_proficiencies, skill)
+    }
+
+    /// Chooses a skill for the first unchosen background proficiency that offers it. This is the
+    /// same as [Character::choose_class_skill], for the background.
+    pub fn choose_background_skill(&mut self, skill: SkillType) -> bool {
+        choose_skill(&mut self.background.proficiencies, skill)
+    }
+
     /// Returns the proficiencies and expertise the character has in each skill.
     ///
     /// This is not the modifiers for each skill. For that, see [Character::skill_modifiers]
@@ -581,26 +1239,269 @@ impl Character {
         base
     }
 
-    /// Returns the modifiers the character has in each skill.
+    /// Returns how much of the proficiency bonus the character adds to a skill.
     ///
-    /// This calculates the base modifiers using the character's ability scores, finds the skills that the character are proficient in with [Character::skills], and adds the proficiency bonus to a skill if the character is proficient in it. (Proficiency is added twice if the character has proficiency and expertise)
-    pub fn skill_modifiers(&self) -> SkillModifiers {
-        let mut modifiers = self
-            .skills()
-            .modifiers(&self.stats(), self.proficiency_bonus());
+    /// Expertise beats proficiency, which beats half proficiency from features like
+    /// [Jack of All Trades](FeatureEffect::JackOfAllTrades). Expertise in a skill the character
+    /// isn't proficient in only counts as proficiency.
+    pub fn skill_proficiency_tier(&self, skill: SkillType) -> ProficiencyTier {
+        self.skill_proficiency_tier_with(&self.skills(), skill)
+    }
 
-        for effect in self.total_features().iter().flat_map(|t| t.effects.iter()) {
-            if let FeatureEffect::AddSkillModifier(t, n) = effect {
-                *modifiers.get_skill_type_mut(*t) += *n
+    fn skill_proficiency_tier_with(
+        &self,
+        skills: &SkillProficiencies,
+        skill: SkillType,
+    ) -> ProficiencyTier {
+        let proficiency = skills[skill];
+        match (proficiency.proficiency, proficiency.expertise) {
+            (true, true) => ProficiencyTier::Expertise,
+            (true, false) | (false, true) => ProficiencyTier::Full,
+            (false, false) if self.half_proficiency_bonus(skill.stat_type(), 2) > 0 => {
+                ProficiencyTier::Half
             }
+            (false, false) => ProficiencyTier::None,
         }
-
-        modifiers
     }
 
-    /// Returns a vector of references to every item marked as held.
-    ///
-    /// Just like for [Character::items], the first field in the tuple is the item, and the second
+    // half the proficiency bonus, if any feature gives it for checks with the stat. jack of all
+    // trades rounds down and remarkable athlete rounds up.
+    fn half_proficiency_bonus(&self, stat: StatType, proficiency_bonus: isize) -> isize {
+        let physical = matches!(
+            stat,
+            StatType::Strength | StatType::Dexterity | StatType::Constitution
+        );
+        self.total_features()
+            .iter()
+            .flat_map(|f| f.effects.iter())
+            .map(|effect| match effect {
+                FeatureEffect::JackOfAllTrades => proficiency_bonus / 2,
+                FeatureEffect::RemarkableAthlete if physical => (proficiency_bonus + 1) / 2,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the modifiers the character has in each skill.
+    ///
+    /// This calculates the base modifiers using the character's ability scores, and adds the
+    /// proficiency bonus to each skill based on its
+    /// [proficiency tier](Character::skill_proficiency_tier). (Proficiency is added twice if the
+    /// character has proficiency and expertise, and half for features like Jack of All Trades)
+    pub fn skill_modifiers(&self) -> SkillModifiers {
+        let skills = self.skills();
+        let stat_modifiers = self.stats().modifiers();
+        let proficiency_bonus = self.proficiency_bonus();
+
+        let mut modifiers = SkillModifiers::default();
+        for skill in SkillType::iter() {
+            let bonus = match self.skill_proficiency_tier_with(&skills, skill) {
+                ProficiencyTier::None => 0,
+                ProficiencyTier::Half => {
+                    self.half_proficiency_bonus(skill.stat_type(), proficiency_bonus)
+                }
+                ProficiencyTier::Full => proficiency_bonus,
+                ProficiencyTier::Expertise => proficiency_bonus * 2,
+            };
+            modifiers[skill] = stat_modifiers.stats[skill.stat_type()] + bonus;
+        }
+
+        for effect in self.total_features().iter().flat_map(|t| t.effects.iter()) {
+            match effect {
+                FeatureEffect::AddSkillModifier(t, n) => *modifiers.get_skill_type_mut(*t) += *n,
+                // skill checks are ability checks, so these apply to each of the stat's skills
+                FeatureEffect::AddAbilityCheckModifier(stat, n) => {
+                    for skill in SkillType::iter().filter(|s| s.stat_type() == *stat) {
+                        modifiers[skill] += *n;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        modifiers
+    }
+
+    /// Returns the modifier for a plain ability check with a stat, like a strength check to break
+    /// down a door. Skill checks use [Character::skill_modifiers] instead.
+    ///
+    /// This is the stat's modifier plus any [FeatureEffect::AddAbilityCheckModifier] for it. The
+    /// proficiency bonus is added if a feature has [FeatureEffect::AbilityCheckProficiency] for the
+    /// stat, and otherwise half of it is added for features like Jack of All Trades.
+    pub fn ability_check_mod(&self, stat: StatType) -> isize {
+        let features = self.total_features();
+        let effects = || features.iter().flat_map(|f| f.effects.iter());
+
+        let proficiency_bonus = self.proficiency_bonus();
+        let proficient = effects().any(|e| *e == FeatureEffect::AbilityCheckProficiency(stat));
+        let proficiency = if proficient {
+            proficiency_bonus
+        } else {
+            self.half_proficiency_bonus(stat, proficiency_bonus)
+        };
+        let bonus: isize = effects()
+            .map(|effect| match effect {
+                FeatureEffect::AddAbilityCheckModifier(s, n) if *s == stat => *n,
+                _ => 0,
+            })
+            .sum();
+        self.stats().modifiers().stats[stat] + proficiency + bonus
+    }
+
+    /// Returns the character's initiative modifier.
+    ///
+    /// Initiative is a dexterity check, so this is the
+    /// [dexterity check modifier](Character::ability_check_mod), which includes half proficiency
+    /// from features like Jack of All Trades. Any [FeatureEffect::InitiativeBonus] is added on
+    /// top.
+    pub fn initiative(&self) -> isize {
+        let dexterity_check = self.ability_check_mod(StatType::Dexterity);
+        let bonus: isize = self
+            .total_features()
+            .iter()
+            .flat_map(|f| f.effects.iter())
+            .map(|effect| match effect {
+                FeatureEffect::InitiativeBonus(n) => *n,
+                _ => 0,
+            })
+            .sum();
+        dexterity_check + bonus
+    }
+
+    /// Returns true if the character has advantage on initiative rolls, from a feature with
+    /// [FeatureEffect::InitiativeAdvantage].
+    pub fn initiative_advantage(&self) -> bool {
+        self.total_features()
+            .iter()
+            .flat_map(|f| f.effects.iter())
+            .any(|e| matches!(e, FeatureEffect::InitiativeAdvantage))
+    }
+
+    /// Returns the character's passive score in a skill, which is 10 plus their modifier.
+    ///
+    /// Passive scores are what a DM compares against when the character isn't actively rolling,
+    /// like a character's passive stealth against a guard's passive perception.
+    ///
+    /// Any [FeatureEffect::PassiveBonus] for the skill is added on top, like the +5 to passive
+    /// perception from Observant. If the character has disadvantage on checks with the skill (see
+    /// [Character::has_disadvantage_on]), this is 5 lower.
+    pub fn passive_skill(&self, skill: SkillType) -> isize {
+        let disadvantage = self.has_disadvantage_on(RollKind::AbilityCheck(skill.stat_type()));
+        let penalty = if disadvantage { 5 } else { 0 };
+        let bonus: isize = self
+            .total_features()
+            .iter()
+            .flat_map(|f| f.effects.iter())
+            .map(|effect| match effect {
+                FeatureEffect::PassiveBonus(s, n) if *s == skill => *n,
+                _ => 0,
+            })
+            .sum();
+        10 + self.skill_modifiers()[skill] + bonus - penalty
+    }
+
+    /// Returns the character's passive perception, which is what the DM checks to see if they
+    /// notice something hidden.
+    pub fn passive_perception(&self) -> isize {
+        self.passive_skill(SkillType::Perception)
+    }
+
+    /// Returns the character's passive deception, which is the DC to see through their lies
+    /// without rolling.
+    pub fn passive_deception(&self) -> isize {
+        self.passive_skill(SkillType::Deception)
+    }
+
+    /// Returns the character's passive stealth.
+    ///
+    /// If the character is wearing armor that gives disadvantage on stealth checks, this is 5
+    /// lower, as disadvantage is a -5 to passive scores. Disadvantage from other sources doesn't
+    /// stack with it.
+    pub fn passive_stealth(&self) -> isize {
+        let armor_disadvantage = self.equipped_items().iter().any(
+            |i| matches!(&i.item.item_type, ItemType::Armor(armor) if armor.stealth_disadvantage),
+        );
+        let other_disadvantage =
+            self.has_disadvantage_on(RollKind::AbilityCheck(StatType::Dexterity));
+        let penalty = if armor_disadvantage && !other_disadvantage {
+            5
+        } else {
+            0
+        };
+        self.passive_skill(SkillType::Stealth) - penalty
+    }
+
+    /// Whether the character has disadvantage on a kind of d20 roll.
+    ///
+    /// Currently, this comes from:
+    /// - Being [heavily encumbered](Encumbrance::HeavilyEncumbered), which gives disadvantage on
+    ///   ability checks, attack rolls, and saving throws that use strength, dexterity, or
+    ///   constitution. This is only with the [variant encumbrance](Character::variant_encumbrance)
+    ///   rules.
+    /// - [Exhaustion](Character::add_exhaustion), which gives disadvantage on ability checks at
+    ///   level 1, and on attack rolls and saving throws at level 3.
+    /// - [Conditions](Character::conditions). Being frightened or poisoned gives disadvantage on
+    ///   ability checks, being restrained gives disadvantage on dexterity saving throws, and see
+    ///   [Character::has_disadvantage_on_attacks] for attack rolls.
+    pub fn has_disadvantage_on(&self, roll: RollKind) -> bool {
+        let exhausted = match roll {
+            RollKind::AbilityCheck(_) => self.exhaustion >= 1,
+            RollKind::Attack(_) | RollKind::Save(_) => self.exhaustion >= 3,
+        };
+        let condition = match roll {
+            RollKind::AbilityCheck(_) => {
+                self.has_condition(Condition::Frightened) || self.has_condition(Condition::Poisoned)
+            }
+            RollKind::Attack(_) => self.has_disadvantage_on_attacks(),
+            RollKind::Save(stat) => {
+                stat == StatType::Dexterity && self.has_condition(Condition::Restrained)
+            }
+        };
+        let (RollKind::AbilityCheck(stat) | RollKind::Attack(stat) | RollKind::Save(stat)) = roll;
+        let physical = matches!(
+            stat,
+            StatType::Strength | StatType::Dexterity | StatType::Constitution
+        );
+        let encumbered =
+            self.variant_encumbrance && self.encumbrance_level() == Encumbrance::HeavilyEncumbered;
+        exhausted || condition || (physical && encumbered)
+    }
+
+    /// The total weight of everything the character is carrying, in pounds. Items without a
+    /// known [weight](Item::weight) are counted as weightless.
+    pub fn carried_weight(&self) -> f32 {
+        self.items
+            .iter()
+            .map(|h| h.item.weight.unwrap_or(0.0) * h.quantity as f32)
+            .sum()
+    }
+
+    /// The most weight the character can carry, in pounds, which is 15 times their strength
+    /// score.
+    pub fn carrying_capacity(&self) -> f32 {
+        self.stats().strength as f32 * 15.0
+    }
+
+    /// How weighed down the character is, using the variant encumbrance rules.
+    ///
+    /// Carrying more than 5 times their strength score in pounds makes them encumbered, and more
+    /// than 10 times makes them heavily encumbered.
+    pub fn encumbrance_level(&self) -> Encumbrance {
+        let strength = self.stats().strength as f32;
+        let weight = self.carried_weight();
+        if weight > strength * 10.0 {
+            Encumbrance::HeavilyEncumbered
+        } else if weight > strength * 5.0 {
+            Encumbrance::Encumbered
+        } else {
+            Encumbrance::Unencumbered
+        }
+    }
+
+    /// Returns a vector of references to every item marked as held.
+    ///
+    /// Just like for [Character::items], the first field in the tuple is the item, and the second
     /// field is the count of the item. (How many.)
     pub fn equipped_items(&self) -> Vec<ItemCount> {
         // gets the items that are selected as held
@@ -610,12 +1511,210 @@ impl Character {
             .collect()
     }
 
+    /// Returns every magic item the character has, along with whether it's equipped.
+    ///
+    /// A magic item is any item that grants features. Items without any are mundane gear, and
+    /// aren't included.
+    pub fn magic_items(&self) -> Vec<(&Item, bool)> {
+        self.items
+            .iter()
+            .filter(|h| !h.item.features.is_empty())
+            .map(|h| (&h.item, h.equipped))
+            .collect()
+    }
+
+    /// Returns the magic items whose features currently apply to the character, which are the
+    /// ones that are equipped and, if they need it, attuned. See [Character::item_features].
+    pub fn active_magic_items(&self) -> Vec<&Item> {
+        self.magic_items()
+            .into_iter()
+            .filter_map(|(item, equipped)| {
+                (equipped && item.attunement_satisfied()).then_some(item)
+            })
+            .collect()
+    }
+
+    /// The number of items the character is attuned to.
+    pub fn attuned_count(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|h| h.item.attunement == Some(true))
+            .count()
+    }
+
+    /// Attunes the character to the item at an index in [Character::items].
+    ///
+    /// A character can be attuned to at most 3 items at a time. Returns false if they already
+    /// are, if there's no item at the index, or if the item doesn't need attunement. Attuning to
+    /// an item that's already attuned does nothing and returns true.
+    pub fn try_attune(&mut self, item_index: usize) -> bool {
+        let attuned_count = self.attuned_count();
+        let Some(held) = self.items.get_mut(item_index) else {
+            return false;
+        };
+        match held.item.attunement {
+            None => false,
+            Some(true) => true,
+            Some(false) if attuned_count >= 3 => false,
+            Some(false) => {
+                held.item.attunement = Some(true);
+                true
+            }
+        }
+    }
+
+    /// Ends the character's attunement to the item at an index in [Character::items]. Returns
+    /// false if there's no item there, or it wasn't attuned.
+    pub fn end_attunement(&mut self, item_index: usize) -> bool {
+        match self.items.get_mut(item_index) {
+            Some(held) if held.item.attunement == Some(true) => {
+                held.item.attunement = Some(false);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Equips the weapon that makes the [best weapon action](Character::best_weapon_action), out
+    /// of the weapons the character has and is proficient with. Any other weapons are
+    /// unequipped.
+    ///
+    /// Returns false, leaving the character's weapons as they were, if none of their weapons
+    /// beat an unarmed strike.
+    pub fn equip_best_weapon(&mut self) -> bool {
+        let proficiencies = self.equipment_proficiencies();
+        let weapon_indexes: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| matches!(i.item.item_type, ItemType::Weapon(_)))
+            .map(|(n, _)| n)
+            .collect();
+        let previously_equipped: Vec<bool> =
+            weapon_indexes.iter().map(|n| self.items[*n].equipped).collect();
+
+        let mut best: Option<(usize, f64)> = None;
+        for &index in &weapon_indexes {
+            let item = &self.items[index].item;
+            let proficient = match &item.item_type {
+                ItemType::Weapon(w) => {
+                    proficiencies.is_proficient_with_weapon(&w.weapon_type, &item.name)
+                }
+                _ => false,
+            };
+            if !proficient {
+                continue;
+            }
+
+            for &n in &weapon_indexes {
+                self.items[n].equipped = n == index;
+            }
+            let average = self
+                .best_weapon_action()
+                .filter(|a| a.name != "Unarmed Strike")
+                .map(|a| a.damage_roll.average());
+            if let Some(average) = average {
+                if best.is_none_or(|(_, b)| average > b) {
+                    best = Some((index, average));
+                }
+            }
+        }
+
+        for (&n, &equipped) in weapon_indexes.iter().zip(previously_equipped.iter()) {
+            self.items[n].equipped = match best {
+                Some((best_index, _)) => n == best_index,
+                None => equipped,
+            };
+        }
+        best.is_some()
+    }
+
+    /// Equips the armor that gives the character the highest AC, out of the armor they have and
+    /// are proficient with. Any other armor is unequipped. Shields aren't changed.
+    ///
+    /// Returns false, leaving the character's armor as it was, if none of their armor beats the
+    /// AC they have without it.
+    pub fn equip_best_armor(&mut self) -> bool {
+        let proficiencies = self.equipment_proficiencies();
+        let armor_indexes: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| matches!(i.item.item_type, ItemType::Armor(_)))
+            .map(|(n, _)| n)
+            .collect();
+        let previously_equipped: Vec<bool> =
+            armor_indexes.iter().map(|n| self.items[*n].equipped).collect();
+
+        for &n in &armor_indexes {
+            self.items[n].equipped = false;
+        }
+        let unarmored_ac = self.ac();
+
+        let mut best: Option<(usize, isize)> = None;
+        for &index in &armor_indexes {
+            let proficient = match &self.items[index].item.item_type {
+                ItemType::Armor(armor) => proficiencies.is_proficient_with_armor(armor.category),
+                _ => false,
+            };
+            if !proficient {
+                continue;
+            }
+
+            self.items[index].equipped = true;
+            let ac = self.ac();
+            self.items[index].equipped = false;
+            if ac > unarmored_ac && best.is_none_or(|(_, b)| ac > b) {
+                best = Some((index, ac));
+            }
+        }
+
+        for (&n, &equipped) in armor_indexes.iter().zip(previously_equipped.iter()) {
+            self.items[n].equipped = match best {
+                Some((best_index, _)) => n == best_index,
+                None => equipped,
+            };
+        }
+        best.is_some()
+    }
+
+    /// Equips the armor at an index in [Character::items], unequipping any other armor first so
+    /// the character is only ever wearing one set. Shields aren't changed.
+    ///
+    /// Returns false, leaving the character's armor as it was, if there's no item at the index,
+    /// it isn't armor, or the character isn't proficient with it.
+    pub fn equip_armor(&mut self, item_index: usize) -> bool {
+        let proficient = match self.items.get(item_index).map(|h| &h.item.item_type) {
+            Some(ItemType::Armor(armor)) => self
+                .equipment_proficiencies()
+                .is_proficient_with_armor(armor.category),
+            _ => false,
+        };
+        if !proficient {
+            return false;
+        }
+
+        self.unequip_all_armor();
+        self.items[item_index].equip();
+        true
+    }
+
+    /// Unequips every piece of armor the character has on. Shields aren't changed.
+    pub fn unequip_all_armor(&mut self) {
+        self.items
+            .iter_mut()
+            .filter(|h| matches!(h.item.item_type, ItemType::Armor(_)))
+            .for_each(|h| h.unequip());
+    }
+
     // ---------- SPELLS ----------
 
     /// gets the spell save dc and spell attack modifier of the specified class.
     ///
     /// The first field of the tuple is the spell save dc, and the second is the spell attack
-    /// modifier.
+    /// modifier. Any [FeatureEffect::SpellSaveDcBonus] and [FeatureEffect::SpellAttackBonus] for
+    /// the class (or for every class) are added on, which only count for items while they're
+    /// equipped (and attuned, if needed).
     ///
     /// Returns a [None] if the character is not a spellcaster.
     ///
@@ -661,19 +1760,42 @@ impl Character {
         class_index: usize,
         modifiers: &Modifiers,
     ) -> Option<(isize, isize)> {
-        let spellcasting_ability = &self
-            .classes
-            .get(class_index)?
-            .spellcasting
-            .as_ref()?
-            .0
-            .spellcasting_ability;
-        let spellcasting_mod = *modifiers.stats.get_stat_type(spellcasting_ability);
+        let class = self.classes.get(class_index)?;
+        let spellcasting_ability = &class.spellcasting.as_ref()?.0.spellcasting_ability;
+        let (mut save_dc, mut attack_mod) =
+            self.scores_for_ability(*spellcasting_ability, modifiers);
+        let for_class = |name: &Option<String>| {
+            name.as_ref()
+                .is_none_or(|name| name.eq_ignore_ascii_case(&class.class))
+        };
+        for effect in self.total_features().iter().flat_map(|f| f.effects.iter()) {
+            match effect {
+                FeatureEffect::SpellSaveDcBonus(n, name) if for_class(name) => save_dc += n,
+                FeatureEffect::SpellAttackBonus(n, name) if for_class(name) => attack_mod += n,
+                _ => (),
+            }
+        }
+        Some((save_dc, attack_mod))
+    }
 
-        let spell_save_dc = 8 + self.proficiency_bonus() + spellcasting_mod;
-        let spell_attack_mod = self.proficiency_bonus() + spellcasting_mod;
+    /// Gets the spell save DC and spell attack modifier for the character's [racial
+    /// spells](Character::racial_spells), like a tiefling's Hellish Rebuke. Returns [None] if the
+    /// character doesn't have any.
+    ///
+    /// These use the ability given by the race instead of a class's spellcasting ability, so a
+    /// character doesn't need to be a spellcaster to have them. If the racial spells somehow use
+    /// different abilities, the first one is used.
+    pub fn racial_spellcasting_scores(&self) -> Option<(isize, isize)> {
+        let (_, ability, _) = self.racial_spells.first()?;
+        Some(self.scores_for_ability(*ability, &self.stats().modifiers()))
+    }
 
-        Some((spell_save_dc, spell_attack_mod))
+    // the (spell save dc, spell attack modifier) for spells cast with the ability
+    fn scores_for_ability(&self, ability: StatType, modifiers: &Modifiers) -> (isize, isize) {
+        let ability_mod = modifiers.stats[ability];
+        let spell_save_dc = 8 + self.proficiency_bonus() + ability_mod;
+        let spell_attack_mod = self.proficiency_bonus() + ability_mod;
+        (spell_save_dc, spell_attack_mod)
     }
 
     /// Gets every spell actively prepared or known by the character.
@@ -689,6 +1811,33 @@ impl Character {
             .collect()
     }
 
+    /// Gets every spell prepared or known by the character, without duplicates.
+    ///
+    /// A spell known by more than one class (compared by name, ignoring case) only shows up once,
+    /// in the order it first appears in [Character::spells]. To see which classes grant a spell,
+    /// use [Character::spell_sources].
+    pub fn unique_spells(&self) -> Vec<&Spell> {
+        let mut seen = HashSet::new();
+        self.spells()
+            .into_iter()
+            .map(|(spell, _)| spell)
+            .filter(|spell| seen.insert(spell.name.to_lowercase()))
+            .collect()
+    }
+
+    /// Gets the indexes of every [SpeccedClass] that has the named spell prepared or known. The
+    /// name is compared ignoring case.
+    pub fn spell_sources(&self, name: &str) -> Vec<usize> {
+        let mut sources: Vec<usize> = self
+            .spells()
+            .into_iter()
+            .filter(|(spell, _)| spell.name.eq_ignore_ascii_case(name))
+            .map(|(_, class_index)| class_index)
+            .collect();
+        sources.dedup();
+        sources
+    }
+
     /// Gets total spell slots, the base spell slots the class has access to after a long rest.
     pub fn spell_slots(&self) -> Option<SpellSlots> {
         let caster_classes = self.classes.iter().filter_map(|v| {
@@ -749,21 +1898,87 @@ impl Character {
     ///   # }
     /// ```
     pub fn pact_slots(&self) -> Option<PactSlots> {
-        let (_, slots_level) = self
-            .classes
+        let slots_level = self.pact_caster_level()?;
+
+        if slots_level == 0 {
+            return None;
+        }
+
+        Some(PactSlots::from(PACT_CASTING_SLOTS[slots_level - 1]))
+    }
+
+    // the level of the first class with pact magic, e.g. warlock level.
+    fn pact_caster_level(&self) -> Option<usize> {
+        self.classes
             .iter()
             .filter_map(|v| {
                 v.spellcasting
                     .as_ref()
                     .map(|s| (s.0.spellcaster_type, v.level))
             })
-            .find(|(s, _)| matches!(s, SpellCasterType::Warlock))?;
+            .find(|(s, _)| matches!(s, SpellCasterType::Warlock))
+            .map(|(_, level)| level)
+    }
 
-        if slots_level == 0 {
-            return None;
+    /// Lists the Mystic Arcanum the character has access to, as the spell level along with the
+    /// chosen spell, if any.
+    ///
+    /// Warlocks gain a 6th level arcanum at level 11, then a 7th, 8th, and 9th at levels 13, 15,
+    /// and 17. Characters without pact magic get an empty list.
+    ///
+    /// To choose a spell, see [Character::set_mystic_arcanum].
+    pub fn mystic_arcana(&self) -> Vec<(usize, Option<&Spell>)> {
+        let pact_level = self.pact_caster_level().unwrap_or(0);
+        MYSTIC_ARCANUM_LEVELS
+            .iter()
+            .zip(self.mystic_arcana.iter())
+            .filter(|((class_level, _), _)| pact_level >= *class_level)
+            .map(|((_, spell_level), arcanum)| (*spell_level, arcanum.spell.as_ref()))
+            .collect()
+    }
+
+    /// Sets the spell for the Mystic Arcanum of the spell's level.
+    ///
+    /// Returns false if the spell isn't between 6th and 9th level, if the character doesn't have
+    /// access to an arcanum of that level yet, or if the spell isn't on the warlock spell list.
+    pub fn set_mystic_arcanum(&mut self, spell: Spell) -> bool {
+        let level = spell.level;
+        if !self.mystic_arcana().iter().any(|(l, _)| *l == level) {
+            return false;
         }
+        let warlock_index = self.classes.iter().position(|class| {
+            class
+                .spellcasting
+                .as_ref()
+                .is_some_and(|(casting, _)| casting.spellcaster_type == SpellCasterType::Warlock)
+        });
+        if !warlock_index.is_some_and(|index| self.on_spell_list(index, &spell)) {
+            return false;
+        }
+        self.mystic_arcana[level - 6].spell = Some(spell);
+        true
+    }
 
-        Some(PactSlots::from(PACT_CASTING_SLOTS[slots_level - 1]))
+    /// Casts the Mystic Arcanum of the given spell level.
+    ///
+    /// This doesn't use a pact slot. Instead, each arcanum can be cast once per long rest.
+    ///
+    /// Returns false if there's no arcanum spell at that level, or if it's already been cast since
+    /// the last long rest.
+    pub fn cast_mystic_arcanum(&mut self, level: usize) -> bool {
+        if !self
+            .mystic_arcana()
+            .iter()
+            .any(|(l, spell)| *l == level && spell.is_some())
+        {
+            return false;
+        }
+        let arcanum = &mut self.mystic_arcana[level - 6];
+        if arcanum.used {
+            return false;
+        }
+        arcanum.used = true;
+        true
     }
 
     /// Cast the spell, expending a spell slot.
@@ -771,6 +1986,10 @@ impl Character {
     /// Returns false if the spell could not be cast. For example, if the character is not a
     /// spellcaster, or if there are no spell slots left of the specified type.
     ///
+    /// The second argument is the level of the slot to spend, for upcasting. If it's None, the
+    /// spell is cast at its own level. This fails if it's lower than the spell's level, or if the
+    /// casted thing is a cantrip, since cantrips can't be upcast.
+    ///
     /// the third argument is which spell list to pull from. Set this to None regularly.
     ///
     /// Spells can be casted either with regular spell slots or warlock pact magic. Typically this doesn't come into effect, though
@@ -779,23 +1998,232 @@ impl Character {
     /// were a warlock first. If it's a Some(false), it uses Spell slots, and if it's Some(True),
     /// it uses pact magic.
     ///
-    /// Note that this only decrements the spell slot at the spell's level.
-    pub fn cast<T: Castable>(&mut self, casted: &T, spell_list: Option<bool>) -> bool {
+    /// If spell_list is none and the casted thing is a [SpellAction], its
+    /// [slot pool](SpellAction::slot_pool) is checked first, so an action that can only be cast
+    /// with pact slots uses pact magic, and one that can only be cast with spell slots uses spell
+    /// slots. Actions that don't use a slot always succeed, like cantrips.
+    ///
+    /// Note that this only decrements the spell slot at the level being cast at. See
+    /// [Character::spend_slot] and [Character::spend_pact_slot] to spend slots directly.
+    pub fn cast<T: Castable>(
+        &mut self,
+        casted: &T,
+        upcast: Option<usize>,
+        spell_list: Option<bool>,
+    ) -> bool {
+        let level = match upcast {
+            Some(_) if casted.level() == 0 => return false,
+            Some(l) if l >= casted.level() => l,
+            Some(_) => return false,
+            None => casted.level(),
+        };
+        self.cast_at_level(level, casted.slot_pool(), spell_list)
+    }
+
+    fn cast_at_level(
+        &mut self,
+        level: usize,
+        slot_pool: SlotPool,
+        spell_list: Option<bool>,
+    ) -> bool {
         match spell_list {
 
-            None => {
-                let v = self.first_caster_class();
-                match v {
+            None => match slot_pool {
+                SlotPool::NoSlot => true,
+                SlotPool::PactSlots => self.cast_with_pact(level),
+                SlotPool::SpellSlots => self.cast_with_slots(level),
+                SlotPool::Either => match self.first_caster_class() {
                     None => false,
-                    Some(SpellCasterType::Warlock) => self.cast_with_pact(casted.level()),
-                    Some(_) => self.cast_with_slots(casted.level()),
-                }
+                    Some(SpellCasterType::Warlock) => self.cast_with_pact(level),
+                    Some(_) => self.cast_with_slots(level),
+                },
             },
-            Some(true) =>  self.cast_with_pact(casted.level()),
-            Some(false) => self.cast_with_slots(casted.level())
+            Some(true) =>  self.cast_with_pact(level),
+            Some(false) => self.cast_with_slots(level)
+        }
+    }
+
+    /// Cast the spell with the lowest level slot available that's at least the spell's level,
+    /// from either regular spell slots or pact magic.
+    ///
+    /// Returns the level of the slot that was spent, or [None] if there wasn't one. Cantrips
+    /// don't need a slot, so they always return `Some(0)`. If a pact slot and a regular slot are
+    /// the same level, the pact slot is spent, since it comes back on a short rest.
+    ///
+    /// Use [Character::cast] or [Character::cast_prepared] to pick the slot yourself.
+    pub fn cast_lowest_available<T: Castable>(&mut self, casted: &T) -> Option<usize> {
+        let level = casted.level();
+        if level == 0 {
+            return Some(0);
+        }
+
+        let pact_level = self
+            .available_pact_slots
+            .filter(|p| p.num > 0 && p.level >= level)
+            .map(|p| p.level);
+        let slot_level = self
+            .available_spell_slots
+            .as_ref()
+            .and_then(|slots| (level..=slots.0.len()).find(|l| slots.0[l - 1] > 0));
+
+        match (slot_level, pact_level) {
+            (Some(s), Some(p)) if s < p => self.cast_with_slots(s).then_some(s),
+            (_, Some(p)) => self.cast_with_pact(p).then_some(p),
+            (Some(s), None) => self.cast_with_slots(s).then_some(s),
+            (None, None) => None,
         }
     }
 
+    /// Checks if the character could cast a spell right now, and if not, why.
+    ///
+    /// The spell must be prepared or known by one of the character's classes (or be one of their
+    /// [Mystic Arcanum](Character::mystic_arcana)), and unless it's a cantrip, the character needs
+    /// an unspent spell slot or pact slot of at least the spell's level. An incapacitated
+    /// character can't cast anything.
+    pub fn can_cast(&self, spell: &Spell) -> Castability {
+        let blocking = [
+            Condition::Unconscious,
+            Condition::Paralyzed,
+            Condition::Petrified,
+            Condition::Stunned,
+            Condition::Incapacitated,
+        ]
+        .into_iter()
+        .find(|c| self.conditions.contains(c));
+        if let Some(condition) = blocking {
+            return Castability::Blocked(condition);
+        }
+
+        let class_index = self.classes.iter().position(|class| {
+            class
+                .spellcasting
+                .as_ref()
+                .is_some_and(|(_, spells)| {
+                    spells
+                        .iter()
+                        .any(|s| s.name.eq_ignore_ascii_case(&spell.name))
+                })
+        });
+
+        let Some(class_index) = class_index else {
+            let is_arcanum = self.mystic_arcana().iter().any(|(level, arcanum)| {
+                *level == spell.level
+                    && arcanum.is_some_and(|s| s.name.eq_ignore_ascii_case(&spell.name))
+            });
+            return match is_arcanum {
+                false => Castability::NotPrepared,
+                true if self.mystic_arcana[spell.level - 6].used => Castability::NoSlot,
+                true => Castability::MysticArcanum,
+            };
+        };
+
+        if spell.level == 0 {
+            return Castability::Castable { class_index };
+        }
+
+        let has_slot = self
+            .available_spell_slots
+            .as_ref()
+            .is_some_and(|slots| slots.0[spell.level - 1..].iter().any(|n| *n > 0));
+        let has_pact_slot = self
+            .available_pact_slots
+            .is_some_and(|slots| slots.num > 0 && slots.level >= spell.level);
+
+        if has_slot || has_pact_slot {
+            Castability::Castable { class_index }
+        } else {
+            Castability::NoSlot
+        }
+    }
+
+    /// Returns true if the class at the index can cast the spell as a ritual.
+    ///
+    /// The spell needs to be a ritual, and the class needs
+    /// [ritual casting](super::spells::Spellcasting::ritual_casting). It also needs to be
+    /// prepared or known by the class, except for classes that prepare from a spellbook like
+    /// wizards, who can cast any ritual in their spellbook. Since the spellbook isn't tracked,
+    /// those rituals only need to be on the class's spell list.
+    pub fn can_cast_as_ritual(&self, spell: &Spell, class_index: usize) -> bool {
+        let Some((casting, spells)) = self
+            .classes
+            .get(class_index)
+            .and_then(|c| c.spellcasting.as_ref())
+        else {
+            return false;
+        };
+        spell.ritual
+            && casting.ritual_casting
+            && (spells
+                .iter()
+                .any(|s| s.name.eq_ignore_ascii_case(&spell.name))
+                || casting.prepares_from_spellbook && self.on_spell_list(class_index, spell))
+    }
+
+    /// Casts a spell as a ritual, which takes 10 minutes longer but doesn't spend a spell slot.
+    ///
+    /// Returns false if none of the character's classes can cast it as a ritual. See
+    /// [Character::can_cast_as_ritual].
+    pub fn cast_ritual(&mut self, spell: &Spell) -> bool {
+        (0..self.classes.len()).any(|n| self.can_cast_as_ritual(spell, n))
+    }
+
+    /// Casts one of the character's [racial spells](Character::racial_spells) by name.
+    ///
+    /// At will spells can always be cast. Once per long rest spells can be cast if they haven't
+    /// been since the last long rest, and spells that need a spell slot spend the lowest one
+    /// available, like [Character::cast_lowest_available].
+    ///
+    /// Returns false if the character doesn't have the spell, or can't cast it right now.
+    pub fn cast_racial_spell(&mut self, name: &str) -> bool {
+        let Some(index) = self
+            .racial_spells
+            .iter()
+            .position(|(spell, _, _)| spell.name.eq_ignore_ascii_case(name))
+        else {
+            return false;
+        };
+
+        match self.racial_spells[index].2 {
+            RacialSpellUse::AtWill => true,
+            RacialSpellUse::OncePerLongRest { used: true } => false,
+            RacialSpellUse::OncePerLongRest { used: false } => {
+                self.racial_spells[index].2 = RacialSpellUse::OncePerLongRest { used: true };
+                true
+            }
+            RacialSpellUse::SpellSlot => {
+                let spell = self.racial_spells[index].0.clone();
+                self.cast_lowest_available(&spell).is_some()
+            }
+        }
+    }
+
+    // adds any racial spells the character has reached the level for. Spells that were already
+    // added are left alone, so their uses aren't reset.
+    fn update_racial_spells(&mut self) {
+        let level = self.level();
+        let new_spells: Vec<(Spell, StatType, RacialSpellUse)> = self
+            .total_features()
+            .into_iter()
+            .flat_map(|f| f.effects.iter())
+            .filter_map(|effect| match effect {
+                FeatureEffect::RacialSpell {
+                    spell,
+                    ability,
+                    usage,
+                    level: spell_level,
+                } if *spell_level <= level => Some((spell.as_ref().clone(), *ability, *usage)),
+                _ => None,
+            })
+            .filter(|(spell, _, _)| {
+                !self
+                    .racial_spells
+                    .iter()
+                    .any(|(s, _, _)| s.name == spell.name)
+            })
+            .collect();
+        self.racial_spells.extend(new_spells);
+    }
+
     fn first_caster_class(&self) -> Option<SpellCasterType> {
         self.classes
             .iter()
@@ -852,67 +2280,177 @@ impl Character {
             None => spell.level(),
         };
 
-        match spell_list {
-            Some(true) => self.cast_with_pact(level),
-            Some(false) => self.cast_with_slots(level),
-            None => {
-                let v = self.first_caster_class();
-                match v {
-                    None => false,
-                    Some(SpellCasterType::Warlock) => self.cast_with_pact(level),
-                    Some(_) => self.cast_with_slots(level),
-                }
-            }
-        }
+        self.cast_at_level(level, SlotPool::Either, spell_list)
     }
 
     fn cast_with_slots(&mut self, level: usize) -> bool {
+        level == 0 || self.spend_slot(level)
+    }
+
+    fn cast_with_pact(&mut self, level: usize) -> bool {
         if level == 0 {
             return true;
         }
-        let spell_slots = match &mut self.available_spell_slots {
-            Some(s) => s,
-            _ => return false,
-        };
+        if self
+            .available_pact_slots
+            .is_none_or(|slots| slots.level < level)
+        {
+            return false;
+        }
+        self.spend_pact_slot()
+    }
 
-        let spell_slot = &mut spell_slots.0[level - 1];
+    /// Spends a regular spell slot of the given level, without casting anything.
+    ///
+    /// Returns false if the character has no spell slots of that level left.
+    pub fn spend_slot(&mut self, level: usize) -> bool {
+        let spell_slot = match &mut self.available_spell_slots {
+            Some(s) if level > 0 => s.0.get_mut(level - 1),
+            _ => None,
+        };
 
-        if *spell_slot < 1 {
-            return false;
+        match spell_slot {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                true
+            }
+            _ => false,
         }
+    }
 
-        *spell_slot -= 1;
-        true
+    /// Regains a regular spell slot of the given level, e.g. from a wizard's Arcane Recovery or a
+    /// sorcerer's Font of Magic.
+    ///
+    /// This does nothing if the character already has all of their spell slots of that level.
+    pub fn restore_slot(&mut self, level: usize) {
+        let max = match self.spell_slots() {
+            Some(slots) if level > 0 && level <= slots.0.len() => slots.0[level - 1],
+            _ => return,
+        };
+        if let Some(slots) = &mut self.available_spell_slots {
+            let n = &mut slots.0[level - 1];
+            *n = (*n + 1).min(max);
+        }
     }
 
-    fn cast_with_pact(&mut self, level: usize) -> bool {
-        if level == 0 {
-            return true;
+    /// Spends a pact magic slot, without casting anything.
+    ///
+    /// Returns false if the character has no pact slots left.
+    pub fn spend_pact_slot(&mut self) -> bool {
+        match &mut self.available_pact_slots {
+            Some(slots) if slots.num > 0 => {
+                slots.num -= 1;
+                true
+            }
+            _ => false,
         }
-        let spell_slot = match &mut self.available_pact_slots {
-            Some(s) => s,
-            _ => return false,
+    }
+
+    /// Regains a pact magic slot.
+    ///
+    /// This does nothing if the character already has all of their pact slots.
+    pub fn restore_pact_slot(&mut self) {
+        let max = match self.pact_slots() {
+            Some(slots) => slots.num,
+            None => return,
         };
+        if let Some(slots) = &mut self.available_pact_slots {
+            slots.num = (slots.num + 1).min(max);
+        }
+    }
+
+    /// Spends a regular spell slot for sorcery points, like a sorcerer's Font of Magic.
+    ///
+    /// Returns the points gained, which is the slot's level, or [None] if there wasn't a slot of
+    /// that level to spend. The points aren't added anywhere, since they're up to the caller to
+    /// keep. To use the character's "Sorcery Points" [TrackedField] instead, see
+    /// [Character::convert_slot_to_sorcery_points].
+    pub fn convert_slot_to_points(&mut self, slot_level: usize) -> Option<usize> {
+        self.spend_slot(slot_level).then_some(slot_level)
+    }
 
-        if spell_slot.level < level {
+    /// Spends sorcery points to create a regular spell slot, like a sorcerer's Font of Magic.
+    ///
+    /// The cost is taken from `points`, per [SORCERY_POINT_SLOT_COSTS]. Returns false without
+    /// spending anything if there aren't enough points, if the slot is above 5th level, or if the
+    /// character doesn't have spell slots. To use the character's "Sorcery Points" [TrackedField]
+    /// instead, see [Character::convert_sorcery_points_to_slot].
+    ///
+    /// Created slots can go over the character's maximum for that level, until a long rest.
+    pub fn convert_points_to_slot(&mut self, target_level: usize, points: &mut usize) -> bool {
+        let cost = target_level
+            .checked_sub(1)
+            .and_then(|i| SORCERY_POINT_SLOT_COSTS.get(i));
+        let (Some(&cost), Some(slots)) = (cost, &mut self.available_spell_slots) else {
+            return false;
+        };
+        if *points < cost {
             return false;
         }
+        *points -= cost;
+        slots.0[target_level - 1] += 1;
+        true
+    }
 
-        if spell_slot.num < 1 {
+    /// Like [Character::convert_slot_to_points], but the points go to the character's
+    /// [TrackedField] named "Sorcery Points", up to its maximum.
+    ///
+    /// Returns false without spending the slot if the character doesn't track sorcery points, or
+    /// there wasn't a slot of that level to spend.
+    pub fn convert_slot_to_sorcery_points(&mut self, slot_level: usize) -> bool {
+        if self.sorcery_points_mut().is_none() {
             return false;
         }
+        let Some(gained) = self.convert_slot_to_points(slot_level) else {
+            return false;
+        };
+        if let Some((current, max)) = self.sorcery_points_mut() {
+            *current = (*current + gained).min(max);
+        }
+        true
+    }
 
-        spell_slot.num -= 1;
+    /// Like [Character::convert_points_to_slot], but the points are taken from the character's
+    /// [TrackedField] named "Sorcery Points".
+    ///
+    /// Returns false without spending anything if the character doesn't track sorcery points, or
+    /// if [Character::convert_points_to_slot] would fail.
+    pub fn convert_sorcery_points_to_slot(&mut self, target_level: usize) -> bool {
+        let Some(mut points) = self.sorcery_points_mut().map(|(current, _)| *current) else {
+            return false;
+        };
+        if !self.convert_points_to_slot(target_level, &mut points) {
+            return false;
+        }
+        if let Some((current, _)) = self.sorcery_points_mut() {
+            *current = points;
+        }
         true
     }
 
+    // the character's current sorcery points and their maximum, from the first class that tracks
+    // them.
+    fn sorcery_points_mut(&mut self) -> Option<(&mut usize, usize)> {
+        self.classes.iter_mut().find_map(|class| {
+            let (field, current) = class
+                .tracked_fields
+                .iter_mut()
+                .find(|(field, _)| field.name.eq_ignore_ascii_case("sorcery points"))?;
+            let max = tracked_field_max(field, &class.class_specific)?;
+            Some((current, max))
+        })
+    }
+
     // ----------- FEATURES ------------
 
     /// Every [Feature] currently granted by any items the character has equipped.
+    ///
+    /// Items that need [attunement](Item::attunement) only grant their features once the
+    /// character is attuned to them.
     pub fn item_features(&self) -> Vec<&Feature> {
         self.items
             .iter()
-            .filter_map(|v| v.equipped.then_some(&v.item))
+            .filter_map(|v| (v.equipped && v.item.attunement_satisfied()).then_some(&v.item))
             .flat_map(|item_count| item_count.features.iter())
             .collect()
     }
@@ -960,22 +2498,71 @@ impl Character {
 
     /// Every [Feature] from all sources in effect on the character.
     ///
-    /// The features are collected from the character's race, subrace, class, subclass, items, and
-    /// any extra bonus features the character may have as listed in [Character::bonus_features].
+    /// The features are collected from the character's race, subrace, class, subclass, items,
+    /// features taken in place of an ability score increase, feats, and any extra bonus features
+    /// the character may have as listed in [Character::bonus_features].
     pub fn total_features(&self) -> Vec<&Feature> {
-        let bonus_features = self.bonus_features.iter();
-        let item_features = self.item_features().into_iter();
-        let class_features = self.class_features().into_iter();
-        let subclass_features = self.subclass_features().into_iter();
-        let race_features = self.race_features();
-        let subrace_features = self.subrace_features();
-
-        class_features
-            .chain(item_features)
-            .chain(subclass_features)
-            .chain(race_features)
-            .chain(subrace_features)
-            .chain(bonus_features)
+        self.features_with_source()
+            .into_iter()
+            .map(|(_, feature)| feature)
+            .collect()
+    }
+
+    /// Every [Feature] in effect on the character, like [Character::total_features], along with
+    /// where each one came from.
+    pub fn features_with_source(&self) -> Vec<(FeatureSource, &Feature)> {
+        fn tagged<'a>(
+            source: FeatureSource,
+            features: impl IntoIterator<Item = &'a Feature>,
+        ) -> impl Iterator<Item = (FeatureSource, &'a Feature)> {
+            features.into_iter().map(move |f| (source, f))
+        }
+
+        tagged(FeatureSource::Class, self.class_features())
+            .chain(tagged(FeatureSource::Item, self.item_features()))
+            .chain(tagged(FeatureSource::Subclass, self.subclass_features()))
+            .chain(tagged(FeatureSource::Race, self.race_features()))
+            .chain(tagged(FeatureSource::Subrace, self.subrace_features()))
+            .chain(tagged(
+                FeatureSource::Feat,
+                self.ability_score_increase_features(),
+            ))
+            .chain(tagged(FeatureSource::Feat, self.feat_features()))
+            .chain(tagged(FeatureSource::Bonus, &self.bonus_features))
+            .collect()
+    }
+
+    /// Every [Feature] in effect on the character, like [Character::total_features], but cloned
+    /// so they can outlive the character.
+    pub fn total_features_owned(&self) -> Vec<Feature> {
+        self.total_features().into_iter().cloned().collect()
+    }
+
+    /// Every [Feature] in effect on the character, cloned and grouped by where they came from.
+    ///
+    /// Sources without any features aren't in the map. Within a source, features are in the same
+    /// order as [Character::features_with_source].
+    pub fn features_by_source(&self) -> HashMap<FeatureSource, Vec<Feature>> {
+        let mut by_source: HashMap<FeatureSource, Vec<Feature>> = HashMap::new();
+        for (source, feature) in self.features_with_source() {
+            by_source.entry(source).or_default().push(feature.clone());
+        }
+        by_source
+    }
+
+    /// Every [FeatureEffect] on the character that `matcher` returns true for, along with where
+    /// it came from.
+    ///
+    /// This is mostly useful for finding out why a value is what it is. For example,
+    /// `|e| matches!(e, FeatureEffect::ACBonus(_))` finds every ac bonus the character has.
+    pub fn effect_sources(
+        &self,
+        matcher: impl Fn(&FeatureEffect) -> bool,
+    ) -> Vec<(FeatureSource, &FeatureEffect)> {
+        self.features_with_source()
+            .into_iter()
+            .flat_map(|(source, feature)| feature.effects.iter().map(move |e| (source, e)))
+            .filter(|(_, effect)| matcher(effect))
             .collect()
     }
 
@@ -1011,7 +2598,7 @@ impl Character {
             _ => None,
         });
 
-        // finds the first armor equipped. We're assuming there's only one.
+        // finds the first armor equipped. Character::equip_armor makes sure there's only one.
         let armor = equipped_items.iter().find_map(|i| {
             if let ItemType::Armor(armor) = &i.item.item_type {
                 Some(armor)
@@ -1049,8 +2636,45 @@ impl Character {
         ac
     }
 
+    /// Gets the character's ac against a specific attack, including bonuses that only apply in
+    /// some situations. See [CombatContext].
+    ///
+    /// [Character::ac] is the same as this with an empty context.
+    pub fn ac_in_context(&self, context: CombatContext) -> isize {
+        let mut ac = self.ac();
+        if context.shield_spell_active {
+            ac += 5;
+        }
+        if context.shield_of_faith_active {
+            ac += 2;
+        }
+        if context.defensive_duelist && self.can_defensive_duel() {
+            ac += self.proficiency_bonus();
+        }
+        ac
+    }
+
+    // Defensive Duelist needs the feat, and a finesse weapon the character is proficient with.
+    fn can_defensive_duel(&self) -> bool {
+        let has_feat = self
+            .total_features()
+            .iter()
+            .any(|f| f.name.eq_ignore_ascii_case("defensive duelist"));
+        let proficiencies = self.equipment_proficiencies();
+        has_feat
+            && self.equipped_items().iter().any(|i| match &i.item.item_type {
+                ItemType::Weapon(w) => {
+                    w.properties.finesse
+                        && proficiencies.is_proficient_with_weapon(&w.weapon_type, &i.item.name)
+                }
+                _ => false,
+            })
+    }
+
     /// This finds the maximum hp of the character. This assumes that you took the average die
     /// instead of rolling for each level up.
+    ///
+    /// This is halved at 4 or more levels of [exhaustion](Character::add_exhaustion).
     pub fn max_hp(&self) -> usize {
         let level = self.level();
         let hit_die = self
@@ -1069,12 +2693,18 @@ impl Character {
                 hp += level;
             }
         }
+
+        // exhaustion level 4 halves max hp
+        if self.exhaustion >= 4 {
+            hp /= 2;
+        }
         hp
     }
 
     /// Returns every language the character knows.
     ///
-    /// This is collected from background, race, and features.
+    /// This is collected from background, race, and features. See [Character::languages] for a
+    /// sorted list.
     pub fn total_languages(&self) -> HashSet<&str> {
         let mut languages: HashSet<&str> = HashSet::new();
 
@@ -1087,59 +2717,409 @@ impl Character {
         }
 
 
-        // background languages
-        languages.extend(self.background.languages());
+        // background languages
+        languages.extend(self.background.languages());
+
+        // feature granted languages
+        // e.g. druidic, or extra language from high elf subrace
+        let features = self
+            .total_features()
+            .into_iter()
+            .flat_map(|v| v.effects.iter());
+        for feature in features {
+            if let FeatureEffect::AddedLanguage(LanguageOption::Fixed(l)) = feature {
+                languages.insert(l.as_str());
+            }
+        }
+
+        languages
+    }
+
+    /// Every language the character knows, sorted and without duplicates, like a character sheet
+    /// would list them.
+    ///
+    /// Languages that haven't been chosen yet aren't included. See
+    /// [Character::unchosen_languages].
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = self
+            .total_languages()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        languages.sort();
+        languages
+    }
+
+    /// Every language choice the character hasn't made yet, from their race, background, and
+    /// features, so they can be prompted for.
+    pub fn unchosen_languages(&self) -> Vec<UnchosenLanguage> {
+        let race = self
+            .race
+            .wildcard_languages
+            .iter()
+            .enumerate()
+            .filter(|(_, lang)| lang.is_none())
+            .map(|(index, _)| UnchosenLanguage::Race(index));
+
+        let background = self
+            .background
+            .unchosen_language_options()
+            .into_iter()
+            .map(|(index, option)| UnchosenLanguage::Background(index, option.clone()));
+
+        let features = self
+            .features_with_source()
+            .into_iter()
+            .flat_map(|(source, feature)| {
+                feature
+                    .effects
+                    .iter()
+                    .filter_map(move |effect| match effect {
+                        FeatureEffect::AddedLanguage(LanguageOption::Fixed(_)) => None,
+                        FeatureEffect::AddedLanguage(option) => Some(UnchosenLanguage::Feature {
+                            source,
+                            feature: feature.name.clone(),
+                            option: option.clone(),
+                        }),
+                        _ => None,
+                    })
+            });
+
+        race.chain(background).chain(features).collect()
+    }
+
+    /// Processes the character taking damage, without any damage type.
+    ///
+    /// The damage is taken as is, ignoring the character's resistances. See
+    /// [Character::damage_typed] for damage that has a type. Temporary hit points are spent
+    /// first.
+    ///
+    /// If the character is already at 0 hp, this instead counts as a failed death save (see
+    /// [Character::record_death_save]). Dropping to 0 hp ends the character's concentration, but
+    /// otherwise keeping it is up to the caller (see [Character::concentration_save_dc]).
+    ///
+    /// If the character's hp reaches 0, this returns true. Otherwise, it returns false.
+    pub fn damage(&mut self, damage: usize) -> bool {
+        self.apply_damage(damage, false)
+    }
+
+    /// Processes the character taking damage of a type, after their resistances, immunities,
+    /// and vulnerabilities (see [Character::damage_defenses]).
+    ///
+    /// Immunity takes the damage to 0, resistance halves it (rounding down), and vulnerability
+    /// doubles it. Resistance and vulnerability to the same type cancel out. Whatever's left is
+    /// spent from temporary hit points first.
+    ///
+    /// If the character's hp reaches 0, this returns true. Otherwise, it returns false.
+    pub fn damage_typed(&mut self, amount: usize, damage_type: DamageType) -> bool {
+        let amount = self.damage_defenses().adjust(amount, damage_type);
+        self.apply_damage(amount, false)
+    }
+
+    /// The damage types the character resists, is immune to, or is vulnerable to, from
+    /// [FeatureEffect::Resistance], [FeatureEffect::Immunity], and
+    /// [FeatureEffect::Vulnerability].
+    pub fn damage_defenses(&self) -> DamageDefenses {
+        let mut defenses = DamageDefenses::default();
+        for feature in self.total_features() {
+            for effect in feature.effects.iter() {
+                let (list, damage_type) = match effect {
+                    FeatureEffect::Resistance(t) => (&mut defenses.resistances, t),
+                    FeatureEffect::Immunity(t) => (&mut defenses.immunities, t),
+                    FeatureEffect::Vulnerability(t) => (&mut defenses.vulnerabilities, t),
+                    _ => continue,
+                };
+                if !list.contains(damage_type) {
+                    list.push(*damage_type);
+                }
+            }
+        }
+        defenses
+    }
+
+    /// Processes the character taking damage from a critical hit, without any damage type.
+    ///
+    /// This is the same as [Character::damage], except that if the character is already at 0 hp
+    /// it counts as two failed death saves instead of one.
+    pub fn damage_critical(&mut self, damage: usize) -> bool {
+        self.apply_damage(damage, true)
+    }
+
+    /// Records a death saving throw for a character at 0 hp.
+    ///
+    /// Three successes stabilize the character and reset their saves, and three failures mean
+    /// they're dead. Nothing is recorded if the character isn't dying, meaning they're above 0
+    /// hp, already stable, or already dead.
+    pub fn record_death_save(&mut self, success: bool) {
+        if self.hp > 0 || self.is_stable() || self.is_dead() {
+            return;
+        }
+        if !success {
+            self.death_saves.failures += 1;
+            return;
+        }
+        self.death_saves.successes += 1;
+        if self.death_saves.successes >= 3 {
+            self.death_saves = DeathSaves {
+                stable: true,
+                ..Default::default()
+            };
+        }
+    }
+
+    /// Whether the character has failed three death saves, or has 6 levels of exhaustion.
+    pub fn is_dead(&self) -> bool {
+        self.death_saves.failures >= 3 || self.exhaustion >= 6
+    }
+
+    /// Adds levels of exhaustion, up to 6.
+    ///
+    /// The effects of exhaustion stack:
+    /// 1. Disadvantage on ability checks (see [Character::has_disadvantage_on]).
+    /// 2. [Speed](Character::speed) halved.
+    /// 3. Disadvantage on attack rolls and saving throws.
+    /// 4. [Max hp](Character::max_hp) halved.
+    /// 5. Speed reduced to 0.
+    /// 6. Death (see [Character::is_dead]).
+    ///
+    /// If the character's max hp drops below their current hp, their hp drops with it.
+    pub fn add_exhaustion(&mut self, levels: u8) {
+        self.exhaustion = self.exhaustion.saturating_add(levels).min(6);
+        self.hp = self.hp.min(self.max_hp());
+    }
+
+    /// Removes levels of exhaustion, down to 0. A [long rest](Character::long_rest) removes one.
+    pub fn remove_exhaustion(&mut self, levels: u8) {
+        self.exhaustion = self.exhaustion.saturating_sub(levels);
+    }
+
+    /// Gives the character a condition. Returns false if they already had it.
+    pub fn add_condition(&mut self, condition: Condition) -> bool {
+        self.conditions.insert(condition)
+    }
+
+    /// Removes a condition from the character. Returns false if they didn't have it.
+    pub fn remove_condition(&mut self, condition: Condition) -> bool {
+        self.conditions.remove(&condition)
+    }
+
+    /// Whether the character has a condition.
+    ///
+    /// This includes conditions that come with another one. Paralyzed, petrified, stunned, and
+    /// unconscious characters are also incapacitated, and unconscious characters are also prone.
+    pub fn has_condition(&self, condition: Condition) -> bool {
+        self.conditions
+            .iter()
+            .any(|c| *c == condition || c.implies().contains(&condition))
+    }
+
+    /// Whether the character has disadvantage on every attack roll they make, from their
+    /// conditions or exhaustion.
+    ///
+    /// Being blinded, frightened, poisoned, prone, or restrained gives disadvantage on attack
+    /// rolls, and so does 3 or more levels of exhaustion. This doesn't include heavy encumbrance,
+    /// which only affects some attacks. See [Character::has_disadvantage_on].
+    pub fn has_disadvantage_on_attacks(&self) -> bool {
+        let conditions = [
+            Condition::Blinded,
+            Condition::Frightened,
+            Condition::Poisoned,
+            Condition::Prone,
+            Condition::Restrained,
+        ];
+        self.exhaustion >= 3 || conditions.into_iter().any(|c| self.has_condition(c))
+    }
+
+    /// Whether an attack that hits the character from within 5 feet is automatically a critical
+    /// hit, which happens when they're paralyzed or unconscious.
+    pub fn hits_within_5ft_are_critical(&self) -> bool {
+        self.has_condition(Condition::Paralyzed) || self.has_condition(Condition::Unconscious)
+    }
+
+    /// Whether the character is at 0 hp but no longer making death saves.
+    pub fn is_stable(&self) -> bool {
+        self.hp == 0 && self.death_saves.stable
+    }
+
+    /// Heals the character, up to their [max hp](Character::max_hp). Returns the hp actually
+    /// regained.
+    ///
+    /// Healing a character at 0 hp clears their death saves. A [dead](Character::is_dead)
+    /// character can't be healed, and nothing is regained.
+    pub fn heal(&mut self, amount: usize) -> usize {
+        if self.is_dead() {
+            return 0;
+        }
+        let healed = amount.min(self.max_hp().saturating_sub(self.hp));
+        self.hp += healed;
+        if self.hp > 0 {
+            self.death_saves = DeathSaves::default();
+        }
+        healed
+    }
+
+    /// Heals the character back up to their [max hp](Character::max_hp). Returns the hp actually
+    /// regained, which is 0 for a [dead](Character::is_dead) character.
+    pub fn heal_to_full(&mut self) -> usize {
+        self.heal(self.max_hp())
+    }
+
+    /// Gains temporary hit points.
+    ///
+    /// Temporary hit points don't stack, so this only replaces the character's current
+    /// temporary hit points if the new amount is higher.
+    pub fn gain_temp_hp(&mut self, amount: usize) {
+        self.temp_hp = self.temp_hp.max(amount);
+    }
 
-        // feature granted languages
-        // e.g. druidic, or extra language from high elf subrace
-        let features = self
-            .bonus_features
-            .iter()
-            .chain(self.item_features())
-            .chain(self.class_features())
-            .flat_map(|v| v.effects.iter());
-        for feature in features {
-            if let FeatureEffect::AddedLanguage(LanguageOption::Fixed(l)) = feature {
-                languages.insert(l.as_str());
-            }
+    // temporary hit points are spent first, then the rest comes off of hp. Damage at 0 hp is a
+    // failed death save, or two for a critical hit.
+    fn apply_damage(&mut self, damage: usize, critical: bool) -> bool {
+        if self.hp == 0 && damage > 0 && !self.is_dead() {
+            self.death_saves.stable = false;
+            self.death_saves.failures = (self.death_saves.failures + 1 + u8::from(critical)).min(3);
+            return true;
         }
-
-        languages
+        let absorbed = damage.min(self.temp_hp);
+        self.temp_hp -= absorbed;
+        self.hp = self.hp.saturating_sub(damage - absorbed);
+        if self.hp == 0 {
+            self.break_concentration();
+        }
+        self.hp == 0
     }
 
-    /// Processes the character taking damage.
+    /// Uses up one of a consumable item the character has, like a potion of healing, and applies
+    /// its effect. The item is removed once there's none left.
     ///
-    /// If the character's hp reaches 0, this returns true. Otherwise, it returns false.
-    pub fn damage(&mut self, damage: usize) -> bool {
-        let o = self.hp.checked_sub(damage);
-        match o {
-            Some(s) => {
-                self.hp = s;
-                self.hp == 0
-            }
-            None => {
-                self.hp = 0;
-                true
+    /// # Errors
+    /// Returns a [ConsumableError] if the character doesn't have the item, or if it isn't a
+    /// [consumable](ItemType::Consumable). Nothing is used up in either case.
+    pub fn use_consumable(
+        &mut self,
+        item_name: &str,
+        rng: &mut impl Rng,
+    ) -> Result<ConsumableResult, ConsumableError> {
+        let index = self
+            .items
+            .iter()
+            .position(|held| held.quantity > 0 && held.item.name.eq_ignore_ascii_case(item_name))
+            .ok_or_else(|| ConsumableError::NotFound(item_name.to_string()))?;
+        let ItemType::Consumable(effect) = self.items[index].item.item_type else {
+            return Err(ConsumableError::NotConsumable(item_name.to_string()));
+        };
+
+        let rolled = match effect {
+            ConsumableEffect::Healing {
+                number,
+                dice,
+                bonus,
+            } => {
+                let dice_total: usize = (0..number).map(|_| rng.random_range(1..=dice)).sum();
+                dice_total + bonus
             }
+        };
+        let healed = self.heal(rolled);
+
+        let held = &mut self.items[index];
+        held.quantity -= 1;
+        let remaining = held.quantity;
+        if remaining == 0 {
+            self.items.remove(index);
         }
+
+        Ok(ConsumableResult {
+            rolled,
+            healed,
+            remaining,
+        })
     }
 
     /// Gets the walking speed of the character
+    ///
+    /// Monk unarmored movement only applies while the character isn't wearing armor or using a
+    /// shield. If any feature sets a [minimum speed](FeatureEffect::SpeedFloor), that's applied
+    /// last, after any penalties.
+    ///
+    /// To see where the speed comes from, see [Character::speed_breakdown].
     pub fn speed(&self) -> usize {
-        let speed_bonus: usize = self
-            .race_features()
-            .into_iter()
-            .chain(self.class_features())
-            .chain(self.bonus_features.iter())
-            .flat_map(|v| v.effects.iter())
-            .map(|effect| match effect {
-                FeatureEffect::SpeedBonus(n) => *n,
-                FeatureEffect::UnarmoredMovement => self.unarmored_movement(),
-                _ => 0,
-            })
-            .sum();
+        self.speed_breakdown().total
+    }
+
+    /// Lists everything that adds to or takes away from the character's walking speed, along with
+    /// the final [speed](Character::speed).
+    ///
+    /// The speed starts at the race's speed. Then bonuses from features are added, and penalties
+    /// are taken away: 10 feet for wearing armor without enough strength, and 10 or 20 feet for
+    /// being [encumbered](Character::encumbrance_level) if the character uses the
+    /// [variant encumbrance](Character::variant_encumbrance) rules. The speed can't go below 0, or
+    /// below a [minimum speed](FeatureEffect::SpeedFloor) set by a feature.
+    pub fn speed_breakdown(&self) -> SpeedBreakdown {
+        let mut parts = vec![(SpeedSource::Race, self.race.speed as isize)];
+        let mut floor = None;
+
+        let unarmored = self.is_unarmored();
+        for (source, feature) in self.features_with_source() {
+            for effect in &feature.effects {
+                let bonus = match effect {
+                    FeatureEffect::SpeedBonus(n) => *n,
+                    FeatureEffect::UnarmoredMovement if unarmored => self.unarmored_movement(),
+                    FeatureEffect::SpeedFloor(n) => {
+                        floor = floor.max(Some(*n));
+                        continue;
+                    }
+                    _ => continue,
+                };
+                let source = SpeedSource::Feature(source, feature.name.clone());
+                parts.push((source, bonus as isize));
+            }
+        }
 
-        self.race.speed + speed_bonus
+        // heavy armor without enough strength slows you down by 10 feet
+        let strength = self.stats().strength;
+        let too_heavy = self
+            .equipped_items()
+            .iter()
+            .any(|i| match &i.item.item_type {
+                ItemType::Armor(armor) => armor
+                    .strength_minimum
+                    .is_some_and(|min| strength < min as isize),
+                _ => false,
+            });
+        if too_heavy {
+            parts.push((SpeedSource::HeavyArmor, -10));
+        }
+
+        let encumbrance = self.encumbrance_level();
+        let encumbrance_penalty = match encumbrance {
+            _ if !self.variant_encumbrance => 0,
+            Encumbrance::Unencumbered => 0,
+            Encumbrance::Encumbered => -10,
+            Encumbrance::HeavilyEncumbered => -20,
+        };
+        if encumbrance_penalty != 0 {
+            parts.push((SpeedSource::Encumbrance(encumbrance), encumbrance_penalty));
+        }
+
+        let speed = parts.iter().map(|(_, n)| n).sum::<isize>().max(0) as usize;
+        let speed = speed.max(floor.unwrap_or(0));
+
+        // exhaustion halves speed at level 2, and takes it to 0 at level 5
+        let exhaustion_penalty = match self.exhaustion {
+            0..=1 => 0,
+            2..=4 => speed - speed / 2,
+            _ => speed,
+        };
+        if exhaustion_penalty != 0 {
+            let source = SpeedSource::Exhaustion(self.exhaustion);
+            parts.push((source, -(exhaustion_penalty as isize)));
+        }
+
+        SpeedBreakdown {
+            parts,
+            floor,
+            total: speed - exhaustion_penalty,
+        }
     }
 
     /// Returns the different speeds of the character, e.g. flying and climbing.
@@ -1178,13 +3158,160 @@ impl Character {
                 FeatureEffect::BurrowingSpeed(s) => add_speed!(speeds.burrowing, *s),
                 FeatureEffect::ClimbingSpeed(s) => add_speed!(speeds.climbing, *s),
                 FeatureEffect::SwimmingSpeed(s) => add_speed!(speeds.swimming, *s),
-                _ => panic!(),
+                _ => (),
             };
         }
 
         speeds
     }
 
+    /// Returns the character's special senses, like darkvision.
+    ///
+    /// Each is the longest range granted by any of the character's features, including their race
+    /// and subrace. A human would have `character.senses().darkvision == None`, and a dwarf would
+    /// have `Some(60)`.
+    pub fn senses(&self) -> Senses {
+        let mut senses = Senses::default();
+        let effects = self
+            .total_features()
+            .into_iter()
+            .flat_map(|v| v.effects.iter());
+
+        for effect in effects {
+            let (sense, range) = match effect {
+                FeatureEffect::Darkvision(r) => (&mut senses.darkvision, *r),
+                FeatureEffect::Blindsight(r) => (&mut senses.blindsight, *r),
+                FeatureEffect::Tremorsense(r) => (&mut senses.tremorsense, *r),
+                _ => continue,
+            };
+            *sense = Some(sense.map_or(range, |v| v.max(range)));
+        }
+
+        senses
+    }
+
+    /// Starts a new turn for the character, resetting their movement to their [speed](Character::speed)
+    /// and giving back their actions, bonus actions, and reaction.
+    pub fn start_turn(&mut self) {
+        self.turn = TurnState {
+            movement_remaining: self.speed(),
+            ..Default::default()
+        };
+    }
+
+    /// Moves the character a distance in feet, using up their movement for the turn.
+    ///
+    /// Moving through difficult terrain costs twice as much movement. Returns false without
+    /// moving if the character doesn't have enough movement left.
+    pub fn move_distance(&mut self, feet: usize, difficult_terrain: bool) -> bool {
+        let cost = if difficult_terrain { feet * 2 } else { feet };
+        if cost > self.turn.movement_remaining {
+            return false;
+        }
+        self.turn.movement_remaining -= cost;
+        true
+    }
+
+    /// Takes the Dash action, gaining extra movement equal to the character's speed for the
+    /// turn.
+    ///
+    /// This doubles the movement the character has for the turn, after any penalties to their
+    /// speed have been applied.
+    pub fn dash(&mut self) {
+        self.turn.movement_remaining += self.speed();
+        self.turn.dashed = true;
+    }
+
+    /// Takes the Disengage action. The character's movement doesn't provoke opportunity attacks
+    /// for the rest of the turn, which is up to whatever is running the combat to enforce.
+    pub fn disengage(&mut self) {
+        self.turn.disengaged = true;
+    }
+
+    /// The actions, bonus actions, and reactions the character has this turn, before any are
+    /// used.
+    ///
+    /// Everyone gets one of each. Each [FeatureEffect::ExtraBonusAction] the character has adds
+    /// a bonus action, and using [Action Surge](Character::action_surge) adds an action for the
+    /// turn it's used on.
+    pub fn action_economy(&self) -> ActionEconomy {
+        let extra_bonus_actions = self
+            .total_features()
+            .iter()
+            .flat_map(|feature| feature.effects.iter())
+            .filter(|effect| matches!(effect, FeatureEffect::ExtraBonusAction))
+            .count();
+
+        ActionEconomy {
+            actions: 1 + usize::from(self.turn.action_surged),
+            bonus_actions: 1 + extra_bonus_actions,
+            reactions: 1,
+        }
+    }
+
+    /// Uses one of the character's actions for the turn. Returns false if they have none left.
+    pub fn use_action(&mut self) -> bool {
+        if self.turn.actions_used >= self.action_economy().actions {
+            return false;
+        }
+        self.turn.actions_used += 1;
+        true
+    }
+
+    /// Uses one of the character's bonus actions for the turn. Returns false if they have none
+    /// left.
+    pub fn use_bonus_action(&mut self) -> bool {
+        if self.turn.bonus_actions_used >= self.action_economy().bonus_actions {
+            return false;
+        }
+        self.turn.bonus_actions_used += 1;
+        true
+    }
+
+    /// Uses the character's reaction. Returns false if they've already used it since the start
+    /// of their last turn.
+    pub fn use_reaction(&mut self) -> bool {
+        if self.turn.reactions_used >= self.action_economy().reactions {
+            return false;
+        }
+        self.turn.reactions_used += 1;
+        true
+    }
+
+    /// Uses the fighter's Action Surge, gaining an extra action this turn.
+    ///
+    /// This spends one use of the first class resource (see [TrackedField]) named "Action
+    /// Surge". Returns false without spending anything if there are no uses left, or if Action
+    /// Surge was already used this turn.
+    pub fn action_surge(&mut self) -> bool {
+        if self.turn.action_surged {
+            return false;
+        }
+        let uses = self.classes.iter_mut().find_map(|class| {
+            class
+                .tracked_fields
+                .iter_mut()
+                .find(|(field, current)| {
+                    field.name.eq_ignore_ascii_case("action surge") && *current > 0
+                })
+                .map(|(_, current)| current)
+        });
+        let Some(uses) = uses else {
+            return false;
+        };
+        *uses -= 1;
+        self.turn.action_surged = true;
+        true
+    }
+
+    // whether the character has no armor or shield equipped, as monk features require.
+    fn is_unarmored(&self) -> bool {
+        !self
+            .equipped_items()
+            .iter()
+            .any(|i| matches!(i.item.item_type, ItemType::Armor(_) | ItemType::Shield))
+    }
+
     fn unarmored_movement(&self) -> usize {
         let level = self
             .classes
@@ -1213,10 +3340,40 @@ impl Character {
         // if the class has spellcasting, the new spell slots need to be calculated.
         self.level_up_spellslots(spell_slots_before);
         self.level_up_warlock_pactslots(pact_slots_before);
+        self.update_racial_spells();
 
         Some(v)
     }
 
+    /// Gives the character experience points.
+    pub fn add_xp(&mut self, xp: usize) {
+        self.xp += xp;
+    }
+
+    /// The total experience points needed for the character to reach their next level, or [None]
+    /// if they're already level 20.
+    pub fn next_level_xp(&self) -> Option<usize> {
+        XP_BY_LEVEL.get(self.level()).copied()
+    }
+
+    /// Returns true if the character has enough experience points to gain a level.
+    pub fn can_level_up(&self) -> bool {
+        self.next_level_xp().is_some_and(|needed| self.xp >= needed)
+    }
+
+    /// Levels up in the given class like [Character::level_up], but only if the character has
+    /// enough experience points for it. See [Character::can_level_up].
+    ///
+    /// Returns the character's new level in that class. Campaigns that level by milestones should
+    /// use [Character::level_up] instead.
+    pub fn level_up_with_xp(&mut self, class: &Class) -> Result<usize, LevelError> {
+        let needed = self.next_level_xp().ok_or(LevelError::MaxLevel)?;
+        if self.xp < needed {
+            return Err(LevelError::NotEnoughXp { needed });
+        }
+        self.level_up(class).ok_or(LevelError::FailedMulticlassPrereq)
+    }
+
     fn level_up_etc_specific(&mut self, class: &Class) {
         for specced_class in self.classes.iter_mut() {
             let level_before = specced_class.level - 1;
@@ -1348,6 +3505,7 @@ impl Character {
         self.hp = self.max_hp();
         self.level_up_spellslots(spell_slots_before);
         self.level_up_warlock_pactslots(pact_slots_before);
+        self.update_racial_spells();
         Some(new_class_level)
     }
 
@@ -1393,13 +3551,14 @@ impl Character {
 
     /// Returns the total equipment proficiencies for the character.
     ///
-    /// This aggregates proficiencies from the class, possible race features, and
+    /// This aggregates proficiencies from the class, possible race features, feats, and
     /// [Character::bonus_features].
     pub fn equipment_proficiencies(&self) -> EquipmentProficiencies {
         let feature_effects = self
             .race_features()
             .into_iter()
             .chain(self.subrace_features())
+            .chain(self.feat_features())
             .chain(self.bonus_features.iter())
             .flat_map(|v| v.effects.iter());
 
@@ -1463,11 +3622,48 @@ impl Character {
             damage_roll: DamageRoll::new(1, 4, bonus, DamageType::Bludgeoning),
             two_handed: false,
             second_attack: false,
+            range: None,
+            reach: Some(5),
         });
 
         weapon_actions_vec
     }
 
+    /// Gets the equipped weapon action with the highest average damage.
+    ///
+    /// Off-hand attacks from light weapons aren't considered, since they take a bonus action.
+    /// Ties go to the action with the higher attack bonus (so a weapon the character is proficient
+    /// with beats one they aren't), and then to an actual weapon over an unarmed strike. This
+    /// only returns [None] if [Character::weapon_actions] is somehow empty.
+    pub fn best_weapon_action(&self) -> Option<WeaponAction> {
+        self.weapon_actions()
+            .into_iter()
+            .filter(|action| !action.second_attack)
+            .max_by(|a, b| {
+                a.damage_roll
+                    .average()
+                    .total_cmp(&b.damage_roll.average())
+                    .then(a.attack_bonus.cmp(&b.attack_bonus))
+                    .then((a.name != "Unarmed Strike").cmp(&(b.name != "Unarmed Strike")))
+            })
+    }
+
+    /// Gets the main [WeaponAction] the character would have with a weapon, whether or not they
+    /// have it. Useful for deciding whether a weapon is worth picking up or buying.
+    ///
+    /// This is calculated the same way as [Character::weapon_actions], with `name` used to check
+    /// proficiency with specific weapons. Only the one-handed main attack is returned.
+    pub fn hypothetical_weapon_attack(&self, weapon: &Weapon, name: &str) -> WeaponAction {
+        weapon_actions_inner(
+            name,
+            weapon,
+            &self.stats().modifiers(),
+            &self.equipment_proficiencies(),
+            self.proficiency_bonus(),
+        )
+        .swap_remove(0)
+    }
+
     /// Gets the attacks possible from all spells prepared in any class. The resulting
     /// [SpellAction] has the final calculated attack modifer and damage roll needed to preform an
     ///  attack.
@@ -1481,13 +3677,26 @@ impl Character {
     ///
     /// If the character is a warlock, it still returns everthing up until their maximum spell
     /// level, since they're still able to downcast below their spell slot's level.
+    ///
+    /// Regular spell slots and pact slots are capped separately. When multiclassing, a spell is
+    /// included at each level either kind of slot can cast it at, and its
+    /// [slot pool](SpellAction::slot_pool) says which ones can.
+    ///
+    /// [Racial spells](Character::racial_spells) are included too, using their own ability for
+    /// the attack modifier and save dc. Ones that don't use a spell slot are only cast at their
+    /// own level.
     pub fn spell_actions(&self) -> Vec<SpellAction> {
-        let modifiers = self.stats().modifiers();
+        self.spell_actions_with_spells()
+            .into_iter()
+            .map(|(_, _, action)| action)
+            .collect()
+    }
 
-        let max_slot_level = match self.max_slot_level() {
-            Some(v) => v,
-            None => return vec![],
-        };
+    // spell_actions, along with the spell each comes from, and how it's cast if it's a racial
+    // spell
+    fn spell_actions_with_spells(&self) -> Vec<(&Spell, Option<RacialSpellUse>, SpellAction)> {
+        let modifiers = self.stats().modifiers();
+        let slot_caps = self.slot_caps();
 
         let mut char_spell_actions = vec![];
         for (index, class) in self
@@ -1499,35 +3708,253 @@ impl Character {
             let spellcasting_stuff = self
                 .spellcasting_scores_with_modifiers(index, &modifiers)
                 .zip(class.spellcasting.as_ref());
-            let ((_, attack_mod), (_, spells)) = match spellcasting_stuff {
+            let ((save_dc, attack_mod), (_, spells)) = match spellcasting_stuff {
                 Some(s) => s,
                 _ => continue,
             };
 
             let class_spell_actions = spells
                 .iter()
-                .filter_map(|s| spell_actions(s, attack_mod, max_slot_level, self.level()))
-                .flat_map(|v| v.into_iter())
-                .collect::<Vec<_>>();
+                .filter_map(|s| {
+                    let actions = spell_actions(s, (save_dc, attack_mod), slot_caps, self.level())?;
+                    Some(actions.into_iter().map(move |a| (s, None, a)))
+                })
+                .flatten();
             char_spell_actions.extend(class_spell_actions);
         }
+
+        for (spell, ability, usage) in self.racial_spells.iter() {
+            let scores = self.scores_for_ability(*ability, &modifiers);
+            let caps = match usage {
+                RacialSpellUse::SpellSlot => slot_caps,
+                _ => (spell.level, 0),
+            };
+            let Some(mut actions) = spell_actions(spell, scores, caps, self.level()) else {
+                continue;
+            };
+            if *usage != RacialSpellUse::SpellSlot {
+                for action in actions.iter_mut() {
+                    action.slot_pool = SlotPool::NoSlot;
+                }
+            }
+            char_spell_actions.extend(actions.into_iter().map(|a| (spell, Some(*usage), a)));
+        }
         char_spell_actions
     }
 
-    fn max_slot_level(&self) -> Option<usize> {
-        let spell_slots = self
-            .spell_slots()
-            .map(|v| v.0.into_iter().position(|v| v == 0).unwrap_or(8) + 1);
-        let pact_slots = self.pact_slots().map(|v| v.level + 1);
-
-        match (spell_slots, pact_slots) {
-            (Some(s), Some(p)) => Some(s.max(p)),
-            (Some(s), None) => Some(s),
-            (None, Some(p)) => Some(p),
-            (None, None) => None,
+    /// The expected (average) damage of a single cast of a spell action against a target.
+    ///
+    /// `target_save_bonus_or_ac` is the target's AC for spells that use an attack roll, or the
+    /// target's saving throw modifier for spells that use a saving throw. It's ignored for spells
+    /// that always hit.
+    ///
+    /// Attack spells account for the chance to hit, including a natural 1 always missing and a
+    /// natural 20 being a critical hit. Save spells account for the chance the target fails the
+    /// save, and deal half damage on a success if the spell allows it.
+    pub fn expected_spell_damage(
+        &self,
+        spell_action: &SpellAction,
+        target_save_bonus_or_ac: isize,
+    ) -> f64 {
+        let damage = spell_action.damage_roll;
+        match spell_action.mechanic {
+            SpellMechanic::Attack => {
+                let hit = d20_success_chance(
+                    spell_action.spell_attack_mod,
+                    target_save_bonus_or_ac,
+                )
+                .clamp(0.05, 0.95);
+                // a critical hit doubles the dice, but not the bonus
+                let dice_average = damage.average() - damage.bonus as f64;
+                hit * damage.average() + 0.05 * dice_average
+            }
+            SpellMechanic::Save {
+                half_on_success,
+                ..
+            } => {
+                let save =
+                    d20_success_chance(target_save_bonus_or_ac, spell_action.spell_save_dc);
+                let on_success = if half_on_success {
+                    damage.average() / 2.0
+                } else {
+                    0.0
+                };
+                (1.0 - save) * damage.average() + save * on_success
+            }
+            SpellMechanic::Automatic => damage.average(),
         }
     }
 
+    /// Picks the spell that would deal the most expected damage to a group of targets, e.g. for
+    /// running a character as an NPC.
+    ///
+    /// This is only a heuristic. Each spell is cast at its own level (or at the character's level
+    /// for a cantrip), with the character's best spell save DC and attack modifier. Its score is
+    /// the [expected damage](Character::expected_spell_damage) against each target, using their
+    /// AC or saving throw, adjusted for their damage resistances, immunities, and
+    /// vulnerabilities, and capped at their remaining hp. Area spells (ones whose description
+    /// mentions a radius, cone, cube, sphere, cylinder, or line) are assumed to catch every
+    /// target, and add up their damage. Other spells only count the target they'd hurt most.
+    ///
+    /// Spells the character doesn't have a spell slot for are skipped, and so are spells without
+    /// damage, since their effects can't be compared. This returns [None] if no spell deals any
+    /// damage, or if the character can't cast spells.
+    pub fn recommend_spell<'a>(
+        &self,
+        targets: &[StatBlock],
+        available: &[&'a Spell],
+    ) -> Option<&'a Spell> {
+        let scores = (0..self.classes.len())
+            .filter_map(|index| self.spellcasting_scores(index))
+            .max()?;
+        let max_level = self.max_castable_level();
+
+        available
+            .iter()
+            .copied()
+            .filter(|spell| spell.level <= max_level)
+            .filter_map(|spell| {
+                let actions = spell_actions(spell, scores, (spell.level, 0), self.level())?;
+                let area = is_area_spell(spell);
+                let damage = actions
+                    .iter()
+                    .map(|action| {
+                        let damages = targets
+                            .iter()
+                            .map(|target| self.expected_spell_damage_against(action, target));
+                        if area {
+                            damages.sum()
+                        } else {
+                            damages.fold(0.0, f64::max)
+                        }
+                    })
+                    .fold(0.0, f64::max);
+                (damage > 0.0).then_some((spell, damage))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(spell, _)| spell)
+    }
+
+    // expected damage against a monster, after its defenses and capped at its hp
+    fn expected_spell_damage_against(&self, action: &SpellAction, target: &StatBlock) -> f64 {
+        let save_bonus_or_ac = match action.mechanic {
+            SpellMechanic::Save { stat, .. } => target.save_mod(stat),
+            _ => target.ac,
+        };
+        let damage = self.expected_spell_damage(action, save_bonus_or_ac);
+        // the fraction of damage that gets through, like 0.5 for resistance
+        let defenses = target.defenses.adjust(2, action.damage_roll.damage_type) as f64 / 2.0;
+        (damage * defenses).min(target.hp as f64)
+    }
+
+    /// The number of attacks the character makes when they take the attack action.
+    ///
+    /// This is one, plus one for each differently named Extra Attack feature (like the fighter's
+    /// "Extra Attack (2)"). Extra Attack from multiple classes doesn't stack.
+    pub fn attacks_per_action(&self) -> usize {
+        let mut names: Vec<&str> = self
+            .total_features()
+            .into_iter()
+            .map(|f| f.name.as_str())
+            .filter(|n| n.starts_with("Extra Attack"))
+            .collect();
+        names.sort();
+        names.dedup();
+        1 + names.len()
+    }
+
+    /// The expected (average) damage the character deals on their action each turn.
+    ///
+    /// This is the better of attacking with the [best weapon](Character::best_weapon_action) for
+    /// every [attack](Character::attacks_per_action) they get, or casting their most damaging
+    /// cantrip. Hit and save chances are accounted for the same way as
+    /// [Character::expected_spell_damage]. Leveled spells, bonus actions, and class features like
+    /// sneak attack aren't counted.
+    pub fn expected_turn_damage(&self, target_ac: isize, target_save_bonus: isize) -> f64 {
+        let weapon = self
+            .best_weapon_action()
+            .map(|action| {
+                let damage = action.damage_roll;
+                let hit = d20_success_chance(action.attack_bonus, target_ac).clamp(0.05, 0.95);
+                let dice_average = damage.average() - damage.bonus as f64;
+                (hit * damage.average() + 0.05 * dice_average) * self.attacks_per_action() as f64
+            })
+            .unwrap_or(0.0);
+
+        self.spell_actions()
+            .iter()
+            .filter(|action| action.spell_level == 0)
+            .map(|action| {
+                let target = match action.mechanic {
+                    SpellMechanic::Save { .. } => target_save_bonus,
+                    _ => target_ac,
+                };
+                self.expected_spell_damage(action, target)
+            })
+            .fold(weapon, f64::max)
+    }
+
+    /// Estimates the challenge rating the character would have as a monster, e.g. for using a
+    /// retired character as a villain.
+    ///
+    /// This follows the Dungeon Master's Guide's "Creating a Monster" steps:
+    /// - The defensive CR is the row of the monster statistics table with the character's max hp,
+    ///   moved up or down one row for every 2 points their AC is above or below that row's AC.
+    /// - The offensive CR is the row with the damage they deal in a round if every attack hits,
+    ///   moved one row for every 2 points their attack bonus (or save DC, for a save cantrip) is
+    ///   above or below that row's.
+    ///
+    /// The result is the average of the two, so it can land between CRs (like 0.3125, between
+    /// 1/4 and 1/2) and should be rounded to whichever CR is closest.
+    ///
+    /// This is only a rough estimate. Damage is counted the same way as
+    /// [Character::expected_turn_damage], so leveled spells, limited use features, and bonus
+    /// actions are ignored, and resistances, healing, and saving throws don't change the
+    /// defensive CR. Spellcasters in particular will come out lower than they should.
+    pub fn estimated_cr(&self) -> f32 {
+        let hp = self.max_hp();
+        let defensive_row = MONSTER_STATS_BY_CR
+            .iter()
+            .position(|row| row.2 >= hp)
+            .unwrap_or(MONSTER_STATS_BY_CR.len() - 1);
+        let ac_difference = self.ac() - MONSTER_STATS_BY_CR[defensive_row].1;
+        let defensive = shift_cr_row(defensive_row, ac_difference);
+
+        // (damage per round, attack bonus or save dc, whether it's a save dc)
+        let weapon = self.best_weapon_action().map(|action| {
+            let damage = action.damage_roll.average() * self.attacks_per_action() as f64;
+            (damage, action.attack_bonus, false)
+        });
+        let cantrips = self
+            .spell_actions()
+            .into_iter()
+            .filter(|action| action.spell_level == 0)
+            .map(|action| match action.mechanic {
+                SpellMechanic::Save { .. } => {
+                    (action.damage_roll.average(), action.spell_save_dc, true)
+                }
+                _ => (action.damage_roll.average(), action.spell_attack_mod, false),
+            });
+        let (damage, bonus, is_dc) = weapon
+            .into_iter()
+            .chain(cantrips)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap_or((0.0, 0, false));
+
+        let offensive_row = MONSTER_STATS_BY_CR
+            .iter()
+            .position(|row| row.4 as f64 >= damage.ceil())
+            .unwrap_or(MONSTER_STATS_BY_CR.len() - 1);
+        let expected_bonus = if is_dc {
+            MONSTER_STATS_BY_CR[offensive_row].5
+        } else {
+            MONSTER_STATS_BY_CR[offensive_row].3
+        };
+        let offensive = shift_cr_row(offensive_row, bonus - expected_bonus);
+
+        (defensive + offensive) / 2.0
+    }
+
     /// Gets the extra attacks granted by any feature(s) that do so.
     /// The resulting [ComputedCustomAction] has the final calculations needed to preform an
     /// attack.
@@ -1548,6 +3975,129 @@ impl Character {
             .collect()
     }
 
+    /// Every attack the character could make, from weapons, spells, and
+    /// [features](Character::ect_actions), sorted by average damage with the highest first.
+    ///
+    /// This puts [Character::weapon_actions], [Character::spell_actions], and
+    /// [Character::ect_actions] into one shape, along with what each one costs to use and whether
+    /// the character can use it right now. A spell is usable if there's an unspent slot of its
+    /// level (or it doesn't need one), and a weapon that uses ammunition is usable if the
+    /// character has the right kind: arrows for bows, bolts for crossbows, bullets for slings, and
+    /// needles for blowguns. A feature that spends a class resource, like ki, is usable if there's
+    /// enough of it left.
+    pub fn attack_options(&self) -> Vec<AttackOption> {
+        let mut options = vec![];
+
+        let has_ammunition = |kinds: &[&str]| {
+            self.items.iter().any(|held| {
+                let name = held.item.name.to_lowercase();
+                held.quantity > 0 && kinds.iter().any(|ammo| name.contains(ammo))
+            })
+        };
+        for action in self.weapon_actions() {
+            let uses_ammunition = self.equipped_items().iter().any(|i| {
+                i.item.name == action.name
+                    && matches!(&i.item.item_type, ItemType::Weapon(w) if w.properties.ammunition)
+            });
+            let has_ammunition = match ammunition_for(&action.name) {
+                Some(kind) => has_ammunition(&[kind]),
+                None => has_ammunition(&["arrow", "bolt", "bullet", "needle"]),
+            };
+            let range = [
+                action.reach.map(|reach| format!("reach {reach} ft.")),
+                action
+                    .range
+                    .map(|(normal, long)| format!("range {normal}/{long} ft.")),
+            ];
+            let range: Vec<String> = range.into_iter().flatten().collect();
+            options.push(AttackOption::new(
+                AttackKind::Weapon,
+                &action.name,
+                AttackRoll::ToHit(action.attack_bonus),
+                action.damage_roll,
+                (!range.is_empty()).then(|| range.join(" or ")),
+                AttackCost::Free,
+                has_ammunition || !uses_ammunition,
+            ));
+        }
+
+        for (spell, usage, action) in self.spell_actions_with_spells() {
+            let roll = match action.mechanic {
+                SpellMechanic::Attack => AttackRoll::ToHit(action.spell_attack_mod),
+                SpellMechanic::Save { stat, .. } => AttackRoll::Save {
+                    dc: action.spell_save_dc,
+                    stat,
+                },
+                SpellMechanic::Automatic => AttackRoll::Automatic,
+            };
+            let level = action.spell_level as usize;
+            let (cost, usable) = match usage {
+                Some(RacialSpellUse::AtWill) => (AttackCost::Free, true),
+                Some(RacialSpellUse::OncePerLongRest { used }) => {
+                    (AttackCost::OncePerLongRest, !used)
+                }
+                _ if level == 0 => (AttackCost::Free, true),
+                _ => (AttackCost::SpellSlot(level), self.has_slot_of_level(level)),
+            };
+            options.push(AttackOption::new(
+                AttackKind::Spell,
+                &action.name,
+                roll,
+                action.damage_roll,
+                Some(spell.range.clone()),
+                cost,
+                usable,
+            ));
+        }
+
+        for action in self.ect_actions() {
+            let (cost, usable) = match action.resource_cost {
+                Some((name, amount)) => {
+                    let usable = self
+                        .tracked_field_remaining(&name)
+                        .is_some_and(|left| left >= amount);
+                    (AttackCost::Resource { name, amount }, usable)
+                }
+                None => (AttackCost::Free, true),
+            };
+            options.push(AttackOption::new(
+                AttackKind::Feature,
+                &action.name,
+                AttackRoll::ToHit(action.attack_bonus),
+                action.damage_roll,
+                None,
+                cost,
+                usable,
+            ));
+        }
+
+        // stable, so equal options keep weapons before spells before features
+        options.sort_by(|a, b| b.average_damage.total_cmp(&a.average_damage));
+        options
+    }
+
+    // how much of the first class resource with this name is left, if the character has it
+    fn tracked_field_remaining(&self, name: &str) -> Option<usize> {
+        self.classes
+            .iter()
+            .flat_map(|class| class.tracked_fields.iter())
+            .find(|(field, _)| field.name.eq_ignore_ascii_case(name))
+            .map(|(_, current)| *current)
+    }
+
+    // if there's an unspent spell slot or pact slot of exactly this level
+    fn has_slot_of_level(&self, level: usize) -> bool {
+        let slot = self
+            .available_spell_slots
+            .as_ref()
+            .and_then(|slots| slots.0.get(level.checked_sub(1)?))
+            .is_some_and(|n| *n > 0);
+        let pact = self
+            .available_pact_slots
+            .is_some_and(|p| p.num > 0 && p.level == level);
+        slot || pact
+    }
+
     fn parse_custom_action(&self, c: &CustomAction) -> ComputedCustomAction {
         let modifiers = self.stats().modifiers();
         let stats_attack_bonus = c
@@ -1564,15 +4114,98 @@ impl Character {
             .sum::<isize>();
         let damage_roll_bonus = (c.damage_roll.bonus + stats_damage_bonus).max(0);
 
-        let damage_roll = DamageRoll {
-            bonus: damage_roll_bonus,
-            ..c.damage_roll
+        let damage_roll = DamageRoll {
+            bonus: damage_roll_bonus,
+            ..c.damage_roll
+        };
+
+        ComputedCustomAction {
+            name: c.name.clone(),
+            attack_bonus,
+            damage_roll,
+            resource_cost: c.resource_cost.clone(),
+        }
+    }
+
+    /// Gets the class specific fields of a class at every level, e.g. the sneak attack dice of
+    /// a rogue from level 1 to 20. This is useful for showing how a class progresses.
+    ///
+    /// For the values at the class's current level, see [SpeccedClass::get_class_specific].
+    ///
+    /// Returns [None] if `class_index` is out of bounds.
+    pub fn class_specific_progression(
+        &self,
+        class_index: usize,
+    ) -> Option<&HashMap<String, [String; 20]>> {
+        self.classes
+            .get(class_index)
+            .map(|class| class.class_specific_progression())
+    }
+
+    /// The DC for a creature to resist the effects of the character's Battle Master maneuvers.
+    ///
+    /// This is 8 + the character's proficiency bonus + their strength or dexterity modifier,
+    /// whichever is higher.
+    pub fn maneuver_save_dc(&self) -> isize {
+        let modifiers = self.stats().modifiers().stats;
+        8 + self.proficiency_bonus() + modifiers.strength.max(modifiers.dexterity)
+    }
+
+    /// Spends a superiority die on a maneuver.
+    ///
+    /// This uses the first class resource (see [TrackedField]) named "Superiority Dice" that has
+    /// a die size set in [TrackedField::class_specific_die] and dice left. It returns the die to
+    /// add to the attack's damage, or [None] if there are no superiority dice to spend.
+    ///
+    /// `damage_type` should be the damage type of the attack the maneuver is used with, and is
+    /// given to the returned die.
+    pub fn use_maneuver(&mut self, damage_type: DamageType) -> Option<DamageRoll> {
+        let (current, die) = self.classes.iter_mut().find_map(|class| {
+            let (field, current) = class.tracked_fields.iter_mut().find(|(field, current)| {
+                field.name.eq_ignore_ascii_case("superiority dice") && *current > 0
+            })?;
+            let die = field
+                .class_specific_die
+                .as_ref()
+                .and_then(|v| class.class_specific.get(v))
+                .and_then(|v| v.trim_start_matches('d').parse().ok())?;
+            Some((current, die))
+        })?;
+
+        *current -= 1;
+        Some(DamageRoll::new(1, die, 0, damage_type))
+    }
+
+    /// Previews what a short rest would restore, without taking it.
+    ///
+    /// This lists the pact magic slots that would be regained, the hit dice available to spend,
+    /// and any class resources (see [TrackedField]) that recharge on a short rest, along with how
+    /// much of each would be regained. Resources that are already full aren't listed.
+    pub fn short_rest_recoverable(&self) -> ShortRestRecovery {
+        let pact_slots = match (self.pact_slots(), &self.available_pact_slots) {
+            (Some(max), Some(available)) => max.num.saturating_sub(available.num),
+            _ => 0,
         };
 
-        ComputedCustomAction {
-            name: c.name.clone(),
-            attack_bonus,
-            damage_roll,
+        let tracked_fields = self
+            .classes
+            .iter()
+            .flat_map(|class| {
+                class.tracked_fields.iter().filter_map(|(field, current)| {
+                    if !field.short_rest {
+                        return None;
+                    }
+                    let max = tracked_field_max(field, &class.class_specific)?;
+                    let regained = max.saturating_sub(*current);
+                    (regained > 0).then(|| (field.name.clone(), regained))
+                })
+            })
+            .collect();
+
+        ShortRestRecovery {
+            pact_slots,
+            hit_dice: self.level().saturating_sub(self.spent_hit_dice),
+            tracked_fields,
         }
     }
 
@@ -1586,6 +4219,12 @@ impl Character {
     /// Returns a bool of it it succeeded or not. The function fails if the amount of hit die are
     /// more than what's available, or if the hit die override has a different length than the amount of hit die spent.
     ///
+    /// Hit dice from the character's first class are spent first, and then the largest ones they
+    /// have left. See [Character::available_hit_dice].
+    ///
+    /// Pact magic slots, and any class resources (see [TrackedField]) that recharge on a short
+    /// rest, are regained. [Character::short_rest_recoverable] previews this.
+    ///
     /// ```
     /// # #[cfg(feature = "dnd5eapi")] {
     /// # use tokio::runtime::Runtime;
@@ -1616,7 +4255,7 @@ impl Character {
     /// # }
     /// ```
     pub fn short_rest(&mut self, die_amount: usize, manual_hit_die: Option<Vec<usize>>) -> bool {
-        let hit_die = self
+        let first_hit_die = self
             .classes
             .first()
             .expect("Character should have a class")
@@ -1626,14 +4265,26 @@ impl Character {
             return false;
         }
 
+        // dice from the first class are spent first, then the largest ones left
+        let mut hit_dice = self.hit_dice_state();
+        hit_dice.sort_by_key(|(die, _, _)| (*die != first_hit_die, Reverse(*die)));
+        let mut spending = vec![];
+        let mut remaining = die_amount;
+        for (die, total, spent) in hit_dice.iter_mut() {
+            let amount = remaining.min(*total - *spent);
+            *spent += amount;
+            remaining -= amount;
+            spending.extend(std::iter::repeat_n(*die, amount));
+        }
+
         let con_mod = if die_amount == 0 {
             0
         } else {
             self.stats().modifiers().stats.constitution.max(0) as usize
         };
 
-        let hit_die_rolls = match manual_hit_die {
-            None => (die_average_max(hit_die) + con_mod) * die_amount,
+        let hit_die_rolls: usize = match manual_hit_die {
+            None => spending.iter().map(|d| die_average_max(*d) + con_mod).sum(),
             Some(v) => {
                 if v.len() != die_amount {
                     return false;
@@ -1642,26 +4293,157 @@ impl Character {
             }
         };
 
-        let max_hp = self.max_hp();
-        self.hp = (self.hp + hit_die_rolls).min(max_hp);
+        self.heal(hit_die_rolls);
 
-        self.spent_hit_dice += die_amount;
+        self.set_hit_dice_state(&hit_dice);
 
         // if there's warlock spell slots, they're replenished.
         if self.available_pact_slots.is_some() {
             self.available_pact_slots = self.pact_slots();
         }
 
+        // regain features that recharge on a short rest
+        for class in self.classes.iter_mut() {
+            for (field, current) in class.tracked_fields.iter_mut() {
+                if !field.short_rest {
+                    continue;
+                }
+                if let Some(max) = tracked_field_max(field, &class.class_specific) {
+                    *current = max;
+                }
+            }
+        }
+
         true
     }
 
+    /// The number of each size of hit die the character has left to spend, as (number of faces,
+    /// amount available), largest dice first.
+    ///
+    /// A multiclassed character has hit dice of each of their classes' sizes. A fighter 2/wizard
+    /// 3 would have `[(10, 2), (6, 3)]` with none spent.
+    pub fn available_hit_dice(&self) -> Vec<(usize, usize)> {
+        self.hit_dice_state()
+            .into_iter()
+            .map(|(die, total, spent)| (die, total - spent))
+            .collect()
+    }
+
+    // (number of faces, total, spent) for each hit die size, largest first.
+    //
+    // spent_hit_dice is the source of truth for the total spent, since it can be set directly.
+    // If the spent sizes don't add up to it, the difference is taken from (or added to) the
+    // largest dice.
+    fn hit_dice_state(&self) -> Vec<(usize, usize, usize)> {
+        let mut totals: Vec<(usize, usize)> = vec![];
+        for class in self.classes.iter() {
+            match totals.iter_mut().find(|(die, _)| *die == class.hit_die) {
+                Some((_, total)) => *total += class.level,
+                None => totals.push((class.hit_die, class.level)),
+            }
+        }
+        totals.sort_by_key(|(die, _)| Reverse(*die));
+
+        let mut state: Vec<(usize, usize, usize)> = totals
+            .into_iter()
+            .map(|(die, total)| {
+                let spent = self.spent_hit_dice_by_size.get(&die).copied().unwrap_or(0);
+                (die, total, spent.min(total))
+            })
+            .collect();
+
+        let target = self.spent_hit_dice.min(self.level());
+        let mut spent: usize = state.iter().map(|v| v.2).sum();
+        for (_, total, die_spent) in state.iter_mut() {
+            if spent > target {
+                let removed = (spent - target).min(*die_spent);
+                *die_spent -= removed;
+                spent -= removed;
+            } else if spent < target {
+                let added = (target - spent).min(*total - *die_spent);
+                *die_spent += added;
+                spent += added;
+            }
+        }
+        state
+    }
+
+    fn set_hit_dice_state(&mut self, state: &[(usize, usize, usize)]) {
+        self.spent_hit_dice = state.iter().map(|v| v.2).sum();
+        self.spent_hit_dice_by_size = state.iter().map(|(die, _, spent)| (*die, *spent)).collect();
+    }
+
     /// Calculates and applies the effects of taking a long rest.
     ///
-    /// This regains all hp, spell slots, pact magic slots, spent hit dice, and
-    /// replenishes any features that recharge on a long rest.
+    /// This regains all hp, spell slots, pact magic slots, mystic arcana, spent hit dice, and
+    /// replenishes any features that recharge on a long rest. It also removes one level of
+    /// exhaustion. A [dead](Character::is_dead) character doesn't benefit from resting, and
+    /// nothing happens.
+    ///
+    /// Half of the character's hit dice (rounded up) are regained, largest dice first. Use
+    /// [Character::long_rest_recover_hit_dice] to pick which ones instead.
     pub fn long_rest(&mut self) {
+        if self.is_dead() {
+            return;
+        }
+        let mut regained = self.hit_dice_regained();
+        let picks: Vec<(usize, usize)> = self
+            .hit_dice_state()
+            .into_iter()
+            .map(|(die, _, spent)| {
+                let amount = spent.min(regained);
+                regained -= amount;
+                (die, amount)
+            })
+            .collect();
+        self.long_rest_inner(&picks);
+    }
+
+    /// Takes a long rest, picking which sizes of hit dice are regained. This is otherwise the
+    /// same as [Character::long_rest].
+    ///
+    /// `picks` are the (number of faces, amount) of the hit dice to regain. They have to add up
+    /// to half the character's level rounded up, or every spent hit die if fewer than that are
+    /// spent, and can't regain more of a size than have been spent.
+    ///
+    /// Returns false without resting if the picks aren't valid, or the character is dead.
+    pub fn long_rest_recover_hit_dice(&mut self, picks: &[(usize, usize)]) -> bool {
+        if self.is_dead() {
+            return false;
+        }
+        let state = self.hit_dice_state();
+        let picked = |die: usize| {
+            picks
+                .iter()
+                .filter(|(d, _)| *d == die)
+                .map(|(_, n)| n)
+                .sum::<usize>()
+        };
+
+        let total: usize = picks.iter().map(|(_, n)| n).sum();
+        let known_sizes = picks
+            .iter()
+            .all(|(d, n)| *n == 0 || state.iter().any(|(die, _, _)| die == d));
+        let within_spent = state.iter().all(|(die, _, spent)| picked(*die) <= *spent);
+        if total != self.hit_dice_regained() || !known_sizes || !within_spent {
+            return false;
+        }
+
+        self.long_rest_inner(picks);
+        true
+    }
+
+    // the number of hit dice a long rest regains
+    fn hit_dice_regained(&self) -> usize {
+        let half = (self.level() as f32 / 2.0).ceil() as usize;
+        half.min(self.spent_hit_dice.min(self.level()))
+    }
+
+    fn long_rest_inner(&mut self, hit_dice_picks: &[(usize, usize)]) {
+        self.remove_exhaustion(1);
+
         // regain all hp
-        self.hp = self.max_hp();
+        self.heal_to_full();
 
         // if there are spell slots, regain them
         if self.available_spell_slots.is_some() {
@@ -1673,10 +4455,29 @@ impl Character {
             self.available_pact_slots = self.pact_slots();
         }
 
+        // mystic arcana can be cast again
+        for arcanum in self.mystic_arcana.iter_mut() {
+            arcanum.used = false;
+        }
+
+        // and so can racial spells
+        for (_, _, usage) in self.racial_spells.iter_mut() {
+            if let RacialSpellUse::OncePerLongRest { used } = usage {
+                *used = false;
+            }
+        }
+
         // regain spent hit dice
-        self.spent_hit_dice = self.spent_hit_dice.min(self.level()); // make sure it's valid
-        let regained = (self.level() as f32 / 2.0).ceil() as usize;
-        self.spent_hit_dice = self.spent_hit_dice.saturating_sub(regained);
+        let mut hit_dice = self.hit_dice_state();
+        for (die, _, spent) in hit_dice.iter_mut() {
+            let regained: usize = hit_dice_picks
+                .iter()
+                .filter(|(d, _)| d == die)
+                .map(|(_, n)| n)
+                .sum();
+            *spent = spent.saturating_sub(regained);
+        }
+        self.set_hit_dice_state(&hit_dice);
 
         // regain features
         for class in self.classes.iter_mut() {
@@ -1686,12 +4487,7 @@ impl Character {
                 if !v.0.long_rest {
                     continue;
                 }
-                let class_specific_max: Option<usize> =
-                    v.0.class_specific_max
-                        .clone()
-                        .and_then(|ref v| specific_fields.get(v)?.parse().ok());
-                let max = v.0.hard_max.or(class_specific_max);
-                if let Some(s) = max {
+                if let Some(s) = tracked_field_max(&v.0, specific_fields) {
                     v.1 = s
                 }
             }
@@ -1800,8 +4596,12 @@ impl Character {
 
     /// Gets the amount of spells the class at the index can prepare or know.
     ///
+    /// For classes that know their spells, this is from
+    /// [Spellcasting::spells_known_per_level](super::spells::Spellcasting::spells_known_per_level)
+    /// if the class has it.
+    ///
     /// Returns [None] if the class does not exist, or if the class is not a spellcaster.
-    pub fn num_spells(&mut self, class_index: usize) -> Option<SpellsAvailable> {
+    pub fn num_spells(&self, class_index: usize) -> Option<SpellsAvailable> {
         let class_level = self.classes.get(class_index)?.level;
         if class_level == 0 {
             return None;
@@ -1814,7 +4614,10 @@ impl Character {
             .stats
             .get_stat_type(&spellcasting_ability);
         let num_cantrips = casting.cantrips_per_level[class_level - 1];
-        let num_spells = (class_level as isize + modifier).max(0) as usize;
+        let num_spells = match (casting.preperation_type, casting.spells_known_per_level) {
+            (SpellCastingPreperation::Known, Some(known)) => known[class_level - 1],
+            _ => (class_level as isize + modifier).max(0) as usize,
+        };
 
         let spells_available = SpellsAvailable {
             num_spells,
@@ -1823,22 +4626,265 @@ impl Character {
 
         Some(spells_available)
     }
+
+    /// Sets the spells the class at the index has prepared, or knows for classes that know their
+    /// spells, checking them against the class's limits first.
+    ///
+    /// The spells are rejected if there are more cantrips or leveled spells than
+    /// [Character::num_spells] allows, if any spell is higher level than the character has
+    /// slots for, or if any spell can't be prepared by the class (see [Character::can_prepare]).
+    /// Nothing is changed if they're rejected.
+    pub fn set_prepared(
+        &mut self,
+        class_index: usize,
+        spells: Vec<Spell>,
+    ) -> Result<(), PrepareError> {
+        let available = self
+            .num_spells(class_index)
+            .ok_or(PrepareError::NotSpellcaster(class_index))?;
+
+        let cantrips = spells.iter().filter(|s| s.level == 0).count();
+        if cantrips > available.num_cantrips {
+            return Err(PrepareError::TooManyCantrips {
+                known: cantrips,
+                max: available.num_cantrips,
+            });
+        }
+        let prepared = spells.len() - cantrips;
+        if prepared > available.num_spells {
+            return Err(PrepareError::TooManySpells {
+                prepared,
+                max: available.num_spells,
+            });
+        }
+        let max_level = self.max_castable_level();
+        if let Some(spell) = spells.iter().find(|s| s.level > max_level) {
+            return Err(PrepareError::LevelTooHigh {
+                name: spell.name.clone(),
+                level: spell.level,
+                max: max_level,
+            });
+        }
+        if let Some(spell) = spells.iter().find(|s| !self.can_prepare(class_index, s)) {
+            return Err(PrepareError::NotOnSpellList(spell.name.clone()));
+        }
+
+        let casting = self.classes[class_index]
+            .spellcasting
+            .as_mut()
+            .ok_or(PrepareError::NotSpellcaster(class_index))?;
+        casting.1 = spells;
+        Ok(())
+    }
+
+    /// Returns true if the class at the index can prepare or know the spell, because it's on the
+    /// class's spell list at the spell's level. Names are compared ignoring case and punctuation,
+    /// so "Antipathy/Sympathy" matches "Antipathy Sympathy".
+    ///
+    /// Classes that prepare from a spellbook, like wizards, can prepare any leveled spell, since
+    /// their spellbook isn't tracked. Their cantrips still need to be on the spell list.
+    ///
+    /// Returns false if the class doesn't exist or isn't a spellcaster.
+    pub fn can_prepare(&self, class_index: usize, spell: &Spell) -> bool {
+        let Some((casting, _)) = self
+            .classes
+            .get(class_index)
+            .and_then(|c| c.spellcasting.as_ref())
+        else {
+            return false;
+        };
+        if casting.prepares_from_spellbook && spell.level > 0 {
+            return true;
+        }
+        self.on_spell_list(class_index, spell)
+    }
+
+    // whether the spell is on the spell list of the class at the index, ignoring case, spaces and
+    // punctuation.
+    fn on_spell_list(&self, class_index: usize, spell: &Spell) -> bool {
+        let Some((casting, _)) = self
+            .classes
+            .get(class_index)
+            .and_then(|c| c.spellcasting.as_ref())
+        else {
+            return false;
+        };
+
+        // lowercase letters and numbers only
+        let simplified = |name: &str| -> String {
+            name.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let name = simplified(&spell.name);
+        casting
+            .spell_list
+            .get(spell.level)
+            .is_some_and(|list| list.iter().any(|s| simplified(s) == name))
+    }
+
+    // the highest spell level the character has a spell slot or pact slot for, or 0 if they have
+    // neither.
+    fn max_castable_level(&self) -> usize {
+        let (slot_level, pact_level) = self.slot_caps();
+        slot_level.max(pact_level)
+    }
+
+    // the highest spell level the character has a regular spell slot for, and the level of their
+    // pact slots. each is 0 if they don't have that kind of slot.
+    fn slot_caps(&self) -> (usize, usize) {
+        let slot_level = self
+            .spell_slots()
+            .and_then(|slots| slots.0.iter().rposition(|n| *n > 0))
+            .map_or(0, |i| i + 1);
+        let pact_level = self.pact_slots().map_or(0, |slots| slots.level);
+        (slot_level, pact_level)
+    }
+
+    /// Checks the spells of every spellcasting class against that class's limits.
+    ///
+    /// When multiclassing, each class prepares its spells separately, using its own level and
+    /// spellcasting ability. This checks each class on its own, returning a warning for each class
+    /// that's over a limit. Cantrips are checked for every spellcasting class, while leveled spells
+    /// are only checked for classes that prepare their spells. An empty list means everything is
+    /// valid.
+    pub fn validate_prepared_spells(&self) -> Vec<SpellPrepWarning> {
+        let mut warnings = vec![];
+
+        for (class_index, class) in self.classes.iter().enumerate() {
+            let (Some((casting, spells)), Some(available)) =
+                (&class.spellcasting, self.num_spells(class_index))
+            else {
+                continue;
+            };
+
+            let cantrips = spells.iter().filter(|s| s.level == 0).count();
+            if cantrips > available.num_cantrips {
+                warnings.push(SpellPrepWarning::TooManyCantrips {
+                    class_index,
+                    known: cantrips,
+                    max: available.num_cantrips,
+                });
+            }
+
+            if !matches!(casting.preperation_type, SpellCastingPreperation::Prepared) {
+                continue;
+            }
+
+            let prepared = spells.len() - cantrips;
+            if prepared > available.num_spells {
+                warnings.push(SpellPrepWarning::TooManySpells {
+                    class_index,
+                    prepared,
+                    max: available.num_spells,
+                });
+            }
+        }
+
+        warnings
+    }
+
+    /// Checks the character for choices that are invalid or have no effect, like an ability
+    /// score increase to a stat that's already at 20, or more spells than a class can prepare.
+    ///
+    /// An empty list means nothing was found.
+    pub fn audit(&self) -> Vec<AuditWarning> {
+        let (_, wasted) = self.capped_stat_totals();
+        wasted
+            .into_iter()
+            .map(AuditWarning::WastedAbilityScoreIncrease)
+            .chain(
+                self.validate_prepared_spells()
+                    .into_iter()
+                    .map(AuditWarning::SpellPrep),
+            )
+            .collect()
+    }
+}
+
+// chooses the option if it's a choice of exactly one thing
+fn resolve_single_choice<T>(option: &mut PresentedOption<T>) -> bool {
+    option.choices().is_some_and(|c| c.len() == 1) && option.choose_in_place(0)
+}
+
+// the chance that a d20 plus the bonus meets or beats the target, without any natural 1 or 20
+// rules.
+fn d20_success_chance(bonus: isize, target: isize) -> f64 {
+    ((21 - (target - bonus)) as f64 / 20.0).clamp(0.0, 1.0)
+}
+
+// The Dungeon Master's Guide's monster statistics by challenge rating, as
+// (cr, ac, max hp, attack bonus, max damage per round, save dc).
+const MONSTER_STATS_BY_CR: [(f32, isize, usize, isize, usize, isize); 34] = [
+    (0.0, 13, 6, 3, 1, 13),
+    (0.125, 13, 35, 3, 3, 13),
+    (0.25, 13, 49, 3, 5, 13),
+    (0.5, 13, 70, 3, 8, 13),
+    (1.0, 13, 85, 3, 14, 13),
+    (2.0, 13, 100, 3, 20, 13),
+    (3.0, 13, 115, 4, 26, 13),
+    (4.0, 14, 130, 5, 32, 14),
+    (5.0, 15, 145, 6, 38, 15),
+    (6.0, 15, 160, 6, 44, 15),
+    (7.0, 15, 175, 6, 50, 15),
+    (8.0, 16, 190, 7, 56, 16),
+    (9.0, 16, 205, 7, 62, 16),
+    (10.0, 17, 220, 7, 68, 16),
+    (11.0, 17, 235, 8, 74, 17),
+    (12.0, 17, 250, 8, 80, 17),
+    (13.0, 18, 265, 8, 86, 18),
+    (14.0, 18, 280, 8, 92, 18),
+    (15.0, 18, 295, 8, 98, 18),
+    (16.0, 18, 310, 9, 104, 18),
+    (17.0, 19, 325, 10, 110, 19),
+    (18.0, 19, 340, 10, 116, 19),
+    (19.0, 19, 355, 10, 122, 19),
+    (20.0, 19, 400, 10, 140, 19),
+    (21.0, 19, 445, 11, 158, 20),
+    (22.0, 19, 490, 11, 176, 20),
+    (23.0, 19, 535, 11, 194, 20),
+    (24.0, 19, 580, 12, 212, 21),
+    (25.0, 19, 625, 12, 230, 21),
+    (26.0, 19, 670, 12, 248, 21),
+    (27.0, 19, 715, 13, 266, 22),
+    (28.0, 19, 760, 13, 284, 22),
+    (29.0, 19, 805, 13, 302, 22),
+    (30.0, 19, 850, 14, 320, 23),
+];
+
+// whether a spell's description describes an area of effect
+fn is_area_spell(spell: &Spell) -> bool {
+    const AREAS: [&str; 6] = ["radius", "cone", "cube", "sphere", "cylinder", "a line"];
+    spell.description.iter().any(|paragraph| {
+        let paragraph = paragraph.to_lowercase();
+        AREAS.iter().any(|area| paragraph.contains(area))
+    })
+}
+
+// moves one row of the cr table for every 2 points of difference from the expected value
+fn shift_cr_row(row: usize, difference: isize) -> f32 {
+    let row = (row as isize + difference / 2).clamp(0, MONSTER_STATS_BY_CR.len() as isize - 1);
+    MONSTER_STATS_BY_CR[row as usize].0
 }
 
 fn die_average_max(d: usize) -> usize {
     ((d as f32 + 1.0) / 2.0).ceil() as usize
 }
 
+// spellcasting scores are (spell save dc, spell attack modifier), and slot caps are the highest
+// level that can be cast with (spell slots, pact slots)
 fn spell_actions(
     spell: &Spell,
-    spell_attack_mod: isize,
-    max_slot_level: usize,
+    spellcasting_scores: (isize, isize),
+    (slot_cap, pact_cap): (usize, usize),
     character_level: usize,
 ) -> Option<Vec<SpellAction>> {
+    let (spell_save_dc, spell_attack_mod) = spellcasting_scores;
     if spell.level == 0 {
         return Some(vec![spell_action_cantrip(
             spell,
-            spell_attack_mod,
+            spellcasting_scores,
             character_level,
         )?]);
     }
@@ -1848,14 +4894,26 @@ fn spell_actions(
             .as_ref()?
             .iter()
             .enumerate()
+            .map(|(n, dv)| (n + spell.level, dv))
             // filter out everything over what the spellcaster can cast
-            .filter(|(n, _)| n + spell.level < max_slot_level)
-            .flat_map(|(n, dv)| dv.iter().map(move |d| (n + spell.level, d)))
-            .map(|(spell_level, damage)| SpellAction {
+            .filter_map(|(level, dv)| {
+                let slot_pool = match (level <= slot_cap, level <= pact_cap) {
+                    (true, true) => SlotPool::Either,
+                    (true, false) => SlotPool::SpellSlots,
+                    (false, true) => SlotPool::PactSlots,
+                    (false, false) => return None,
+                };
+                Some(dv.iter().map(move |d| (level, d, slot_pool)))
+            })
+            .flatten()
+            .map(|(spell_level, damage, slot_pool)| SpellAction {
                 spell_level: spell_level as isize,
                 name: spell.name.clone(),
                 spell_attack_mod,
+                spell_save_dc,
                 damage_roll: *damage,
+                mechanic: spell.mechanic,
+                slot_pool,
             })
             .collect(),
     )
@@ -1863,28 +4921,31 @@ fn spell_actions(
 
 fn spell_action_cantrip(
     spell: &Spell,
-    spell_attack_mod: isize,
+    (spell_save_dc, spell_attack_mod): (isize, isize),
     character_level: usize,
 ) -> Option<SpellAction> {
     let mut damage = spell.leveled_damage.as_ref()?.clone();
     // make sure damage is sorted by level
-    damage.sort_by(|a, b| a.0.cmp(&b.0));
-    // find the rightmost version we can use
+    damage.sort_by_key(|a| a.0);
+    // find the rightmost version we can use. if there isn't one, the damage is malformed and the
+    // cantrip is skipped.
     let position = damage
         .iter()
-        .rposition(|(level, _)| *level <= character_level)
-        .expect("Couldn't find a cantrip damage for this level.");
+        .rposition(|(level, _)| *level <= character_level)?;
 
     Some(SpellAction {
         name: spell.name.clone(),
         spell_level: 0,
         spell_attack_mod,
+        spell_save_dc,
         damage_roll: damage[position].1,
+        mechanic: spell.mechanic,
+        slot_pool: SlotPool::NoSlot,
     })
 }
 
 fn weapon_actions_inner(
-    name: &String,
+    name: &str,
     w: &Weapon,
     m: &Modifiers,
     p: &EquipmentProficiencies,
@@ -1894,6 +4955,8 @@ fn weapon_actions_inner(
     let versatile = w.properties.versatile;
     let two_handed = w.properties.two_handed;
     let light = w.properties.light;
+    let range = w.range.or(w.thrown_range);
+    let reach = w.melee_reach();
 
     let modifier = if finesse && m.stats.dexterity > m.stats.strength {
         m.stats.dexterity
@@ -1901,7 +4964,7 @@ fn weapon_actions_inner(
         m.stats.strength
     };
 
-    let proficient = is_proficient_with(&w.weapon_type, p) || p.other.contains(name);
+    let proficient = p.is_proficient_with_weapon(&w.weapon_type, name);
 
     let bonus = if proficient { proficiency_mod } else { 0 };
 
@@ -1910,11 +4973,13 @@ fn weapon_actions_inner(
     damage_roll.bonus = modifier + bonus;
 
     let base_attack = WeaponAction {
-        name: name.clone(),
+        name: name.to_string(),
         attack_bonus,
         damage_roll,
         two_handed,
         second_attack: false,
+        range,
+        reach,
     };
 
     let mut attacks = vec![base_attack];
@@ -1926,22 +4991,26 @@ fn weapon_actions_inner(
             ..damage_roll
         };
         attacks.push(WeaponAction {
-            name: name.clone(),
+            name: name.to_string(),
             attack_bonus,
             damage_roll,
             two_handed: false,
             second_attack: true,
+            range,
+            reach,
         });
     }
 
     // add possible two-handed attack
     if let Some(d) = versatile {
         attacks.push(WeaponAction {
-            name: name.clone(),
+            name: name.to_string(),
             attack_bonus,
             damage_roll: d,
             two_handed: true,
             second_attack: false,
+            range,
+            reach,
         });
     }
 
@@ -1993,6 +5062,8 @@ pub struct SpeccedClass {
     pub tracked_fields: Vec<(TrackedField, usize)>,
 
     class_specific: HashMap<String, String>,
+    #[serde(default)]
+    class_specific_leveled: HashMap<String, [String; 20]>,
 }
 
 impl SpeccedClass {
@@ -2004,8 +5075,8 @@ impl SpeccedClass {
         let tracked_fields = base_tracked_fields
             .into_iter()
             .map(|v| {
-                let base_max = v.get_base_max(class).unwrap_or(1);
-                (v, base_max)
+                let max = get_etc_field_max(&v, class.class_specific_leveled(), level).unwrap_or(1);
+                (v, max)
             })
             .collect::<Vec<_>>();
 
@@ -2024,8 +5095,9 @@ impl SpeccedClass {
             class_specific: class
                 .class_specific_leveled()
                 .iter()
-                .map(|(k, arr)| (k.clone(), arr[0].clone()))
+                .map(|(k, arr)| (k.clone(), arr[level - 1].clone()))
                 .collect(),
+            class_specific_leveled: class.class_specific_leveled().clone(),
         }
     }
 
@@ -2037,6 +5109,17 @@ impl SpeccedClass {
             .collect()
     }
 
+    /// Whether the class is high enough level to choose its subclass, which is the first level
+    /// any of its subclasses gives a feature at.
+    fn reached_subclass_level(&self) -> bool {
+        match &self.subclass {
+            PresentedOption::Base(_) => true,
+            PresentedOption::Choice(subclasses) => subclasses
+                .iter()
+                .any(|s| s.features[0..self.level].iter().any(|f| !f.is_empty())),
+        }
+    }
+
     fn get_subclass_features(&self) -> Option<Vec<&Feature>> {
         let subclass = self.subclass.as_base()?;
         let features: Vec<_> = subclass.features[0..self.level]
@@ -2059,6 +5142,7 @@ impl SpeccedClass {
             .iter()
             .map(|(k, arr)| (k.clone(), arr[self.level].clone()))
             .collect();
+        self.class_specific_leveled = class.class_specific_leveled().clone();
         self.level += 1;
     }
 
@@ -2066,6 +5150,433 @@ impl SpeccedClass {
     pub fn get_class_specific(&self) -> &HashMap<String, String> {
         &self.class_specific
     }
+
+    /// Gets the etc class specific fields for every level. This is the same as
+    /// [Class::class_specific_leveled].
+    pub fn class_specific_progression(&self) -> &HashMap<String, [String; 20]> {
+        &self.class_specific_leveled
+    }
+}
+
+/// An error in using an item with [Character::use_consumable].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum ConsumableError {
+    #[error("Character doesn't have any {0}")]
+    NotFound(String),
+    #[error("{0} can't be consumed")]
+    NotConsumable(String),
+}
+
+/// An error in leveling up a character with [Character::level_up_with_xp].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum LevelError {
+    #[error("Character needs {needed} xp to level up")]
+    NotEnoughXp { needed: usize },
+    #[error("Character is already level 20")]
+    MaxLevel,
+    #[error("Character doesn't meet the prerequisites to multiclass")]
+    FailedMulticlassPrereq,
+}
+
+/// A saved state of a character. See [Character::checkpoint] and
+/// [Character::checkpoint_compact].
+#[derive(Clone)]
+pub enum CharacterCheckpoint {
+    /// A full copy of the character.
+    Cloned(Box<Character>),
+    /// The character serialized to json.
+    Serialized(Vec<u8>),
+}
+
+/// Situational effects that change a character's ac against an attack. See
+/// [Character::ac_in_context].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CombatContext {
+    /// The Shield spell is active, for +5 ac until the start of the character's next turn.
+    pub shield_spell_active: bool,
+    /// The character is under the Shield of Faith spell, for +2 ac.
+    pub shield_of_faith_active: bool,
+    /// The character is taking the Defensive Duelist reaction, adding their proficiency bonus to
+    /// their ac. This only applies if they have the feat and are wielding a finesse weapon
+    /// they're proficient with.
+    pub defensive_duelist: bool,
+}
+
+/// A kind of d20 roll, along with the ability score it uses. See
+/// [Character::has_disadvantage_on].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RollKind {
+    AbilityCheck(StatType),
+    Attack(StatType),
+    Save(StatType),
+}
+
+/// One of the standard conditions a character can have. See [Character::conditions].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Condition {
+    Blinded,
+    Charmed,
+    Deafened,
+    Frightened,
+    Grappled,
+    Incapacitated,
+    Invisible,
+    Paralyzed,
+    Petrified,
+    Poisoned,
+    Prone,
+    Restrained,
+    Stunned,
+    Unconscious,
+}
+
+impl Condition {
+    // the other conditions that come with this one
+    fn implies(&self) -> &'static [Condition] {
+        match self {
+            Condition::Paralyzed | Condition::Petrified | Condition::Stunned => {
+                &[Condition::Incapacitated]
+            }
+            Condition::Unconscious => &[Condition::Incapacitated, Condition::Prone],
+            _ => &[],
+        }
+    }
+}
+
+/// How weighed down a character is by what they're carrying. See
+/// [Character::encumbrance_level].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Encumbrance {
+    Unencumbered,
+    /// Carrying more than 5 times their strength score.
+    Encumbered,
+    /// Carrying more than 10 times their strength score. This gives disadvantage on rolls that
+    /// use strength, dexterity, or constitution.
+    HeavilyEncumbered,
+}
+
+/// Where a character's walking speed comes from. See [Character::speed_breakdown].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeedBreakdown {
+    /// Each change to the speed in feet, in the order they're applied. Penalties are negative.
+    pub parts: Vec<(SpeedSource, isize)>,
+    /// The highest [minimum speed](FeatureEffect::SpeedFloor) set by a feature, if any.
+    pub floor: Option<usize>,
+    /// The final walking speed, the same as [Character::speed].
+    pub total: usize,
+}
+
+/// Something that changes a character's walking speed. See [SpeedBreakdown].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeedSource {
+    /// The race's base speed.
+    Race,
+    /// A speed bonus or unarmored movement from a feature, along with the feature's name.
+    Feature(FeatureSource, String),
+    /// Wearing armor without its minimum strength.
+    HeavyArmor,
+    /// Carrying too much weight. See [Character::encumbrance_level].
+    Encumbrance(Encumbrance),
+    /// Having 2 or more levels of exhaustion, which is applied after the
+    /// [floor](SpeedBreakdown::floor). See [Character::add_exhaustion].
+    Exhaustion(u8),
+}
+
+/// Something a character can attack with, from any source. See [Character::attack_options].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttackOption {
+    pub name: String,
+    pub kind: AttackKind,
+    /// How the attack is rolled against the target.
+    pub roll: AttackRoll,
+    pub damage_roll: DamageRoll,
+    /// The damage written out, like `"1d8 + 3 slashing"`.
+    pub damage: String,
+    /// The average damage of a hit.
+    pub average_damage: f64,
+    /// How far the attack reaches, like `"reach 5 ft."` for a weapon or `"120 feet"` for a
+    /// spell. [None] for features, which don't list a range.
+    pub range: Option<String>,
+    pub cost: AttackCost,
+    /// If the character has what they need to use the attack right now.
+    pub usable: bool,
+}
+
+impl AttackOption {
+    fn new(
+        kind: AttackKind,
+        name: &str,
+        roll: AttackRoll,
+        damage_roll: DamageRoll,
+        range: Option<String>,
+        cost: AttackCost,
+        usable: bool,
+    ) -> AttackOption {
+        let bonus = match damage_roll.bonus {
+            0 => String::new(),
+            b if b > 0 => format!(" + {b}"),
+            b => format!(" - {}", -b),
+        };
+        let damage = format!(
+            "{}d{}{bonus} {}",
+            damage_roll.number,
+            damage_roll.dice,
+            damage_roll.damage_type.to_string().to_lowercase()
+        );
+        AttackOption {
+            name: name.to_string(),
+            kind,
+            roll,
+            damage_roll,
+            damage,
+            average_damage: damage_roll.average(),
+            range,
+            cost,
+            usable,
+        }
+    }
+}
+
+/// Where an [AttackOption] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AttackKind {
+    /// See [Character::weapon_actions].
+    Weapon,
+    /// See [Character::spell_actions].
+    Spell,
+    /// See [Character::ect_actions].
+    Feature,
+}
+
+/// How an [AttackOption] is rolled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AttackRoll {
+    /// An attack roll with this bonus, against the target's AC.
+    ToHit(isize),
+    /// The target makes a saving throw against the DC.
+    Save { dc: isize, stat: StatType },
+    /// The attack always hits, like Magic Missile.
+    Automatic,
+}
+
+/// What using an [AttackOption] costs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AttackCost {
+    /// Nothing, like a weapon attack or a cantrip.
+    Free,
+    /// A spell slot or pact slot of this level.
+    SpellSlot(usize),
+    /// A racial spell's one cast per long rest.
+    OncePerLongRest,
+    /// Some of a class resource, like ki points. See [CustomAction::resource_cost].
+    Resource { name: String, amount: usize },
+}
+
+/// What a character has done so far on their turn. See [Character::start_turn].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnState {
+    /// Movement left this turn, in feet.
+    pub movement_remaining: usize,
+    /// Whether the character has taken the Dash action this turn.
+    pub dashed: bool,
+    /// Whether the character has taken the Disengage action this turn.
+    pub disengaged: bool,
+    /// The number of actions used this turn.
+    #[serde(default)]
+    pub actions_used: usize,
+    /// The number of bonus actions used this turn.
+    #[serde(default)]
+    pub bonus_actions_used: usize,
+    /// The number of reactions used since the start of the turn.
+    #[serde(default)]
+    pub reactions_used: usize,
+    /// Whether the character has used Action Surge this turn.
+    #[serde(default)]
+    pub action_surged: bool,
+}
+
+/// A character's death saving throws. See [Character::record_death_save].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DeathSaves {
+    pub successes: u8,
+    pub failures: u8,
+    /// Whether the character has been stabilized, and so isn't making death saves anymore.
+    #[serde(default)]
+    pub stable: bool,
+}
+
+/// How many actions, bonus actions, and reactions a character gets in a turn. See
+/// [Character::action_economy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ActionEconomy {
+    pub actions: usize,
+    pub bonus_actions: usize,
+    pub reactions: usize,
+}
+
+/// What happened when a character used a consumable item. See [Character::use_consumable].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConsumableResult {
+    /// The total of the item's roll.
+    pub rolled: usize,
+    /// The hp actually regained, which can be less than the roll if the character was close to
+    /// their max hp.
+    pub healed: usize,
+    /// How many of the item the character has left.
+    pub remaining: usize,
+}
+
+/// What a short rest would restore for a character. See [Character::short_rest_recoverable].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortRestRecovery {
+    /// The number of pact magic slots that would be regained.
+    pub pact_slots: usize,
+    /// The number of hit dice that can be spent during the rest.
+    pub hit_dice: usize,
+    /// Class resources that would be regained, by name, along with the amount regained.
+    pub tracked_fields: Vec<(String, usize)>,
+}
+
+/// A decision that hasn't been made yet while building a character. See
+/// [Character::pending_choices].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PendingChoice {
+    /// The race's subrace. See [SpeccedRace::choose_subrace].
+    Subrace,
+    /// A race ability bonus without a stat, at this index of [SpeccedRace::ability_bonuses].
+    RaceAbilityBonus { index: usize },
+    /// A race trait that's still a choice, at this index of [SpeccedRace::traits].
+    RaceTrait { index: usize },
+    /// The subclass of the class at `class_index`. See [Character::choose_subclass_by_name].
+    Subclass { class_index: usize },
+    /// A class feature that's still a choice. See [Character::choose_feature], which takes the
+    /// same arguments.
+    ClassFeature {
+        class_index: usize,
+        level: usize,
+        option_slot: usize,
+    },
+    /// An ability score increase that hasn't been fully chosen. `feature_index` is the index of
+    /// the feature in the class's features at that level.
+    AbilityScoreIncrease {
+        class_index: usize,
+        level: usize,
+        feature_index: usize,
+    },
+    /// An [expertise](FeatureEffect::Expertise) without both of its skills chosen, from the same
+    /// place as [PendingChoice::AbilityScoreIncrease].
+    Expertise {
+        class_index: usize,
+        level: usize,
+        feature_index: usize,
+    },
+    /// A class skill proficiency, at this index of [Character::class_skill_proficiencies].
+    ClassSkill { slot: usize },
+    /// A background skill proficiency. See [Character::choose_background_proficiency].
+    BackgroundSkill { index: usize },
+    /// An item option, at this index of [Character::unchosen_items]. See
+    /// [Character::choose_items].
+    Item { index: usize },
+    /// A category of item, like "any simple weapon", that needs to be picked. See
+    /// [Character::get_unchosen_categories].
+    ItemCategory { index: usize, choice_index: usize },
+    /// A language. See [Character::unchosen_languages].
+    Language(UnchosenLanguage),
+    /// Part of the background's personality. See [SpeccedBackground::choose_personality].
+    Personality(Personality),
+}
+
+/// A language choice a character hasn't made yet. See [Character::unchosen_languages].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnchosenLanguage {
+    /// A race's wildcard language, at this index. Choose it with
+    /// [SpeccedRace::choose_wildcard_language].
+    Race(usize),
+    /// A background's language option, at this index. Choose it with
+    /// [SpeccedBackground::choose_language_option].
+    Background(usize, LanguageOption),
+    /// A language granted by a feature's [FeatureEffect::AddedLanguage].
+    Feature {
+        /// Where the feature came from.
+        source: FeatureSource,
+        /// The name of the feature.
+        feature: String,
+        option: LanguageOption,
+    },
+}
+
+/// Whether a character can cast a spell, and if not, why. See [Character::can_cast].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Castability {
+    /// The spell can be cast. It's prepared or known by the class at `class_index`.
+    Castable { class_index: usize },
+    /// The spell can be cast as one of the character's Mystic Arcanum.
+    MysticArcanum,
+    /// None of the character's classes have the spell prepared or known.
+    NotPrepared,
+    /// The character doesn't have a spell slot or pact slot high enough to cast the spell, or
+    /// they've already used the Mystic Arcanum it's in since their last long rest.
+    NoSlot,
+    /// The character has a condition that stops them from casting, like being stunned or
+    /// unconscious.
+    Blocked(Condition),
+}
+
+impl Castability {
+    /// Returns true if the spell can be cast.
+    pub fn is_castable(&self) -> bool {
+        matches!(
+            self,
+            Castability::Castable { .. } | Castability::MysticArcanum
+        )
+    }
+}
+
+/// A problem with the spells of one of a character's classes. See
+/// [Character::validate_prepared_spells].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpellPrepWarning {
+    /// The class has more leveled spells prepared than it's allowed.
+    TooManySpells {
+        class_index: usize,
+        prepared: usize,
+        max: usize,
+    },
+    /// The class knows more cantrips than it's allowed.
+    TooManyCantrips {
+        class_index: usize,
+        known: usize,
+        max: usize,
+    },
+}
+
+/// An error in setting a class's spells with [Character::set_prepared].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum PrepareError {
+    #[error("Class {0} doesn't exist or can't cast spells")]
+    NotSpellcaster(usize),
+    #[error("{prepared} spells is more than the {max} the class can have")]
+    TooManySpells { prepared: usize, max: usize },
+    #[error("{known} cantrips is more than the {max} the class can know")]
+    TooManyCantrips { known: usize, max: usize },
+    #[error("{0} isn't on the class's spell list")]
+    NotOnSpellList(String),
+    #[error("{name} is level {level}, but the character can only cast up to level {max}")]
+    LevelTooHigh {
+        name: String,
+        level: usize,
+        max: usize,
+    },
+}
+
+/// A problem found with a character by [Character::audit].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AuditWarning {
+    /// An ability score increase went to a stat that was already at 20, so it didn't do anything.
+    /// See [Character::capped_stats].
+    WastedAbilityScoreIncrease(StatType),
+    /// See [Character::validate_prepared_spells].
+    SpellPrep(SpellPrepWarning),
 }
 
 /// Represents something you can cast.
@@ -2073,6 +5584,10 @@ impl SpeccedClass {
 /// This is mainly used for [Character::cast].
 pub trait Castable {
     fn level(&self) -> usize;
+    /// Which spell slots this can be cast with. Defaults to [SlotPool::Either].
+    fn slot_pool(&self) -> SlotPool {
+        SlotPool::Either
+    }
 }
 
 impl Castable for Spell {
@@ -2087,6 +5602,47 @@ impl Castable for SpellAction {
             .try_into()
             .expect("spell level was negative")
     }
+    fn slot_pool(&self) -> SlotPool {
+        self.slot_pool
+    }
+}
+
+// the ammunition a standard weapon fires, by its name. None for weapons this doesn't know.
+fn ammunition_for(weapon: &str) -> Option<&'static str> {
+    let weapon = weapon.to_lowercase();
+    if weapon.contains("crossbow") {
+        Some("bolt")
+    } else if weapon.contains("bow") {
+        Some("arrow")
+    } else if weapon.contains("sling") {
+        Some("bullet")
+    } else if weapon.contains("blowgun") {
+        Some("needle")
+    } else {
+        None
+    }
+}
+
+// the current maximum of a tracked field, from the class specific fields at the class's level.
+fn tracked_field_max(
+    field: &TrackedField,
+    class_specific: &HashMap<String, String>,
+) -> Option<usize> {
+    field.hard_max.or(field
+        .class_specific_max
+        .as_ref()
+        .and_then(|v| class_specific.get(v)?.parse().ok()))
+}
+
+// chooses the skill in the first choice that has it, unless it's already been chosen
+fn choose_skill(options: &mut [PresentedOption<SkillType>], skill: SkillType) -> bool {
+    if options.iter().any(|o| o.as_base() == Some(&skill)) {
+        return false;
+    }
+    options
+        .iter_mut()
+        .find(|o| o.choices().is_some_and(|c| c.contains(&skill)))
+        .is_some_and(|o| o.choose_where(|s| *s == skill))
 }
 
 fn get_etc_field_max(
@@ -2163,6 +5719,11 @@ impl SpeccedRace {
     pub fn subraces(&self) -> &PresentedOption<Subrace> {
         &self.subraces
     }
+    /// Mutable version of [SpeccedRace::subraces], for choosing a subrace with
+    /// [PresentedOption::choose_where].
+    pub fn subraces_mut(&mut self) -> &mut PresentedOption<Subrace> {
+        &mut self.subraces
+    }
     pub fn choose_subrace(&mut self, index: usize) -> bool {
         self.subraces.choose_in_place(index)
     }
@@ -2171,6 +5732,24 @@ impl SpeccedRace {
         &self.languages
     }
 
+    /// The languages chosen for the race's wildcard languages. A [None] hasn't been chosen yet.
+    pub fn wildcard_languages(&self) -> &Vec<Option<String>> {
+        &self.wildcard_languages
+    }
+
+    /// Chooses the language for one of the race's wildcard languages.
+    ///
+    /// Returns false if the index is out of bounds.
+    pub fn choose_wildcard_language(&mut self, index: usize, lang: &str) -> bool {
+        match self.wildcard_languages.get_mut(index) {
+            Some(slot) => {
+                *slot = Some(lang.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn new(race: &Race) -> SpeccedRace {
         let wildcard_languages: Vec<Option<String>> = vec![None; race.wildcard_languages()];
         let subraces = PresentedOption::Choice(race.subraces().to_vec());
@@ -2186,6 +5765,15 @@ impl SpeccedRace {
     }
 }
 
+/// One of the parts of a background's personality. See [SpeccedBackground::personality].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Personality {
+    Trait,
+    Ideal,
+    Bond,
+    Flaw,
+}
+
 /// A [Background] in application.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SpeccedBackground {
@@ -2265,6 +5853,30 @@ impl SpeccedBackground {
         self.language_options[index].set_to(lang.to_string())
     }
 
+    /// The personality trait, ideal, bond, or flaw from the background. It's a
+    /// [PresentedOption::Choice] until it's chosen with [SpeccedBackground::choose_personality].
+    pub fn personality(&self, kind: Personality) -> &PresentedOption<String> {
+        match kind {
+            Personality::Trait => &self.personality_traits,
+            Personality::Ideal => &self.ideals,
+            Personality::Bond => &self.bonds,
+            Personality::Flaw => &self.flaws,
+        }
+    }
+
+    /// Chooses a personality trait, ideal, bond, or flaw by its index in the background's list.
+    ///
+    /// Returns false if the index is out of bounds, or if it's already chosen.
+    pub fn choose_personality(&mut self, kind: Personality, index: usize) -> bool {
+        let option = match kind {
+            Personality::Trait => &mut self.personality_traits,
+            Personality::Ideal => &mut self.ideals,
+            Personality::Bond => &mut self.bonds,
+            Personality::Flaw => &mut self.flaws,
+        };
+        option.choose_in_place(index)
+    }
+
     pub fn new(background: &Background) -> SpeccedBackground {
         SpeccedBackground {
             background: background.name().to_string(),
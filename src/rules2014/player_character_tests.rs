@@ -0,0 +1,3247 @@
+//! Character tests that build everything from homebrew parts, so they don't need the api.
+use super::background::{BackgroundBuilder, LanguageOption};
+use super::class::{Class, ClassBuilder, Subclass, TrackedField};
+use super::features::{
+    chosen, AbilityScoreIncrease, CustomAction, Feat, Feature, FeatureEffect, FeatureSource,
+    Prerequisite, PresentedOption,
+};
+use super::items::{
+    Armor, ArmorCategory, Coins, ConsumableEffect, DamageRoll, DamageType, Item, ItemType,
+    RangeStatus, Weapon, WeaponProperties, WeaponType,
+};
+use super::monster::StatBlock;
+use super::player_character::{
+    ActionEconomy, AttackCost, AttackKind, AttackOption, AttackRoll, AuditWarning, Castability,
+    Character, CharacterBuilder, CharacterCheckpoint, CombatContext, Condition, ConsumableError,
+    DeathSaves, EditableCharacter, EditableCharacterError, Encumbrance, LevelError, PendingChoice,
+    Personality, PrepareError, RollKind, SpeedSource, SpellPrepWarning, UnchosenLanguage,
+};
+use super::spells::{SlotPool, Spell, SpellAction, SpellMechanic};
+use super::stats::{ProficiencyTier, Senses, SkillType, StatType, Stats, XP_BY_LEVEL};
+use super::test_fixtures::{
+    chain_mail, character, cleric, fighter, human, longsword, monk, shield, soldier, spell,
+    tiefling, warlock, HomebrewProvider, Missing,
+};
+use super::{RaceBuilder, SubraceBuilder};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn new_at_level() {
+    let stats = Stats::from(&[15, 13, 14, 8, 12, 10]);
+    let new = |class: &Class, level| {
+        Character::new_at_level(
+            "john".to_string(),
+            class,
+            &soldier(),
+            &human(),
+            stats,
+            level,
+        )
+    };
+    assert!(new(&fighter(), 0).is_none());
+    assert!(new(&fighter(), 21).is_none());
+
+    let direct = new(&fighter(), 17).unwrap();
+    let mut leveled = character("john");
+    leveled.level_up_to_level(&fighter(), 17);
+    assert_eq!(direct.level(), 17);
+    assert_eq!(direct.max_hp(), leveled.max_hp());
+    assert_eq!(direct.hp, direct.max_hp());
+    assert_eq!(
+        direct.classes[0].current_class_features,
+        leveled.classes[0].current_class_features
+    );
+    assert_eq!(
+        direct.classes[0].get_class_specific(),
+        leveled.classes[0].get_class_specific()
+    );
+    assert_eq!(
+        direct.classes[0].tracked_fields,
+        leveled.classes[0].tracked_fields
+    );
+    assert_eq!(
+        direct.pending_choices().len(),
+        leveled.pending_choices().len()
+    );
+
+    let warlock = warlock();
+    let direct = new(&warlock, 5).unwrap();
+    let mut leveled = new(&warlock, 1).unwrap();
+    leveled.level_up_to_level(&warlock, 5);
+    assert_eq!(direct.available_pact_slots, leveled.available_pact_slots);
+    assert_eq!(direct.available_pact_slots, direct.pact_slots());
+}
+
+#[test]
+fn duplicate_is_independent() {
+    let mut original = character("john");
+    original.items.push(longsword().into());
+
+    let mut copy = original.duplicate("jim");
+    assert_eq!(copy.name, "jim");
+    assert_eq!(original.name, "john");
+    assert_eq!(copy.hp, original.hp);
+
+    copy.damage(3);
+    copy.items[0].equip();
+    copy.items.push(chain_mail().into());
+
+    assert_eq!(original.hp, original.max_hp());
+    assert_ne!(copy.hp, original.hp);
+    assert_eq!(original.items.len(), 1);
+    assert!(!original.items[0].equipped);
+}
+
+#[test]
+fn feat_save_proficiency() {
+    let mut john = character("john");
+    john.level_up_to_level(&fighter(), 4);
+    assert!(!john.is_proficient_in_save(StatType::Wisdom));
+    let wisdom_save = john.save_mods().stats.wisdom;
+
+    let resilient = Feature {
+        name: "Resilient (Wisdom)".to_string(),
+        description: vec![],
+        effects: vec![
+            FeatureEffect::AddModifier(StatType::Wisdom, 1),
+            FeatureEffect::AddSaveProficiency(StatType::Wisdom),
+        ],
+    };
+    *john.ability_score_increases_mut()[0] = AbilityScoreIncrease::AddedFeature(Some(resilient));
+
+    assert!(john.is_proficient_in_save(StatType::Wisdom));
+    assert!(john.saves().is_proficient(StatType::Wisdom));
+    // wisdom goes from 13 to 14, and proficiency is +2
+    assert_eq!(john.stats().wisdom, 14);
+    assert_eq!(john.save_mods().stats.wisdom, wisdom_save + 3);
+}
+
+#[test]
+fn short_rest_preview() {
+    let mut john = character("john");
+    let preview = john.short_rest_recoverable();
+    assert_eq!(preview.pact_slots, 0);
+    assert_eq!(preview.hit_dice, 1);
+    assert!(preview.tracked_fields.is_empty());
+
+    john.classes[0].tracked_fields[0].1 = 0;
+    john.spent_hit_dice = 1;
+    let preview = john.short_rest_recoverable();
+    assert_eq!(preview.hit_dice, 0);
+    assert_eq!(preview.tracked_fields, vec![("Second Wind".to_string(), 1)]);
+
+    assert!(john.short_rest(0, None));
+    assert_eq!(john.classes[0].tracked_fields[0].1, 1);
+    assert!(john.short_rest_recoverable().tracked_fields.is_empty());
+
+    let monk = monk();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &monk,
+        &soldier(),
+        &human(),
+        Stats::from(&[10, 15, 13, 10, 14, 8]),
+    );
+    jill.level_up_to_level(&monk, 3);
+    jill.classes[0].tracked_fields[0].1 = 0;
+    assert_eq!(
+        jill.short_rest_recoverable().tracked_fields,
+        vec![("Ki Points".to_string(), 3)]
+    );
+    assert!(jill.short_rest(0, None));
+    assert_eq!(jill.classes[0].tracked_fields[0].1, 3);
+}
+
+#[test]
+fn temporary_stat_changes() {
+    let mut john = character("john");
+    let stats = john.stats();
+    assert_eq!(stats.strength, 16);
+
+    john.bonus_features.push(Feature {
+        name: "Strength Bonus".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::AddModifier(StatType::Strength, 10)],
+    });
+    assert_eq!(john.stats().strength, 20);
+
+    // temporary changes aren't capped at 20
+    john.apply_temp_stat_change(StatType::Strength, 2);
+    john.apply_temp_stat_change(StatType::Strength, 2);
+    john.apply_temp_stat_change(StatType::Dexterity, -3);
+    assert_eq!(john.stats().strength, 24);
+    assert_eq!(john.stats().dexterity, stats.dexterity - 3);
+
+    john.clear_temp_stat_changes();
+    assert_eq!(john.stats().strength, 20);
+    assert_eq!(john.stats().dexterity, stats.dexterity);
+}
+
+#[test]
+fn capped_stats() {
+    let fighter = fighter();
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter,
+        &soldier(),
+        &human(),
+        Stats::from(&[19, 13, 14, 8, 12, 10]),
+    );
+    assert_eq!(john.capped_stats(), vec![StatType::Strength]);
+    assert!(john.audit().is_empty());
+
+    john.level_up_to_level(&fighter, 4);
+    john.ability_score_increases_mut()[0]
+        .set_stat_increase(StatType::Strength, Some(StatType::Dexterity));
+    assert_eq!(john.stats().strength, 20);
+    assert_eq!(john.stats().dexterity, 15);
+    assert_eq!(
+        john.audit(),
+        vec![AuditWarning::WastedAbilityScoreIncrease(StatType::Strength)]
+    );
+
+    // uncapped bonuses can go past 20, but don't make the stat capped
+    john.bonus_features.push(Feature {
+        name: "Uncapped Constitution".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::AddModifierUncapped(
+            StatType::Constitution,
+            10,
+        )],
+    });
+    assert_eq!(john.stats().constitution, 25);
+    assert_eq!(john.capped_stats(), vec![StatType::Strength]);
+}
+
+#[test]
+fn class_specific_progression() {
+    let mut john = character("john");
+    assert!(john.class_specific_progression(1).is_none());
+
+    let progression = john.class_specific_progression(0).unwrap();
+    let action_surges = &progression["Action Surges"];
+    assert_eq!(action_surges[0], "0");
+    assert_eq!(action_surges[16], "2");
+
+    john.level_up(&fighter());
+    assert_eq!(john.classes[0].get_class_specific()["Action Surges"], "1");
+    assert_eq!(
+        john.class_specific_progression(0).unwrap()["Action Surges"][19],
+        "2"
+    );
+}
+
+#[test]
+fn choose_subrace_where() {
+    let subraces = ["High Elf", "Wood Elf"].map(|name| SubraceBuilder::new(name).build());
+    let elf = RaceBuilder::new("elf")
+        .add_ability_bonus(Some(StatType::Dexterity), 2)
+        .add_subraces(subraces)
+        .build();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &fighter(),
+        &soldier(),
+        &elf,
+        Stats::default(),
+    );
+
+    let subraces = jill.race.subraces_mut();
+    assert!(!subraces.choose_where(|s| s.name() == "Drow"));
+    assert!(subraces.choose_where(|s| s.name() == "Wood Elf"));
+    assert_eq!(jill.race.subrace().map(|s| s.name()), Some("Wood Elf"));
+}
+
+#[test]
+fn custom_lineage_ability_bonuses() {
+    let lineage = RaceBuilder::new("custom lineage")
+        .add_ability_bonus(None, 1)
+        .add_ability_bonus(None, 1)
+        .build();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &fighter(),
+        &soldier(),
+        &lineage,
+        Stats::from(&[10; 6]),
+    );
+    assert_eq!(jill.stats(), Stats::from(&[10; 6]));
+
+    let mut unchosen = jill.race.ability_bonuses_unchosen();
+    assert_eq!(unchosen.len(), 2);
+    *unchosen[0].0 = Some(StatType::Strength);
+    *unchosen[1].0 = Some(StatType::Wisdom);
+    assert_eq!(jill.stats(), Stats::from(&[11, 10, 10, 10, 11, 10]));
+    assert!(jill.race.ability_bonuses_unchosen().is_empty());
+}
+
+#[test]
+fn auto_resolve_single_choices() {
+    let dwarf = RaceBuilder::new("dwarf")
+        .add_ability_bonus(Some(StatType::Constitution), 2)
+        .add_subrace(SubraceBuilder::new("hill dwarf").build())
+        .build();
+    let mut jack = Character::new(
+        "jack".to_string(),
+        &fighter(),
+        &soldier(),
+        &dwarf,
+        Stats::default(),
+    );
+    jack.class_skill_proficiencies
+        .push(PresentedOption::Choice(vec![SkillType::Survival]));
+
+    // the hill dwarf subrace and the survival proficiency
+    assert_eq!(jack.auto_resolve_single_choices(), 2);
+    assert_eq!(jack.race.subrace().map(|s| s.name()), Some("Hill Dwarf"));
+    assert!(jack.skills()[SkillType::Survival].proficiency);
+    // the fighting style and other skills still have more than one option
+    assert_eq!(jack.feature_choices_at_level(0, 1).len(), 1);
+    assert!(jack.class_skill_proficiencies[0].choices().is_some());
+
+    assert_eq!(jack.auto_resolve_single_choices(), 0);
+}
+
+#[test]
+fn auto_resolve_subclass_at_subclass_level() {
+    let mut fighter = fighter();
+    let mut champion = Subclass {
+        name: "Champion".to_string(),
+        description: vec![],
+        features: Default::default(),
+    };
+    champion.features[2].push(PresentedOption::Base(Feature {
+        name: "Improved Critical".to_string(),
+        description: vec![],
+        effects: vec![],
+    }));
+    fighter.subclasses = vec![champion];
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter,
+        &soldier(),
+        &human(),
+        Stats::default(),
+    );
+
+    // a level 1 fighter doesn't choose a subclass yet, even with only one to choose from
+    john.auto_resolve_single_choices();
+    assert!(john.classes[0].subclass.as_base().is_none());
+
+    john.level_up_to_level(&fighter, 3);
+    assert_eq!(john.auto_resolve_single_choices(), 1);
+    let chosen = john.classes[0].subclass.as_base().unwrap();
+    assert_eq!(chosen.name, "Champion");
+}
+
+#[test]
+fn feature_choices() {
+    let mut john = character("john");
+    assert!(john.feature_choices_at_level(0, 0).is_empty());
+    assert!(john.feature_choices_at_level(1, 1).is_empty());
+
+    let choices = john.feature_choices_at_level(0, 1);
+    assert_eq!(choices.len(), 1);
+    assert_eq!(choices[0].choices().unwrap().len(), 3);
+
+    assert!(!john.choose_feature(0, 1, 1, 0));
+    assert!(!john.choose_feature(0, 1, 0, 3));
+    assert!(john.choose_feature(0, 1, 0, 1));
+
+    assert!(john.feature_choices_at_level(0, 1).is_empty());
+    assert!(john
+        .class_features()
+        .iter()
+        .any(|f| f.name == "Fighting Style: Defense"));
+}
+
+#[test]
+fn mystic_arcanum() {
+    let warlock = warlock();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &warlock,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 14, 13, 10, 12, 15]),
+    );
+    assert!(jill.mystic_arcana().is_empty());
+    assert!(!jill.set_mystic_arcanum(spell("Eyebite", 6)));
+
+    jill.level_up_to_level(&warlock, 13);
+    assert_eq!(jill.mystic_arcana(), vec![(6, None), (7, None)]);
+    assert!(!jill.cast_mystic_arcanum(6));
+
+    // wrong levels for an arcanum
+    assert!(!jill.set_mystic_arcanum(spell("Fireball", 3)));
+    assert!(!jill.set_mystic_arcanum(spell("Dominate Monster", 8)));
+    // not a warlock spell
+    assert!(!jill.set_mystic_arcanum(spell("Heal", 6)));
+    assert!(jill.set_mystic_arcanum(spell("Eyebite", 6)));
+    assert_eq!(jill.mystic_arcana()[0].1.unwrap().name, "Eyebite");
+
+    // once per long rest, without using a pact slot
+    let pact_slots = jill.available_pact_slots;
+    assert!(jill.cast_mystic_arcanum(6));
+    assert!(!jill.cast_mystic_arcanum(6));
+    assert_eq!(jill.available_pact_slots, pact_slots);
+
+    jill.long_rest();
+    assert!(jill.cast_mystic_arcanum(6));
+}
+
+#[test]
+fn multiclass_prepared_spells() {
+    let cleric = cleric();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    jill.level_up_to_level(&cleric, 3);
+    jill.level_up(&warlock());
+    assert!(jill.validate_prepared_spells().is_empty());
+
+    // cleric 3 with a +3 wisdom modifier can prepare 6 spells
+    let cleric_spells = &mut jill.classes[0].spellcasting.as_mut().unwrap().1;
+    cleric_spells.push(spell("Sacred Flame", 0));
+    for n in 0..6 {
+        cleric_spells.push(spell(&format!("Cleric Spell {n}"), 1));
+    }
+    // warlock 1 only gets 2 cantrips
+    let warlock_spells = &mut jill.classes[1].spellcasting.as_mut().unwrap().1;
+    warlock_spells.push(spell("Eldritch Blast", 0));
+    warlock_spells.push(spell("Hex", 1));
+    assert!(jill.validate_prepared_spells().is_empty());
+
+    jill.classes[0]
+        .spellcasting
+        .as_mut()
+        .unwrap()
+        .1
+        .push(spell("Bless", 1));
+    let warlock_spells = &mut jill.classes[1].spellcasting.as_mut().unwrap().1;
+    warlock_spells.push(spell("Minor Illusion", 0));
+    warlock_spells.push(spell("Mage Hand", 0));
+
+    assert_eq!(
+        jill.validate_prepared_spells(),
+        vec![
+            SpellPrepWarning::TooManySpells {
+                class_index: 0,
+                prepared: 7,
+                max: 6
+            },
+            SpellPrepWarning::TooManyCantrips {
+                class_index: 1,
+                known: 3,
+                max: 2
+            },
+        ]
+    );
+}
+
+#[test]
+fn expected_spell_damage() {
+    let john = character("john");
+    let mut action = SpellAction {
+        name: "Fire Bolt".to_string(),
+        spell_level: 0,
+        damage_roll: DamageRoll::new(1, 10, 0, DamageType::Fire),
+        spell_attack_mod: 5,
+        spell_save_dc: 13,
+        mechanic: SpellMechanic::Attack,
+        slot_pool: SlotPool::NoSlot,
+    };
+    // hits AC 15 on a 10 or higher, plus extra dice on a critical hit
+    assert!((john.expected_spell_damage(&action, 15) - 3.3).abs() < 1e-9);
+    // a natural 1 always misses
+    assert!((john.expected_spell_damage(&action, 0) - 5.5).abs() < 1e-9);
+
+    action.name = "Fireball".to_string();
+    action.damage_roll = DamageRoll::new(8, 6, 0, DamageType::Fire);
+    action.mechanic = SpellMechanic::Save {
+        stat: StatType::Dexterity,
+        half_on_success: true,
+    };
+    // +2 saves against DC 13 half the time
+    assert!((john.expected_spell_damage(&action, 2) - 21.0).abs() < 1e-9);
+
+    action.mechanic = SpellMechanic::Automatic;
+    assert!((john.expected_spell_damage(&action, 2) - 28.0).abs() < 1e-9);
+}
+
+#[test]
+fn attack_options() {
+    let fighter = fighter();
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter,
+        &soldier(),
+        &tiefling(),
+        Stats::from(&[15, 13, 14, 8, 12, 14]),
+    );
+    john.level_up_to_level(&fighter, 3);
+    let shortbow = Item {
+        name: "Shortbow".to_string(),
+        description: None,
+        item_type: ItemType::Weapon(Weapon {
+            damage: DamageRoll::new(1, 6, 0, DamageType::Piercing),
+            attack_roll_bonus: 0,
+            weapon_type: WeaponType::Simple,
+            properties: WeaponProperties {
+                ammunition: true,
+                two_handed: true,
+                ..Default::default()
+            },
+            range: Some((80, 320)),
+            thrown_range: None,
+        }),
+        weight: None,
+        attunement: None,
+        features: vec![],
+    };
+    john.items.push((longsword(), 1, true).into());
+    john.items.push((shortbow, 1, true).into());
+    john.bonus_features.push(Feature {
+        name: "Claws".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::CustomAction(CustomAction {
+            name: "Claws".to_string(),
+            static_attack_bonus: 1,
+            attack_bonus_stats: vec![StatType::Strength],
+            add_prof_to_attack: false,
+            damage_roll: DamageRoll::new(1, 4, 0, DamageType::Slashing),
+            damage_bonus_stats: vec![],
+            add_prof_to_damage: false,
+            resource_cost: None,
+        })],
+    });
+    john.bonus_features.push(Feature {
+        name: "Flurry".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::CustomAction(CustomAction {
+            name: "Flurry".to_string(),
+            static_attack_bonus: 0,
+            attack_bonus_stats: vec![StatType::Dexterity],
+            add_prof_to_attack: false,
+            damage_roll: DamageRoll::new(1, 4, 0, DamageType::Bludgeoning),
+            damage_bonus_stats: vec![],
+            add_prof_to_damage: false,
+            resource_cost: Some(("Ki Points".to_string(), 1)),
+        })],
+    });
+
+    let options = john.attack_options();
+    let averages: Vec<f64> = options.iter().map(|o| o.average_damage).collect();
+    assert!(averages.windows(2).all(|w| w[0] >= w[1]));
+
+    let rebuke = &options[0];
+    assert_eq!(rebuke.name, "Hellish Rebuke");
+    assert_eq!(rebuke.kind, AttackKind::Spell);
+    // 8 + 2 + 3
+    assert_eq!(
+        rebuke.roll,
+        AttackRoll::Save {
+            dc: 13,
+            stat: StatType::Dexterity
+        }
+    );
+    assert_eq!(rebuke.damage, "2d10 fire");
+    assert_eq!(rebuke.range.as_deref(), Some("60 feet"));
+    assert_eq!(rebuke.cost, AttackCost::OncePerLongRest);
+    assert!(rebuke.usable);
+
+    let find = |options: &[AttackOption], name: &str| -> AttackOption {
+        options.iter().find(|o| o.name == name).unwrap().clone()
+    };
+    let sword = find(&options, "Longsword");
+    assert_eq!(sword.roll, AttackRoll::ToHit(4));
+    assert_eq!(sword.damage, "1d8 + 4 slashing");
+    assert_eq!(sword.range.as_deref(), Some("reach 5 ft."));
+    assert_eq!(sword.cost, AttackCost::Free);
+
+    let bow = find(&options, "Shortbow");
+    assert_eq!(bow.range.as_deref(), Some("range 80/320 ft."));
+    assert!(!bow.usable, "no arrows to shoot");
+
+    let claws = find(&options, "Claws");
+    assert_eq!(claws.kind, AttackKind::Feature);
+    assert_eq!(claws.roll, AttackRoll::ToHit(3));
+    assert_eq!(claws.range, None);
+    assert_eq!(claws.cost, AttackCost::Free);
+
+    // no ki to spend
+    let flurry = find(&options, "Flurry");
+    assert_eq!(
+        flurry.cost,
+        AttackCost::Resource {
+            name: "Ki Points".to_string(),
+            amount: 1
+        }
+    );
+    assert!(!flurry.usable);
+    john.classes[0].tracked_fields.push((
+        TrackedField {
+            name: "Ki Points".to_string(),
+            long_rest: true,
+            short_rest: true,
+            level_up: false,
+            class_specific_max: None,
+            hard_max: Some(3),
+            class_specific_die: None,
+        },
+        1,
+    ));
+    assert!(find(&john.attack_options(), "Flurry").usable);
+
+    // crossbow bolts don't fit a shortbow
+    let item = |name: &str| Item {
+        name: name.to_string(),
+        description: None,
+        item_type: ItemType::Misc,
+        weight: None,
+        attunement: None,
+        features: vec![],
+    };
+    john.items.push((item("Crossbow Bolt"), 20).into());
+    assert!(!find(&john.attack_options(), "Shortbow").usable);
+
+    john.items.push((item("Arrow"), 20).into());
+    john.cast_racial_spell("Hellish Rebuke");
+    let options = john.attack_options();
+    assert!(find(&options, "Shortbow").usable);
+    assert!(!find(&options, "Hellish Rebuke").usable);
+}
+
+#[test]
+fn use_consumable() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut john = character("john");
+    let potion = |name: &str| Item {
+        name: name.to_string(),
+        description: None,
+        item_type: ItemType::Consumable(ConsumableEffect::healing_potion(name).unwrap()),
+        weight: Some(0.5),
+        attunement: None,
+        features: vec![],
+    };
+    john.items.push((potion("Potion of Healing"), 2).into());
+    john.items.push((longsword(), 1).into());
+
+    john.damage(10);
+    let result = john.use_consumable("potion of healing", &mut rng).unwrap();
+    assert!((4..=10).contains(&result.rolled));
+    assert_eq!(john.hp, 2 + result.healed);
+    assert_eq!(result.remaining, 1);
+
+    // healing doesn't go past max hp
+    john.hp = john.max_hp() - 1;
+    let result = john.use_consumable("Potion of Healing", &mut rng).unwrap();
+    assert_eq!(result.healed, 1);
+    assert_eq!(john.hp, john.max_hp());
+    assert_eq!(result.remaining, 0);
+    assert_eq!(john.items.len(), 1);
+
+    assert_eq!(
+        john.use_consumable("Potion of Healing", &mut rng),
+        Err(ConsumableError::NotFound("Potion of Healing".to_string()))
+    );
+    assert_eq!(
+        john.use_consumable("Longsword", &mut rng),
+        Err(ConsumableError::NotConsumable("Longsword".to_string()))
+    );
+
+    // greater potions roll more dice
+    john.items
+        .push((potion("Potion of Superior Healing"), 1).into());
+    john.hp = 1;
+    let result = john
+        .use_consumable("Potion of Superior Healing", &mut rng)
+        .unwrap();
+    assert!((16..=40).contains(&result.rolled));
+    assert_eq!(ConsumableEffect::healing_potion("Potion of Climbing"), None);
+}
+
+#[test]
+fn open5e_export() {
+    let mut john = character("John Smith");
+    john.items.push((longsword(), 1, true).into());
+    let json = john.to_open5e_json();
+
+    assert_eq!(json["name"], "John Smith");
+    assert_eq!(json["slug"], "john-smith");
+    assert_eq!(json["subtype"], "human");
+    assert_eq!(json["hit_dice"], "1d10");
+    assert_eq!(json["hit_points"], john.max_hp());
+    assert_eq!(json["armor_class"], john.ac());
+    assert_eq!(json["speed"]["walk"], 30);
+
+    // ability scores make the round trip
+    let scores: Vec<isize> = [
+        "strength",
+        "dexterity",
+        "constitution",
+        "intelligence",
+        "wisdom",
+        "charisma",
+    ]
+    .iter()
+    .map(|s| json[s].as_i64().unwrap() as isize)
+    .collect();
+    let scores: [isize; 6] = scores.try_into().unwrap();
+    assert_eq!(Stats::from(&scores), john.stats());
+
+    // fighters are proficient in strength and constitution saves
+    assert_eq!(json["strength_save"], john.save_mods().stats.strength);
+    assert!(json["dexterity_save"].is_null());
+    // from the soldier background
+    assert_eq!(
+        json["skills"]["athletics"],
+        john.skill_modifiers().athletics
+    );
+    assert!(json["skills"].get("stealth").is_none());
+
+    let longsword = json["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["name"] == "Longsword")
+        .unwrap();
+    assert_eq!(longsword["damage_dice"], "1d8");
+    assert_eq!(longsword["attack_bonus"], 5);
+}
+
+#[test]
+fn character_sheet() {
+    let mut john = character("John Smith");
+    john.items.push((longsword(), 1, true).into());
+    john.items.push((shield(), 1, false).into());
+    let sheet = john.sheet();
+    let lines: Vec<&str> = sheet.lines().collect();
+    assert_eq!(lines[0], "John Smith");
+    assert_eq!(lines[1], "Human | Fighter 1 | Soldier");
+    assert_eq!(
+        lines[3],
+        format!(
+            "AC {} | HP 12/12 | Proficiency +2 | Speed 30 ft.",
+            john.ac()
+        )
+    );
+    assert!(lines.contains(&"  STR 16 (+3)"));
+    assert!(lines.contains(&"  * Strength +5"));
+    assert!(lines.contains(&"    Dexterity +2"));
+    assert!(lines.contains(&"  * Athletics +5"));
+    assert!(lines.contains(&"    Sleight of Hand +2"));
+    // only equipped items are listed
+    assert!(lines.contains(&"  Longsword"));
+    assert!(!sheet.contains("Shield"));
+    assert!(!sheet.contains("Spells"));
+
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &warlock(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 14, 13, 10, 12, 15]),
+    );
+    let spells = &mut jill.classes[0].spellcasting.as_mut().unwrap().1;
+    spells.push(spell("Hex", 1));
+    spells.push(spell("Eldritch Blast", 0));
+    spells.push(spell("Armor of Agathys", 1));
+    assert!(jill
+        .sheet()
+        .ends_with("Spells\n  Cantrips: Eldritch Blast\n  1st level: Hex, Armor of Agathys"));
+}
+
+#[test]
+fn can_cast() {
+    let warlock = warlock();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &warlock,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 14, 13, 10, 12, 15]),
+    );
+    let hex = spell("Hex", 1);
+    assert_eq!(jill.can_cast(&hex), Castability::NotPrepared);
+
+    jill.classes[0].spellcasting.as_mut().unwrap().1.push(hex.clone());
+    assert_eq!(jill.can_cast(&hex), Castability::Castable { class_index: 0 });
+    // spell names are matched ignoring case
+    assert!(jill.can_cast(&spell("hex", 1)).is_castable());
+    // a warlock's only slot is their pact slot
+    jill.available_pact_slots.as_mut().unwrap().num = 0;
+    assert_eq!(jill.can_cast(&hex), Castability::NoSlot);
+    assert!(!jill.can_cast(&hex).is_castable());
+
+    // cantrips don't need a slot
+    let eldritch_blast = spell("Eldritch Blast", 0);
+    jill.classes[0]
+        .spellcasting
+        .as_mut()
+        .unwrap()
+        .1
+        .push(eldritch_blast.clone());
+    assert!(jill.can_cast(&eldritch_blast).is_castable());
+
+    // conditions are checked before anything else
+    jill.add_condition(Condition::Stunned);
+    assert_eq!(
+        jill.can_cast(&eldritch_blast),
+        Castability::Blocked(Condition::Stunned)
+    );
+    assert_eq!(
+        jill.can_cast(&hex),
+        Castability::Blocked(Condition::Stunned)
+    );
+    jill.remove_condition(Condition::Stunned);
+    jill.add_condition(Condition::Unconscious);
+    assert_eq!(
+        jill.can_cast(&eldritch_blast),
+        Castability::Blocked(Condition::Unconscious)
+    );
+    jill.remove_condition(Condition::Unconscious);
+    jill.add_condition(Condition::Prone);
+    assert!(jill.can_cast(&eldritch_blast).is_castable());
+    jill.remove_condition(Condition::Prone);
+
+    jill.level_up_to_level(&warlock, 11);
+    let eyebite = spell("Eyebite", 6);
+    assert_eq!(jill.can_cast(&eyebite), Castability::NotPrepared);
+    jill.set_mystic_arcanum(eyebite.clone());
+    assert_eq!(jill.can_cast(&eyebite), Castability::MysticArcanum);
+    jill.cast_mystic_arcanum(6);
+    assert_eq!(jill.can_cast(&eyebite), Castability::NoSlot);
+}
+
+#[test]
+fn movement() {
+    let mut john = character("john");
+    john.start_turn();
+    assert_eq!(john.turn.movement_remaining, 30);
+
+    assert!(john.move_distance(10, false));
+    // difficult terrain costs double
+    assert!(john.move_distance(5, true));
+    assert_eq!(john.turn.movement_remaining, 10);
+    assert!(!john.move_distance(10, true));
+
+    john.dash();
+    john.disengage();
+    assert_eq!(john.turn.movement_remaining, 40);
+    assert!(john.turn.dashed && john.turn.disengaged);
+
+    // heavy armor without enough strength slows the character down before dashing
+    john.apply_temp_stat_change(StatType::Strength, -6);
+    john.items.push((chain_mail(), 1, true).into());
+    john.start_turn();
+    assert_eq!(john.turn.movement_remaining, 20);
+    assert!(!john.turn.disengaged);
+    john.dash();
+    assert_eq!(john.turn.movement_remaining, 40);
+}
+
+#[test]
+fn action_economy() {
+    let mut john = character("john");
+    john.start_turn();
+    assert_eq!(
+        john.action_economy(),
+        ActionEconomy {
+            actions: 1,
+            bonus_actions: 1,
+            reactions: 1,
+        }
+    );
+    assert!(john.use_action());
+    assert!(!john.use_action());
+    assert!(john.use_bonus_action());
+    assert!(!john.use_bonus_action());
+    assert!(john.use_reaction());
+    assert!(!john.use_reaction());
+
+    // no action surge at level 1
+    assert!(!john.action_surge());
+
+    john.level_up(&fighter());
+    john.long_rest();
+    john.start_turn();
+    assert!(john.use_action());
+    assert!(john.action_surge());
+    assert_eq!(john.action_economy().actions, 2);
+    assert!(john.use_action());
+    assert!(!john.use_action());
+    // only once per turn, and the one use is spent
+    assert!(!john.action_surge());
+    john.start_turn();
+    assert_eq!(john.action_economy().actions, 1);
+    assert!(!john.action_surge());
+
+    john.bonus_features.push(Feature {
+        name: "Quick Hands".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::ExtraBonusAction],
+    });
+    assert_eq!(john.action_economy().bonus_actions, 2);
+    assert!(john.use_bonus_action());
+    assert!(john.use_bonus_action());
+    assert!(!john.use_bonus_action());
+}
+
+#[test]
+fn best_weapon_action() {
+    let mut john = character("john");
+    assert_eq!(john.best_weapon_action().unwrap().name, "Unarmed Strike");
+
+    john.items.push((longsword(), 1, true).into());
+    let best = john.best_weapon_action().unwrap();
+    assert_eq!(best.name, "Longsword");
+    assert_eq!(best.damage_roll.dice, 8);
+    assert!(!best.two_handed);
+}
+
+#[test]
+fn damage_typed() {
+    let mut john = character("john");
+    let effects = vec![
+        FeatureEffect::Resistance(DamageType::Fire),
+        FeatureEffect::Immunity(DamageType::Poison),
+        FeatureEffect::Vulnerability(DamageType::Cold),
+        FeatureEffect::Resistance(DamageType::Thunder),
+        FeatureEffect::Vulnerability(DamageType::Thunder),
+    ];
+    john.bonus_features.push(Feature {
+        name: "Odd Blood".to_string(),
+        description: vec![],
+        effects,
+    });
+    let max = john.hp;
+
+    john.damage_typed(5, DamageType::Fire);
+    assert_eq!(john.hp, max - 2);
+    john.damage_typed(5, DamageType::Poison);
+    assert_eq!(john.hp, max - 2);
+    john.damage_typed(2, DamageType::Cold);
+    assert_eq!(john.hp, max - 6);
+    // resistance and vulnerability cancel out
+    john.damage_typed(1, DamageType::Thunder);
+    assert_eq!(john.hp, max - 7);
+    // untyped damage skips all of them
+    john.damage(1);
+    assert_eq!(john.hp, max - 8);
+
+    assert!(john.damage_typed(100, DamageType::Slashing));
+    assert_eq!(john.hp, 0);
+}
+
+#[test]
+fn temp_hp() {
+    let mut john = character("john");
+    let max = john.hp;
+
+    john.gain_temp_hp(5);
+    john.gain_temp_hp(3);
+    assert_eq!(john.temp_hp, 5);
+
+    assert!(!john.damage(3));
+    assert_eq!((john.hp, john.temp_hp), (max, 2));
+    assert!(!john.damage(4));
+    assert_eq!((john.hp, john.temp_hp), (max - 2, 0));
+
+    // resistance halves the damage before temp hp soaks it up
+    john.bonus_features.push(Feature {
+        name: "Fire Blood".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::Resistance(DamageType::Fire)],
+    });
+    john.gain_temp_hp(4);
+    john.damage_typed(10, DamageType::Fire);
+    assert_eq!((john.hp, john.temp_hp), (max - 3, 0));
+
+    // dropping to 0 is about hp, not temp hp
+    john.gain_temp_hp(5);
+    assert!(!john.damage(5));
+    assert!(john.damage(max));
+}
+
+#[test]
+fn death_saves() {
+    let mut john = character("john");
+    // saves aren't recorded above 0 hp
+    john.record_death_save(false);
+    assert_eq!(john.death_saves, DeathSaves::default());
+    assert!(john.damage(100));
+    assert!(!john.is_dead() && !john.is_stable());
+
+    john.record_death_save(true);
+    john.record_death_save(false);
+    assert_eq!(
+        john.death_saves,
+        DeathSaves {
+            successes: 1,
+            failures: 1,
+            stable: false,
+        }
+    );
+
+    // damage while down is a failure, and a critical hit is two
+    john.damage(1);
+    assert_eq!(john.death_saves.failures, 2);
+    john.damage_critical(1);
+    assert!(john.is_dead());
+    // nothing is recorded once the character is dead
+    let dead_saves = john.death_saves;
+    john.record_death_save(true);
+    john.record_death_save(false);
+    assert_eq!(john.death_saves, dead_saves);
+
+    let mut jim = character("jim");
+    jim.damage(100);
+    for _ in 0..3 {
+        jim.record_death_save(true);
+    }
+    assert!(jim.is_stable());
+    assert_eq!(
+        (jim.death_saves.successes, jim.death_saves.failures),
+        (0, 0)
+    );
+    // or while they're stable
+    jim.record_death_save(false);
+    assert!(jim.is_stable());
+    assert_eq!(jim.death_saves.failures, 0);
+    // getting hit again starts the dying over
+    jim.damage(1);
+    assert!(!jim.is_stable());
+    assert_eq!(jim.death_saves.failures, 1);
+
+    jim.long_rest();
+    assert_eq!(jim.death_saves, DeathSaves::default());
+}
+
+#[test]
+fn heal() {
+    let mut john = character("john");
+    let max = john.max_hp();
+    john.damage(5);
+    assert_eq!(john.heal(3), 3);
+    assert_eq!(john.heal(10), 2);
+    assert_eq!(john.hp, max);
+    assert_eq!(john.heal(1), 0);
+
+    john.damage(100);
+    john.record_death_save(false);
+    assert_eq!(john.heal(1), 1);
+    assert_eq!(john.death_saves, DeathSaves::default());
+
+    assert_eq!(john.heal_to_full(), max - 1);
+    assert_eq!(john.hp, max);
+
+    // the dead stay dead
+    john.damage(100);
+    john.damage_critical(1);
+    john.damage(1);
+    assert!(john.is_dead());
+    let dead_saves = john.death_saves;
+    assert_eq!(john.heal(5), 0);
+    assert_eq!(john.heal_to_full(), 0);
+    assert_eq!(john.hp, 0);
+    assert_eq!(john.death_saves, dead_saves);
+    assert!(john.is_dead());
+}
+
+#[test]
+fn concentration() {
+    let mut john = character("john");
+    let mut bless = spell("Bless", 1);
+    bless.concentration = true;
+    let mut haste = spell("Haste", 3);
+    haste.concentration = true;
+
+    assert!(!john.start_concentration(&spell("Magic Missile", 1)));
+    assert_eq!(john.concentration, None);
+
+    assert!(john.start_concentration(&bless));
+    assert!(john.start_concentration(&haste));
+    assert_eq!(john.concentration.as_deref(), Some("Haste"));
+    john.break_concentration();
+    assert_eq!(john.concentration, None);
+
+    john.start_concentration(&bless);
+    john.damage(1);
+    assert_eq!(john.concentration.as_deref(), Some("Bless"));
+    john.damage(100);
+    assert_eq!(john.concentration, None);
+
+    assert_eq!(Character::concentration_save_dc(0), 10);
+    assert_eq!(Character::concentration_save_dc(22), 11);
+}
+
+#[test]
+fn initiative() {
+    let mut john = character("john");
+    assert_eq!(john.initiative(), 2);
+    assert!(!john.initiative_advantage());
+
+    john.bonus_features.push(Feature {
+        name: "Alert".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::InitiativeBonus(5)],
+    });
+    assert_eq!(john.initiative(), 7);
+
+    // jack of all trades adds half proficiency, rounded down
+    john.bonus_features.push(Feature {
+        name: "Jack of All Trades".to_string(),
+        description: vec![],
+        effects: vec![
+            FeatureEffect::JackOfAllTrades,
+            FeatureEffect::InitiativeAdvantage,
+        ],
+    });
+    assert_eq!(john.initiative(), 8);
+    assert!(john.initiative_advantage());
+}
+
+#[test]
+fn ability_check_mod() {
+    let mut john = character("john");
+    // strength 15 + 1 from being human
+    assert_eq!(john.ability_check_mod(StatType::Strength), 3);
+    assert_eq!(john.ability_check_mod(StatType::Intelligence), -1);
+
+    john.bonus_features.push(Feature {
+        name: "Jack of All Trades".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::JackOfAllTrades],
+    });
+    assert_eq!(john.ability_check_mod(StatType::Intelligence), 0);
+
+    john.bonus_features.push(Feature {
+        name: "Bookish".to_string(),
+        description: vec![],
+        effects: vec![
+            FeatureEffect::AbilityCheckProficiency(StatType::Intelligence),
+            FeatureEffect::AddAbilityCheckModifier(StatType::Intelligence, 1),
+        ],
+    });
+    // full proficiency replaces the half from jack of all trades
+    assert_eq!(john.ability_check_mod(StatType::Intelligence), 2);
+    assert_eq!(john.ability_check_mod(StatType::Constitution), 3);
+
+    // the modifier also applies to the stat's skills, along with half proficiency from jack of
+    // all trades
+    let without = character("jim").skill_modifiers();
+    let with = john.skill_modifiers();
+    assert_eq!(with.arcana, without.arcana + 1 + 1);
+    assert_eq!(with.athletics, without.athletics);
+}
+
+#[test]
+fn passive_perception() {
+    let mut john = character("john");
+    // wisdom 12 + 1 from being human
+    assert_eq!(john.passive_perception(), 11);
+
+    john.bonus_features.push(Feature {
+        name: "Observant".to_string(),
+        description: vec![],
+        effects: vec![
+            FeatureEffect::PassiveBonus(SkillType::Perception, 5),
+            FeatureEffect::PassiveBonus(SkillType::Investigation, 5),
+        ],
+    });
+    assert_eq!(john.passive_perception(), 16);
+    assert_eq!(john.passive_skill(SkillType::Investigation), 14);
+    assert_eq!(john.skill_modifiers()[SkillType::Perception], 1);
+}
+
+#[test]
+fn exhaustion() {
+    let mut john = character("john");
+    let max_hp = john.max_hp();
+    let dexterity_check = RollKind::AbilityCheck(StatType::Dexterity);
+    let wisdom_save = RollKind::Save(StatType::Wisdom);
+
+    john.add_exhaustion(1);
+    assert!(john.has_disadvantage_on(dexterity_check));
+    assert!(!john.has_disadvantage_on(wisdom_save));
+    assert_eq!(john.speed(), 30);
+
+    john.add_exhaustion(1);
+    assert_eq!(john.speed(), 15);
+    assert_eq!(
+        john.speed_breakdown().parts.last(),
+        Some(&(SpeedSource::Exhaustion(2), -15))
+    );
+
+    john.add_exhaustion(1);
+    assert!(john.has_disadvantage_on(wisdom_save));
+    assert_eq!(john.max_hp(), max_hp);
+
+    john.add_exhaustion(1);
+    assert_eq!(john.max_hp(), max_hp / 2);
+    assert_eq!(john.hp, max_hp / 2);
+
+    john.add_exhaustion(1);
+    assert_eq!(john.speed(), 0);
+    assert!(!john.is_dead());
+    john.add_exhaustion(5);
+    assert_eq!(john.exhaustion, 6);
+    assert!(john.is_dead());
+
+    // resting doesn't bring the dead back
+    john.long_rest();
+    assert_eq!(john.exhaustion, 6);
+    assert!(john.is_dead());
+    assert!(!john.long_rest_recover_hit_dice(&[]));
+
+    john.remove_exhaustion(1);
+    john.long_rest();
+    assert_eq!(john.exhaustion, 4);
+    john.remove_exhaustion(10);
+    assert_eq!(john.exhaustion, 0);
+    assert_eq!(john.speed(), 30);
+}
+
+#[test]
+fn starting_coins() {
+    let mut john = character("john");
+    assert_eq!(john.coins.gp, 10);
+
+    let rope = Coins {
+        gp: 1,
+        ..Default::default()
+    };
+    assert!(john.coins.try_spend(rope));
+    john.coins.add(Coins {
+        sp: 5,
+        ..Default::default()
+    });
+    assert_eq!(john.coins.total_in_gp(), 9.5);
+
+    let saved = serde_json::to_string(&john).unwrap();
+    let loaded: Character = serde_json::from_str(&saved).unwrap();
+    assert_eq!(loaded.coins, john.coins);
+}
+
+#[test]
+fn set_prepared() {
+    let cleric = cleric();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    // not a spellcaster
+    assert_eq!(
+        character("john").set_prepared(0, vec![]),
+        Err(PrepareError::NotSpellcaster(0))
+    );
+    assert_eq!(
+        jill.set_prepared(1, vec![]),
+        Err(PrepareError::NotSpellcaster(1))
+    );
+
+    // cleric 1 with a +3 wisdom modifier prepares 4 spells and knows 3 cantrips
+    let mut spells: Vec<Spell> = ["Bless", "Cure Wounds", "Guiding Bolt", "Healing Word"]
+        .into_iter()
+        .map(|name| spell(name, 1))
+        .collect();
+    spells.extend(
+        ["Guidance", "Light", "Sacred Flame"]
+            .into_iter()
+            .map(|name| spell(name, 0)),
+    );
+    assert_eq!(jill.set_prepared(0, spells.clone()), Ok(()));
+    assert_eq!(jill.classes[0].spellcasting.as_ref().unwrap().1, spells);
+
+    let mut too_many = spells.clone();
+    too_many.push(spell("Sanctuary", 1));
+    assert_eq!(
+        jill.set_prepared(0, too_many),
+        Err(PrepareError::TooManySpells {
+            prepared: 5,
+            max: 4
+        })
+    );
+    let mut too_many = spells.clone();
+    too_many.push(spell("Thaumaturgy", 0));
+    assert_eq!(
+        jill.set_prepared(0, too_many),
+        Err(PrepareError::TooManyCantrips { known: 4, max: 3 })
+    );
+    assert_eq!(
+        jill.set_prepared(0, vec![spell("Spiritual Weapon", 2)]),
+        Err(PrepareError::LevelTooHigh {
+            name: "Spiritual Weapon".to_string(),
+            level: 2,
+            max: 1
+        })
+    );
+    // a rejected list doesn't change anything
+    assert_eq!(jill.classes[0].spellcasting.as_ref().unwrap().1, spells);
+
+    // warlocks know their spells instead, 3 at level 2
+    let warlock = warlock();
+    let mut jack = Character::new(
+        "jack".to_string(),
+        &warlock,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 14, 13, 10, 12, 15]),
+    );
+    jack.level_up(&warlock);
+    assert_eq!(jack.num_spells(0).unwrap().num_spells, 3);
+    let known: Vec<Spell> = ["Armor of Agathys", "Hellish Rebuke", "Hex"]
+        .into_iter()
+        .map(|name| spell(name, 1))
+        .collect();
+    assert_eq!(jack.set_prepared(0, known.clone()), Ok(()));
+    let mut too_many = known;
+    too_many.push(spell("Witch Bolt", 1));
+    assert_eq!(
+        jack.set_prepared(0, too_many),
+        Err(PrepareError::TooManySpells {
+            prepared: 4,
+            max: 3
+        })
+    );
+}
+
+#[test]
+fn can_prepare() {
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    assert!(jill.can_prepare(0, &spell("Bless", 1)));
+    assert!(jill.can_prepare(0, &spell("cure wounds", 1)));
+    assert!(jill.can_prepare(0, &spell("Sacred Flame", 0)));
+    // the right name, but at the wrong level
+    assert!(!jill.can_prepare(0, &spell("Bless", 0)));
+    assert!(!jill.can_prepare(0, &spell("Hex", 1)));
+    assert!(!jill.can_prepare(1, &spell("Bless", 1)));
+    assert!(!character("john").can_prepare(0, &spell("Bless", 1)));
+
+    assert_eq!(
+        jill.set_prepared(0, vec![spell("Hex", 1)]),
+        Err(PrepareError::NotOnSpellList("Hex".to_string()))
+    );
+
+    // wizards can prepare any leveled spell from their spellbook, but not any cantrip
+    jill.classes[0]
+        .spellcasting
+        .as_mut()
+        .unwrap()
+        .0
+        .prepares_from_spellbook = true;
+    assert!(jill.can_prepare(0, &spell("Hex", 1)));
+    assert!(!jill.can_prepare(0, &spell("Eldritch Blast", 0)));
+}
+
+#[test]
+fn ritual_casting() {
+    let cleric = cleric();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    jill.level_up(&warlock());
+    let ritual = |name: &str| Spell {
+        ritual: true,
+        ..spell(name, 1)
+    };
+    jill.set_prepared(0, vec![ritual("Sanctuary"), spell("Bless", 1)])
+        .unwrap();
+    jill.set_prepared(1, vec![ritual("Hex")]).unwrap();
+
+    assert!(jill.can_cast_as_ritual(&ritual("Sanctuary"), 0));
+    assert!(jill.can_cast_as_ritual(&ritual("sanctuary"), 0));
+    // not a ritual, not prepared, or a class without ritual casting
+    assert!(!jill.can_cast_as_ritual(&spell("Bless", 1), 0));
+    assert!(!jill.can_cast_as_ritual(&ritual("Cure Wounds"), 0));
+    assert!(!jill.can_cast_as_ritual(&ritual("Hex"), 1));
+    assert!(!jill.can_cast_as_ritual(&ritual("Sanctuary"), 2));
+
+    let slots = jill.available_spell_slots.clone();
+    let pact_slots = jill.available_pact_slots;
+    assert!(jill.cast_ritual(&ritual("Sanctuary")));
+    assert!(!jill.cast_ritual(&ritual("Hex")));
+    assert_eq!(jill.available_spell_slots, slots);
+    assert_eq!(jill.available_pact_slots, pact_slots);
+
+    // wizards can cast any ritual from their spellbook, which only has spells from their list
+    let casting = &mut jill.classes[0].spellcasting.as_mut().unwrap().0;
+    casting.prepares_from_spellbook = true;
+    casting.spell_list[1].push("Detect Magic".to_string());
+    assert!(jill.cast_ritual(&ritual("Detect Magic")));
+    assert!(!jill.cast_ritual(&ritual("Alarm")));
+}
+
+#[test]
+fn pact_and_slot_spell_actions() {
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    let warlock = warlock();
+    for _ in 0..3 {
+        jill.level_up(&warlock);
+    }
+    let damaging = |name: &str| Spell {
+        damage: Some(
+            (1..=5)
+                .map(|n| vec![DamageRoll::new(n + 1, 10, 0, DamageType::Fire)])
+                .collect(),
+        ),
+        ..spell(name, 1)
+    };
+    jill.set_prepared(0, vec![damaging("Guiding Bolt")])
+        .unwrap();
+    jill.set_prepared(1, vec![damaging("Hellish Rebuke")])
+        .unwrap();
+
+    // one level of cleric only has 1st level slots, but three levels of warlock has 2nd level
+    // pact slots
+    let pools: Vec<_> = jill
+        .spell_actions()
+        .into_iter()
+        .map(|a| (a.name, a.spell_level, a.slot_pool))
+        .collect();
+    assert_eq!(
+        pools,
+        vec![
+            ("Guiding Bolt".to_string(), 1, SlotPool::Either),
+            ("Guiding Bolt".to_string(), 2, SlotPool::PactSlots),
+            ("Hellish Rebuke".to_string(), 1, SlotPool::Either),
+            ("Hellish Rebuke".to_string(), 2, SlotPool::PactSlots),
+        ]
+    );
+
+    // cleric came first, so regular slots are used unless only pact slots can cast it
+    let actions = jill.spell_actions();
+    let slots = jill.available_spell_slots.clone().unwrap();
+    let pact_slots = jill.available_pact_slots.unwrap();
+    assert!(jill.cast(&actions[3], None, None));
+    assert_eq!(jill.available_pact_slots.unwrap().num, pact_slots.num - 1);
+    assert!(jill.cast(&actions[0], None, None));
+    assert_eq!(
+        jill.available_spell_slots.as_ref().unwrap().0[0],
+        slots.0[0] - 1
+    );
+}
+
+#[test]
+fn short_rest_tracked_fields() {
+    let warlock = warlock();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &warlock,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    jill.level_up(&warlock);
+    let field = |name: &str, short_rest: bool| TrackedField {
+        name: name.to_string(),
+        long_rest: true,
+        short_rest,
+        level_up: false,
+        class_specific_max: None,
+        hard_max: Some(1),
+        class_specific_die: None,
+    };
+    jill.classes[0].tracked_fields = vec![
+        (field("Dark One's Own Luck", true), 0),
+        (field("Hurl Through Hell", false), 0),
+    ];
+    assert!(jill.cast(&spell("Hex", 1), None, None));
+
+    assert!(jill.short_rest(0, None));
+    assert_eq!(jill.available_pact_slots, jill.pact_slots());
+    assert_eq!(jill.classes[0].tracked_fields[0].1, 1);
+    // long rest only
+    assert_eq!(jill.classes[0].tracked_fields[1].1, 0);
+}
+
+#[test]
+fn spend_and_restore_slots() {
+    let warlock = warlock();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    jill.level_up(&warlock);
+
+    assert!(jill.spend_slot(1));
+    assert!(jill.spend_slot(1));
+    assert!(!jill.spend_slot(1));
+    assert!(!jill.spend_slot(2));
+    assert!(!jill.spend_slot(0));
+    assert!(!jill.spend_slot(10));
+    jill.restore_slot(1);
+    jill.restore_slot(1);
+    jill.restore_slot(1);
+    assert_eq!(jill.available_spell_slots, jill.spell_slots());
+
+    assert!(jill.spend_pact_slot());
+    assert!(!jill.spend_pact_slot());
+    jill.restore_pact_slot();
+    jill.restore_pact_slot();
+    assert_eq!(jill.available_pact_slots, jill.pact_slots());
+
+    // upcasting needs a slot at least the spell's level
+    assert!(!jill.cast(&spell("Bless", 1), Some(2), Some(false)));
+    assert!(!jill.cast(&spell("Bless", 2), Some(1), Some(false)));
+    assert!(jill.cast(&spell("Bless", 1), Some(1), Some(false)));
+
+    // cantrips can't be upcast, and don't spend a slot trying
+    let slots = jill.available_spell_slots.clone();
+    assert!(!jill.cast(&spell("Sacred Flame", 0), Some(1), Some(false)));
+    assert_eq!(jill.available_spell_slots, slots);
+    assert!(jill.cast(&spell("Sacred Flame", 0), None, None));
+}
+
+#[test]
+fn font_of_magic() {
+    let cleric = cleric();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    jill.level_up_to_level(&cleric, 3);
+    jill.classes[0].tracked_fields = vec![(
+        TrackedField {
+            name: "Sorcery Points".to_string(),
+            long_rest: true,
+            short_rest: false,
+            level_up: false,
+            class_specific_max: None,
+            hard_max: Some(3),
+            class_specific_die: None,
+        },
+        1,
+    )];
+    let points = |jill: &Character| jill.classes[0].tracked_fields[0].1;
+    let slots = |jill: &Character| jill.available_spell_slots.as_ref().unwrap().0[..2].to_vec();
+
+    // points the caller keeps track of
+    let mut kept = jill.convert_slot_to_points(1).unwrap();
+    assert_eq!(kept, 1);
+    assert_eq!(jill.convert_slot_to_points(3), None);
+    // a 1st level slot costs 2 points
+    assert!(!jill.convert_points_to_slot(1, &mut kept));
+    kept += 2;
+    assert!(jill.convert_points_to_slot(1, &mut kept));
+    assert_eq!(kept, 1);
+    assert_eq!(points(&jill), 1);
+    assert_eq!(slots(&jill), [4, 2]);
+
+    // points from the Sorcery Points field
+    assert!(jill.convert_slot_to_sorcery_points(2));
+    assert!(!jill.convert_slot_to_sorcery_points(3));
+    assert_eq!(points(&jill), 3);
+    assert_eq!(slots(&jill), [4, 1]);
+
+    // a 2nd level slot costs 3 points, and a 6th level slot can't be made
+    assert!(jill.convert_sorcery_points_to_slot(2));
+    assert_eq!(points(&jill), 0);
+    assert!(!jill.convert_sorcery_points_to_slot(2));
+    assert!(!jill.convert_sorcery_points_to_slot(6));
+    assert!(!jill.convert_sorcery_points_to_slot(0));
+    assert_eq!(slots(&jill), [4, 2]);
+
+    // points can't go over the maximum of 3
+    assert!(jill.convert_slot_to_sorcery_points(1));
+    assert!(jill.convert_slot_to_sorcery_points(2));
+    assert!(jill.convert_slot_to_sorcery_points(1));
+    assert_eq!(points(&jill), 3);
+    assert_eq!(slots(&jill), [2, 1]);
+    assert!(jill.convert_sorcery_points_to_slot(1));
+    assert_eq!(points(&jill), 1);
+    assert_eq!(slots(&jill), [3, 1]);
+
+    // no sorcery points, so no font of magic
+    let mut john = character("john");
+    john.level_up_to_level(&cleric, 3);
+    let before = john.available_spell_slots.clone();
+    assert!(!john.convert_slot_to_sorcery_points(1));
+    assert!(!john.convert_sorcery_points_to_slot(1));
+    assert_eq!(john.available_spell_slots, before);
+    // but the caller can still keep the points themselves
+    assert_eq!(john.convert_slot_to_points(1), Some(1));
+}
+
+#[test]
+fn languages() {
+    let mut john = character("john");
+    assert_eq!(john.languages(), vec!["Common".to_string()]);
+    assert_eq!(
+        john.unchosen_languages(),
+        vec![
+            UnchosenLanguage::Race(0),
+            UnchosenLanguage::Background(0, LanguageOption::UnnamedChoice),
+        ]
+    );
+
+    let gnomish = LanguageOption::new_named_choice(vec!["Gnomish".to_string()]);
+    john.bonus_features.push(Feature {
+        name: "Tongues".to_string(),
+        description: vec![],
+        effects: vec![
+            FeatureEffect::AddedLanguage(LanguageOption::new_fixed("Druidic".to_string())),
+            FeatureEffect::AddedLanguage(gnomish.clone()),
+        ],
+    });
+    assert!(john.race.choose_wildcard_language(0, "Elvish"));
+    assert!(john.background.choose_language_option(0, "Common"));
+
+    assert_eq!(john.languages(), vec!["Common", "Druidic", "Elvish"]);
+    assert_eq!(
+        john.unchosen_languages(),
+        vec![UnchosenLanguage::Feature {
+            source: FeatureSource::Bonus,
+            feature: "Tongues".to_string(),
+            option: gnomish,
+        }]
+    );
+}
+
+#[test]
+fn senses() {
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &fighter(),
+        &soldier(),
+        &tiefling(),
+        Stats::default(),
+    );
+    assert_eq!(
+        jill.senses(),
+        Senses {
+            darkvision: Some(60),
+            ..Default::default()
+        }
+    );
+    assert_eq!(character("john").senses(), Senses::default());
+
+    // the longest range is used
+    jill.bonus_features.push(Feature {
+        name: "Deep Sight".to_string(),
+        description: vec![],
+        effects: vec![
+            FeatureEffect::Darkvision(120),
+            FeatureEffect::Darkvision(30),
+            FeatureEffect::Tremorsense(10),
+        ],
+    });
+    assert_eq!(
+        jill.senses(),
+        Senses {
+            darkvision: Some(120),
+            blindsight: None,
+            tremorsense: Some(10),
+        }
+    );
+    assert_eq!(
+        jill.to_open5e_json()["senses"],
+        "darkvision 120 ft., tremorsense 10 ft., passive Perception 10"
+    );
+}
+
+#[test]
+fn conditions() {
+    let mut john = character("john");
+    assert!(!john.has_disadvantage_on_attacks());
+
+    assert!(john.add_condition(Condition::Poisoned));
+    assert!(!john.add_condition(Condition::Poisoned));
+    assert!(john.has_condition(Condition::Poisoned));
+    assert!(john.has_disadvantage_on_attacks());
+    assert!(john.has_disadvantage_on(RollKind::Attack(StatType::Strength)));
+    assert!(john.has_disadvantage_on(RollKind::AbilityCheck(StatType::Wisdom)));
+    assert!(!john.has_disadvantage_on(RollKind::Save(StatType::Dexterity)));
+    assert!(john.remove_condition(Condition::Poisoned));
+    assert!(!john.remove_condition(Condition::Poisoned));
+    assert!(!john.has_disadvantage_on_attacks());
+
+    john.add_condition(Condition::Restrained);
+    assert!(john.has_disadvantage_on(RollKind::Save(StatType::Dexterity)));
+    assert!(!john.has_disadvantage_on(RollKind::Save(StatType::Strength)));
+    john.conditions.clear();
+
+    // unconscious comes with incapacitated and prone
+    assert!(!john.hits_within_5ft_are_critical());
+    john.add_condition(Condition::Unconscious);
+    assert!(john.has_condition(Condition::Incapacitated));
+    assert!(john.has_condition(Condition::Prone));
+    assert!(john.has_disadvantage_on_attacks());
+    assert!(john.hits_within_5ft_are_critical());
+    assert!(!john.has_condition(Condition::Paralyzed));
+}
+
+#[test]
+fn choose_subclass_by_name() {
+    let subclass = |name: &str| Subclass {
+        name: name.to_string(),
+        description: vec![],
+        features: Default::default(),
+    };
+    let mut fighter = fighter();
+    fighter.subclasses = vec![subclass("Champion"), subclass("Battle Master")];
+    let build = |name: &str| {
+        CharacterBuilder::new("john")
+            .class(&fighter)
+            .subclass(name)
+            .background(&soldier())
+            .race(&human())
+            .stats(Stats::default())
+            .build()
+    };
+
+    let john = build("battle master").unwrap();
+    let chosen = john.classes[0].subclass.as_base().unwrap();
+    assert_eq!(chosen.name, "Battle Master");
+    assert!(build("Eldritch Knight").is_err());
+
+    let mut john = character("john");
+    john.classes[0].subclass = PresentedOption::Choice(fighter.subclasses.clone());
+    assert!(!john.choose_subclass_by_name(0, "Eldritch Knight"));
+    assert!(!john.choose_subclass_by_name(1, "Champion"));
+    assert!(john.choose_subclass_by_name(0, "CHAMPION"));
+    let chosen = john.classes[0].subclass.as_base().unwrap();
+    assert_eq!(chosen.name, "Champion");
+    // already chosen
+    assert!(!john.choose_subclass_by_name(0, "Battle Master"));
+}
+
+#[test]
+fn pending_choices() {
+    let mut john = character("john");
+    let personality = [
+        Personality::Trait,
+        Personality::Ideal,
+        Personality::Bond,
+        Personality::Flaw,
+    ];
+    let mut expected = vec![
+        PendingChoice::ClassFeature {
+            class_index: 0,
+            level: 1,
+            option_slot: 0,
+        },
+        PendingChoice::ClassSkill { slot: 0 },
+        PendingChoice::ClassSkill { slot: 1 },
+        PendingChoice::Language(UnchosenLanguage::Race(0)),
+        PendingChoice::Language(UnchosenLanguage::Background(
+            0,
+            LanguageOption::UnnamedChoice,
+        )),
+    ];
+    expected.extend(personality.map(PendingChoice::Personality));
+    assert_eq!(john.pending_choices(), expected);
+
+    // ability score increases show up as the character levels
+    john.level_up_to_level(&fighter(), 4);
+    assert!(john
+        .pending_choices()
+        .contains(&PendingChoice::AbilityScoreIncrease {
+            class_index: 0,
+            level: 4,
+            feature_index: 0,
+        }));
+
+    assert!(john.choose_feature(0, 1, 0, 1));
+    assert!(john.choose_class_skill(SkillType::Athletics));
+    assert!(john.choose_class_skill(SkillType::Perception));
+    assert!(john.race.choose_wildcard_language(0, "Elvish"));
+    assert!(john.background.choose_language_option(0, "Dwarvish"));
+    for kind in personality {
+        assert!(john.background.choose_personality(kind, 0));
+        assert!(!john.background.choose_personality(kind, 0));
+    }
+    john.ability_score_increases_mut()[0].set_stat_increase(StatType::Strength, None);
+    assert_eq!(john.pending_choices(), vec![]);
+    assert_eq!(
+        john.background.personality(Personality::Ideal).as_base(),
+        Some(&"Responsibility. I do what I must and obey just authority.".to_string())
+    );
+}
+
+#[test]
+fn pending_subclass_at_subclass_level() {
+    let subclass = |name: &str| {
+        let mut subclass = Subclass {
+            name: name.to_string(),
+            description: vec![],
+            features: Default::default(),
+        };
+        subclass.features[2].push(PresentedOption::Base(Feature {
+            name: format!("{name} Feature"),
+            description: vec![],
+            effects: vec![],
+        }));
+        subclass
+    };
+    let mut fighter = fighter();
+    fighter.subclasses = vec![subclass("Champion"), subclass("Battle Master")];
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter,
+        &soldier(),
+        &human(),
+        Stats::default(),
+    );
+    let subclass_choice = PendingChoice::Subclass { class_index: 0 };
+
+    // fighters choose their subclass at level 3
+    assert!(!john.pending_choices().contains(&subclass_choice));
+    john.level_up_to_level(&fighter, 2);
+    assert!(!john.pending_choices().contains(&subclass_choice));
+    john.level_up_to_level(&fighter, 3);
+    assert!(john.pending_choices().contains(&subclass_choice));
+
+    assert!(john.choose_subclass_by_name(0, "Champion"));
+    assert!(!john.pending_choices().contains(&subclass_choice));
+}
+
+#[test]
+fn weakest_and_strongest_saves() {
+    let john = character("john");
+    assert_eq!(john.weakest_save(), StatType::Intelligence);
+    assert_eq!(john.strongest_save(), StatType::Strength);
+
+    let jill = Character::new(
+        "jill".to_string(),
+        &warlock(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 14, 13, 10, 12, 15]),
+    );
+    assert_eq!(jill.weakest_save(), StatType::Strength);
+    assert_eq!(jill.strongest_save(), StatType::Charisma);
+
+    // intelligence totals -1, strength totals 4
+    assert_eq!(
+        Character::weakest_save_among(&[&john, &jill]),
+        StatType::Intelligence
+    );
+    // ties go to the first in order
+    let mut tied = character("tied");
+    tied.bonus_features.push(Feature {
+        name: "Tied".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::AddSaveModifier(StatType::Strength, -6)],
+    });
+    assert_eq!(tied.weakest_save(), StatType::Strength);
+}
+
+#[test]
+fn cast_lowest_available() {
+    let cleric = cleric();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    jill.level_up_to_level(&cleric, 3);
+    let bless = spell("Bless", 1);
+    assert_eq!(jill.cast_lowest_available(&spell("Guidance", 0)), Some(0));
+
+    // 4 first level slots, then 2 second level slots
+    for _ in 0..4 {
+        assert_eq!(jill.cast_lowest_available(&bless), Some(1));
+    }
+    assert!(!jill.cast(&bless, None, None));
+    assert_eq!(jill.cast_lowest_available(&bless), Some(2));
+    assert_eq!(jill.cast_lowest_available(&bless), Some(2));
+    assert_eq!(jill.cast_lowest_available(&bless), None);
+    assert_eq!(jill.cast_lowest_available(&spell("Fireball", 3)), None);
+}
+
+#[test]
+fn unarmored_movement_and_speed_floor() {
+    let monk = monk();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &monk,
+        &soldier(),
+        &human(),
+        Stats::from(&[14, 15, 13, 10, 14, 8]),
+    );
+    assert_eq!(jill.speed(), 30);
+    jill.level_up(&monk);
+    assert_eq!(jill.speed(), 40);
+
+    // armor turns off unarmored movement
+    jill.items.push((chain_mail(), 1, true).into());
+    assert_eq!(jill.speed(), 30);
+
+    // without enough strength for the armor, only a speed floor keeps them at 30
+    jill.apply_temp_stat_change(StatType::Strength, -5);
+    assert_eq!(jill.speed(), 20);
+    jill.bonus_features.push(Feature {
+        name: "Fleet of Foot".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::SpeedFloor(30)],
+    });
+    assert_eq!(jill.speed(), 30);
+}
+
+#[test]
+fn speed_breakdown() {
+    // strength 11 after the human bonus, under chain mail's 13
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter(),
+        &soldier(),
+        &human(),
+        Stats::from(&[10, 14, 13, 10, 12, 10]),
+    );
+    john.bonus_features.push(Feature {
+        name: "Fast Movement".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::SpeedBonus(10)],
+    });
+    let breakdown = john.speed_breakdown();
+    assert_eq!(breakdown.total, 40);
+    assert_eq!(john.speed(), 40);
+
+    let mut armor = chain_mail();
+    armor.weight = Some(55.0);
+    john.items.push((armor, 1, true).into());
+    assert_eq!(john.speed(), 30);
+
+    // 60 pounds is more than 5 times their strength, which only matters with variant encumbrance
+    let mut sword = longsword();
+    sword.weight = Some(5.0);
+    john.items.push((sword, 1, true).into());
+    assert_eq!(john.speed(), 30);
+    john.variant_encumbrance = true;
+    let breakdown = john.speed_breakdown();
+    assert_eq!(
+        breakdown.parts,
+        vec![
+            (SpeedSource::Race, 30),
+            (
+                SpeedSource::Feature(FeatureSource::Bonus, "Fast Movement".to_string()),
+                10
+            ),
+            (SpeedSource::HeavyArmor, -10),
+            (SpeedSource::Encumbrance(Encumbrance::Encumbered), -10),
+        ]
+    );
+    assert_eq!(breakdown.floor, None);
+    assert_eq!(breakdown.total, 20);
+    assert_eq!(john.speed(), 20);
+
+    // heavily encumbered
+    john.items[1].quantity = 12;
+    assert_eq!(john.speed(), 10);
+    john.bonus_features.push(Feature {
+        name: "Fleet of Foot".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::SpeedFloor(25)],
+    });
+    let breakdown = john.speed_breakdown();
+    assert_eq!(breakdown.floor, Some(25));
+    assert_eq!(breakdown.total, 25);
+}
+
+#[test]
+fn unarmored_movement_with_shield() {
+    let monk = monk();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &monk,
+        &soldier(),
+        &human(),
+        Stats::from(&[14, 15, 13, 10, 14, 8]),
+    );
+    jill.level_up_to_level(&monk, 6);
+    assert_eq!(jill.speed(), 45);
+
+    jill.items.push((shield(), 1, true).into());
+    assert_eq!(jill.speed(), 30);
+    // an unequipped shield doesn't count
+    jill.items.last_mut().unwrap().equipped = false;
+    assert_eq!(jill.speed(), 45);
+
+    jill.items.push((chain_mail(), 1, true).into());
+    assert_eq!(jill.speed(), 30);
+}
+
+#[test]
+fn checkpoint_and_restore() {
+    let fighter = fighter();
+    let mut john = character("john");
+    let checkpoints = [john.checkpoint(), john.checkpoint_compact()];
+
+    for checkpoint in &checkpoints {
+        john.damage(5);
+        john.level_up(&fighter);
+        john.items.push((longsword(), 1, true).into());
+
+        john.restore(checkpoint).unwrap();
+        assert_eq!(john.hp, john.max_hp());
+        assert_eq!(john.level(), 1);
+        assert!(john.items.is_empty());
+    }
+
+    let broken = CharacterCheckpoint::Serialized(b"not a character".to_vec());
+    assert!(john.restore(&broken).is_err());
+    assert_eq!(john.name, "john");
+}
+
+#[test]
+fn unique_spells() {
+    let cleric = cleric();
+    let warlock = warlock();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    jill.level_up(&warlock);
+    let spells = |names: &[&str]| names.iter().map(|n| spell(n, 0)).collect::<Vec<_>>();
+    jill.classes[0].spellcasting.as_mut().unwrap().1 = spells(&["Guidance", "Light"]);
+    jill.classes[1].spellcasting.as_mut().unwrap().1 = spells(&["light", "Eldritch Blast"]);
+
+    let names: Vec<&str> = jill
+        .unique_spells()
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Guidance", "Light", "Eldritch Blast"]);
+    assert_eq!(jill.spell_sources("LIGHT"), vec![0, 1]);
+    assert_eq!(jill.spell_sources("Eldritch Blast"), vec![1]);
+    assert!(jill.spell_sources("Fireball").is_empty());
+}
+
+#[test]
+fn equip_starting_gear() {
+    let club = Item {
+        name: "Club".to_string(),
+        description: None,
+        item_type: ItemType::Weapon(Weapon {
+            damage: DamageRoll::new(1, 4, 0, DamageType::Bludgeoning),
+            attack_roll_bonus: 0,
+            weapon_type: WeaponType::Simple,
+            properties: WeaponProperties::default(),
+            range: None,
+            thrown_range: None,
+        }),
+        weight: None,
+        attunement: None,
+        features: vec![],
+    };
+    let leather = Item {
+        name: "Leather Armor".to_string(),
+        description: None,
+        item_type: ItemType::Armor(Armor {
+            ac: 11,
+            category: ArmorCategory::Light,
+            strength_minimum: None,
+            stealth_disadvantage: false,
+        }),
+        weight: None,
+        attunement: None,
+        features: vec![],
+    };
+    let knight = BackgroundBuilder::new("knight")
+        .add_proficiency(PresentedOption::Base(SkillType::History))
+        .add_equipment(club, 1)
+        .add_equipment(longsword(), 1)
+        .add_equipment(leather, 1)
+        .add_equipment(chain_mail(), 1)
+        .add_personality_trait("I'm always polite and respectful.".to_string())
+        .add_personality_trait("I can stare down a hell hound without flinching.".to_string())
+        .add_ideal("Responsibility. I do what I must and obey just authority.".to_string())
+        .add_bond("I fight for those who cannot fight for themselves.".to_string())
+        .add_flaw("I obey the law, even if the law causes misery.".to_string())
+        .build()
+        .unwrap();
+    let equipped = |c: &Character| -> Vec<String> {
+        c.equipped_items()
+            .into_iter()
+            .map(|i| i.item.name.clone())
+            .collect()
+    };
+
+    let (fighter, human) = (fighter(), human());
+    let builder = CharacterBuilder::new("john")
+        .class(&fighter)
+        .background(&knight)
+        .race(&human)
+        .stats(Stats::from(&[15, 13, 14, 8, 12, 10]));
+    let john = builder.clone().build().unwrap();
+    assert!(equipped(&john).is_empty());
+
+    let john = builder
+        .equip_starting_weapon()
+        .equip_starting_armor()
+        .build()
+        .unwrap();
+    assert_eq!(equipped(&john), vec!["Longsword", "Chain Mail"]);
+    assert_eq!(john.ac(), 16);
+
+    // a monk isn't proficient with any armor, and is better off without it
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &monk(),
+        &knight,
+        &human,
+        Stats::from(&[14, 15, 13, 10, 14, 8]),
+    );
+    assert!(!jill.equip_best_armor());
+    assert!(jill.equip_best_weapon());
+    assert_eq!(equipped(&jill), vec!["Club"]);
+}
+
+#[test]
+fn equip_armor() {
+    let leather = Item {
+        name: "Leather Armor".to_string(),
+        description: None,
+        item_type: ItemType::Armor(Armor {
+            ac: 11,
+            category: ArmorCategory::Light,
+            strength_minimum: None,
+            stealth_disadvantage: false,
+        }),
+        weight: None,
+        attunement: None,
+        features: vec![],
+    };
+    let equipped = |c: &Character| -> Vec<String> {
+        c.equipped_items()
+            .into_iter()
+            .map(|i| i.item.name.clone())
+            .collect()
+    };
+
+    let mut john = character("john");
+    john.items = vec![
+        chain_mail().into(),
+        leather.clone().into(),
+        (shield(), 1, true).into(),
+        longsword().into(),
+    ];
+    assert!(john.equip_armor(0));
+    assert_eq!(equipped(&john), vec!["Chain Mail", "Shield"]);
+    assert_eq!(john.ac(), 18);
+
+    // the chain mail comes off when the leather goes on
+    assert!(john.equip_armor(1));
+    assert_eq!(equipped(&john), vec!["Leather Armor", "Shield"]);
+    assert_eq!(john.ac(), 11 + 2 + 2);
+
+    // not armor, or not there at all
+    assert!(!john.equip_armor(2));
+    assert!(!john.equip_armor(3));
+    assert!(!john.equip_armor(4));
+    assert_eq!(equipped(&john), vec!["Leather Armor", "Shield"]);
+
+    john.unequip_all_armor();
+    assert_eq!(equipped(&john), vec!["Shield"]);
+
+    // warlocks can only wear light armor
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &warlock(),
+        &soldier(),
+        &human(),
+        Stats::default(),
+    );
+    jill.items = vec![chain_mail().into(), leather.into()];
+    assert!(!jill.equip_armor(0));
+    assert!(equipped(&jill).is_empty());
+    assert!(jill.equip_armor(1));
+    assert_eq!(equipped(&jill), vec!["Leather Armor"]);
+}
+
+#[test]
+fn passive_deception_and_stealth() {
+    let mut john = character("john");
+    // charisma 11, no proficiency
+    assert_eq!(john.passive_deception(), 10);
+    // dexterity 14
+    assert_eq!(john.passive_stealth(), 12);
+    assert_eq!(john.passive_skill(SkillType::Athletics), 15);
+
+    // chain mail gives disadvantage on stealth
+    john.items.push((chain_mail(), 1, true).into());
+    assert_eq!(john.passive_stealth(), 7);
+    john.items[0].equipped = false;
+    assert_eq!(john.passive_stealth(), 12);
+}
+
+#[test]
+fn racial_spells() {
+    let fighter = fighter();
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter,
+        &soldier(),
+        &tiefling(),
+        Stats::from(&[15, 13, 14, 8, 12, 14]),
+    );
+    let names = |c: &Character| -> Vec<String> {
+        c.racial_spells
+            .iter()
+            .map(|(s, _, _)| s.name.clone())
+            .collect()
+    };
+    assert_eq!(names(&john), vec!["Thaumaturgy"]);
+    assert!(john.cast_racial_spell("Thaumaturgy"));
+    assert!(!john.cast_racial_spell("Hellish Rebuke"));
+
+    john.level_up_to_level(&fighter, 10);
+    assert_eq!(
+        names(&john),
+        vec!["Thaumaturgy", "Hellish Rebuke", "Darkness"]
+    );
+
+    // cast with charisma, even without a spellcasting class. 8 + 4 + 3
+    let rebuke = john.spell_actions();
+    assert_eq!(rebuke.len(), 1);
+    assert_eq!(rebuke[0].name, "Hellish Rebuke");
+    assert_eq!(rebuke[0].spell_save_dc, 15);
+
+    // once per long rest
+    assert!(john.cast_racial_spell("hellish rebuke"));
+    assert!(!john.cast_racial_spell("hellish rebuke"));
+    john.long_rest();
+    assert!(john.cast_racial_spell("hellish rebuke"));
+}
+
+#[test]
+fn racial_spellcasting_scores() {
+    let fighter = fighter();
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter,
+        &soldier(),
+        &tiefling(),
+        Stats::from(&[15, 13, 14, 8, 12, 14]),
+    );
+    assert_eq!(john.spellcasting_scores(0), None);
+    // 8 + 2 + 3, and 2 + 3
+    assert_eq!(john.racial_spellcasting_scores(), Some((13, 5)));
+
+    john.level_up_to_level(&fighter, 5);
+    let cha = john.stats().modifiers().stats.charisma;
+    let dc = 8 + john.proficiency_bonus() + cha;
+    assert_eq!(john.racial_spellcasting_scores(), Some((dc, dc - 8)));
+    let rebuke = john.spell_actions();
+    assert_eq!(rebuke[0].name, "Hellish Rebuke");
+    assert_eq!(rebuke[0].spell_save_dc, dc);
+
+    assert_eq!(character("jim").racial_spellcasting_scores(), None);
+}
+
+#[test]
+fn concentration_saves() {
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    // con 14 and no proficiency
+    assert_eq!(jill.concentration_save_bonus(), 2);
+    assert!(!jill.concentration_save_advantage());
+
+    jill.bonus_features.push(Feature {
+        name: "Resilient (Constitution)".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::AddSaveProficiency(StatType::Constitution)],
+    });
+    assert_eq!(jill.concentration_save_bonus(), 4);
+    assert!(!jill.concentration_save_advantage());
+
+    jill.bonus_features.push(Feature {
+        name: "War Caster".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::ConcentrationAdvantage],
+    });
+    assert!(jill.concentration_save_advantage());
+}
+
+#[test]
+fn superiority_dice() {
+    let dice_count: [String; 20] = std::array::from_fn(|i| {
+        match i + 1 {
+            1..=2 => "0",
+            3..=6 => "4",
+            7..=14 => "5",
+            _ => "6",
+        }
+        .to_string()
+    });
+    let die_size: [String; 20] = std::array::from_fn(|i| {
+        match i + 1 {
+            1..=9 => "d8",
+            10..=17 => "d10",
+            _ => "d12",
+        }
+        .to_string()
+    });
+    let battle_master = ClassBuilder::new()
+        .name("battle master".to_string())
+        .add_feature_at_level(
+            1,
+            Feature {
+                name: "Combat Superiority".to_string(),
+                description: vec![],
+                effects: vec![],
+            },
+        )
+        .set_hit_die(10)
+        .set_skill_proficiency_choices(1, vec![SkillType::Athletics])
+        .add_class_specific_fields([
+            ("superiority dice".to_string(), dice_count),
+            ("superiority die".to_string(), die_size),
+        ])
+        .add_tracked_field(TrackedField {
+            name: "Superiority Dice".to_string(),
+            long_rest: true,
+            short_rest: true,
+            level_up: false,
+            class_specific_max: Some("superiority dice".to_string()),
+            hard_max: None,
+            class_specific_die: Some("superiority die".to_string()),
+        })
+        .build()
+        .unwrap();
+
+    let mut john = Character::new(
+        "john".to_string(),
+        &battle_master,
+        &soldier(),
+        &human(),
+        Stats::from(&[15, 13, 14, 8, 12, 10]),
+    );
+    assert_eq!(john.use_maneuver(DamageType::Slashing), None);
+
+    john.level_up_to_level(&battle_master, 10);
+    // 8 + 4 proficiency + 3 strength
+    assert_eq!(john.maneuver_save_dc(), 15);
+    for _ in 0..5 {
+        let die = john.use_maneuver(DamageType::Slashing).unwrap();
+        assert_eq!((die.number, die.dice), (1, 10));
+        assert_eq!(die.damage_type, DamageType::Slashing);
+    }
+    assert_eq!(john.use_maneuver(DamageType::Slashing), None);
+
+    john.long_rest();
+    assert!(john.use_maneuver(DamageType::Slashing).is_some());
+}
+
+#[test]
+fn level_up_with_xp() {
+    let fighter = fighter();
+    let mut john = character("john");
+    assert!(!john.can_level_up());
+    assert_eq!(
+        john.level_up_with_xp(&fighter),
+        Err(LevelError::NotEnoughXp { needed: 300 })
+    );
+
+    john.add_xp(1000);
+    assert!(john.can_level_up());
+    assert_eq!(john.level_up_with_xp(&fighter), Ok(2));
+    assert_eq!(john.level_up_with_xp(&fighter), Ok(3));
+    assert_eq!(john.level(), 3);
+    assert_eq!(
+        john.level_up_with_xp(&fighter),
+        Err(LevelError::NotEnoughXp { needed: 2700 })
+    );
+
+    // charisma 12, so john can't multiclass into a class that needs 13
+    let picky = ClassBuilder::new()
+        .name("bard".to_string())
+        .add_feature_at_level(
+            1,
+            Feature {
+                name: "Bardic Inspiration".to_string(),
+                description: vec![],
+                effects: vec![],
+            },
+        )
+        .set_hit_die(8)
+        .set_skill_proficiency_choices(1, vec![SkillType::Performance])
+        .add_multiclassing_prerequisite(StatType::Charisma, 13)
+        .build()
+        .unwrap();
+    john.add_xp(2000);
+    assert_eq!(
+        john.level_up_with_xp(&picky),
+        Err(LevelError::FailedMulticlassPrereq)
+    );
+    assert_eq!(john.level(), 3);
+
+    john.add_xp(XP_BY_LEVEL[19]);
+    john.level_up_to_level(&fighter, 20);
+    assert!(!john.can_level_up());
+    assert_eq!(john.level_up_with_xp(&fighter), Err(LevelError::MaxLevel));
+}
+
+#[test]
+fn magic_items() {
+    let mut john = character("john");
+    let mut cloak = Item {
+        name: "Cloak of Protection".to_string(),
+        description: None,
+        item_type: ItemType::Misc,
+        weight: None,
+        attunement: None,
+        features: vec![Feature {
+            name: "Protection".to_string(),
+            description: vec![],
+            effects: vec![FeatureEffect::ACBonus(1)],
+        }],
+    };
+    john.items.push((longsword(), 1, true).into());
+    john.items.push((cloak.clone(), 1, false).into());
+    cloak.name = "Ring of Protection".to_string();
+    john.items.push((cloak, 1, true).into());
+
+    let names =
+        |items: Vec<&Item>| -> Vec<String> { items.into_iter().map(|i| i.name.clone()).collect() };
+    let magic = john.magic_items();
+    assert_eq!(magic.len(), 2);
+    assert_eq!(magic[0].0.name, "Cloak of Protection");
+    assert!(!magic[0].1);
+    assert!(magic[1].1);
+    assert_eq!(names(john.active_magic_items()), vec!["Ring of Protection"]);
+}
+
+#[test]
+fn attunement() {
+    let mut john = character("john");
+    let ac = john.ac();
+    let ring = |n: usize| Item {
+        name: format!("Ring of Protection {n}"),
+        description: None,
+        item_type: ItemType::Misc,
+        weight: None,
+        attunement: Some(false),
+        features: vec![Feature {
+            name: "Protection".to_string(),
+            description: vec![],
+            effects: vec![FeatureEffect::ACBonus(1)],
+        }],
+    };
+    john.items.push((longsword(), 1, true).into());
+    let first = john.items.len();
+    for n in 0..4 {
+        john.items.push((ring(n), 1, true).into());
+    }
+
+    // equipped, but not attuned
+    assert_eq!(john.ac(), ac);
+    assert!(john.active_magic_items().is_empty());
+
+    // the longsword doesn't need attunement
+    assert!(!john.try_attune(first - 1));
+    assert!(!john.try_attune(first + 4));
+    for n in 0..3 {
+        assert!(john.try_attune(first + n));
+    }
+    assert!(john.try_attune(first));
+    assert_eq!(john.attuned_count(), 3);
+    assert!(!john.try_attune(first + 3));
+    assert_eq!(john.ac(), ac + 3);
+
+    assert!(john.end_attunement(first));
+    assert!(!john.end_attunement(first));
+    assert!(john.try_attune(first + 3));
+    assert_eq!(john.active_magic_items().len(), 3);
+}
+
+#[test]
+fn spellcasting_bonuses() {
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &warlock(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 14, 13, 10, 12, 15]),
+    );
+    // charisma 15 + 1 from being human
+    assert_eq!(jill.spellcasting_scores(0), Some((13, 5)));
+
+    let rod = Item {
+        name: "Rod of the Pact Keeper".to_string(),
+        description: None,
+        item_type: ItemType::Misc,
+        weight: Some(2.0),
+        attunement: Some(false),
+        features: vec![Feature {
+            name: "Pact Keeper".to_string(),
+            description: vec![],
+            effects: vec![
+                FeatureEffect::SpellSaveDcBonus(1, Some("warlock".to_string())),
+                FeatureEffect::SpellAttackBonus(1, Some("warlock".to_string())),
+            ],
+        }],
+    };
+    jill.items.push((rod, 1, false).into());
+    let rod_index = jill.items.len() - 1;
+
+    // it needs to be both equipped and attuned
+    assert!(jill.try_attune(rod_index));
+    assert_eq!(jill.spellcasting_scores(0), Some((13, 5)));
+    jill.items[rod_index].equipped = true;
+    assert_eq!(jill.spellcasting_scores(0), Some((14, 6)));
+
+    // the rod only helps warlock spells
+    jill.level_up(&cleric());
+    // wisdom 12
+    assert_eq!(jill.spellcasting_scores(1), Some((11, 3)));
+    jill.end_attunement(rod_index);
+    assert_eq!(jill.spellcasting_scores(0), Some((13, 5)));
+
+    jill.bonus_features.push(Feature {
+        name: "Sharp Focus".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::SpellAttackBonus(2, None)],
+    });
+    assert_eq!(jill.spellcasting_scores(0), Some((13, 7)));
+    assert_eq!(jill.spellcasting_scores(1), Some((11, 5)));
+}
+
+#[test]
+fn feat_prerequisites() {
+    let feat = |name: &str, prerequisites: Vec<Prerequisite>, effects: Vec<FeatureEffect>| Feat {
+        name: name.to_string(),
+        prerequisites,
+        features: vec![Feature {
+            name: name.to_string(),
+            description: vec![],
+            effects,
+        }],
+    };
+    let grappler = feat(
+        "Grappler",
+        vec![Prerequisite::MinAbility(StatType::Strength, 13)],
+        vec![],
+    );
+    let heavy_armor_master = feat(
+        "Heavy Armor Master",
+        vec![Prerequisite::Proficiency(ArmorCategory::Heavy)],
+        vec![],
+    );
+    let heavily_armored = feat(
+        "Heavily Armored",
+        vec![Prerequisite::Proficiency(ArmorCategory::Medium)],
+        vec![FeatureEffect::ArmorProficiency(ArmorCategory::Heavy)],
+    );
+
+    let mut john = character("john");
+    assert!(john.meets_prerequisites(&grappler));
+    assert!(john.meets_prerequisites(&heavy_armor_master));
+
+    // strength 9, and no heavy armor proficiency
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    assert!(!jill.meets_prerequisites(&grappler));
+    assert!(!jill.add_feat(grappler.clone()));
+    assert!(!jill.add_feat(heavy_armor_master.clone()));
+    let war_caster = feat("War Caster", vec![Prerequisite::Spellcasting], vec![]);
+    assert!(jill.meets_prerequisites(&war_caster));
+    assert!(!john.meets_prerequisites(&war_caster));
+
+    assert!(jill.add_feat(heavily_armored));
+    assert!(jill.equipment_proficiencies().heavy_armor);
+    assert!(jill.add_feat(heavy_armor_master));
+    assert_eq!(jill.feats.len(), 2);
+    assert!(john.add_feat(grappler));
+}
+
+#[test]
+fn apply_feat() {
+    let athlete = |prerequisites| Feat {
+        name: "Athlete".to_string(),
+        prerequisites,
+        features: vec![Feature {
+            name: "Athlete".to_string(),
+            description: vec![],
+            effects: vec![FeatureEffect::AddModifier(StatType::Dexterity, 1)],
+        }],
+    };
+    let mut john = character("john");
+    // no ability score increase before level 4
+    assert!(!john.apply_feat(0, 0, athlete(vec![])));
+
+    john.level_up_to_level(&fighter(), 6);
+    let dex = john.stats().dexterity;
+    let pending = john.pending_choices();
+    assert!(pending.contains(&PendingChoice::AbilityScoreIncrease {
+        class_index: 0,
+        level: 4,
+        feature_index: 0,
+    }));
+    // the first ability score increase is the one at level 4
+    assert!(john.apply_feat(0, 0, athlete(vec![])));
+    assert!(!john.pending_choices().contains(&PendingChoice::AbilityScoreIncrease {
+        class_index: 0,
+        level: 4,
+        feature_index: 0,
+    }));
+    assert_eq!(john.stats().dexterity, dex + 1);
+    assert_eq!(john.feats.len(), 1);
+    assert_eq!(john.pending_choices().len(), pending.len() - 1);
+
+    // the level 4 increase is used up, the level 6 one is still open
+    assert!(!john.apply_feat(0, 0, athlete(vec![])));
+    assert!(!john.apply_feat(0, 2, athlete(vec![])));
+    assert!(!john.apply_feat(1, 1, athlete(vec![])));
+    assert!(!john.apply_feat(
+        0,
+        1,
+        athlete(vec![Prerequisite::MinAbility(StatType::Strength, 20)])
+    ));
+    assert!(john.apply_feat(0, 1, athlete(vec![])));
+    assert_eq!(john.stats().dexterity, dex + 2);
+    assert_eq!(john.feats.len(), 2);
+
+    // the feats' features are only counted once each
+    assert!(john.ability_score_increase_features().is_empty());
+    let athletes = john
+        .total_features()
+        .iter()
+        .filter(|f| f.name == "Athlete")
+        .count();
+    assert_eq!(athletes, 2);
+}
+
+#[test]
+fn ac_in_context() {
+    let mut john = character("john");
+    john.items.push((chain_mail(), 1, true).into());
+    assert_eq!(john.ac(), 16);
+    assert_eq!(john.ac_in_context(CombatContext::default()), 16);
+
+    let shielded = CombatContext {
+        shield_spell_active: true,
+        shield_of_faith_active: true,
+        ..Default::default()
+    };
+    assert_eq!(john.ac_in_context(shielded), 23);
+    // the shield spell doesn't last
+    assert_eq!(john.ac(), 16);
+
+    let dueling = CombatContext {
+        defensive_duelist: true,
+        ..Default::default()
+    };
+    // no feat and no finesse weapon
+    assert_eq!(john.ac_in_context(dueling), 16);
+    john.bonus_features.push(Feature {
+        name: "Defensive Duelist".to_string(),
+        description: vec![],
+        effects: vec![],
+    });
+    assert_eq!(john.ac_in_context(dueling), 16);
+
+    let rapier = Item {
+        name: "Rapier".to_string(),
+        description: None,
+        item_type: ItemType::Weapon(Weapon {
+            damage: DamageRoll::new(1, 8, 0, DamageType::Piercing),
+            attack_roll_bonus: 0,
+            weapon_type: WeaponType::Martial,
+            properties: WeaponProperties {
+                finesse: true,
+                ..Default::default()
+            },
+            range: None,
+            thrown_range: None,
+        }),
+        weight: None,
+        attunement: None,
+        features: vec![],
+    };
+    john.items.push((rapier, 1, true).into());
+    assert_eq!(john.ac_in_context(dueling), 18);
+}
+
+#[test]
+fn hypothetical_weapon_attack() {
+    let john = character("john");
+    let ItemType::Weapon(sword) = longsword().item_type else {
+        panic!("longsword should be a weapon")
+    };
+    // +3 strength, +2 proficiency
+    let attack = john.hypothetical_weapon_attack(&sword, "Longsword");
+    assert_eq!(attack.name, "Longsword");
+    assert_eq!(attack.attack_bonus, 5);
+    assert!(!attack.two_handed);
+    assert!(john.weapon_actions().iter().all(|a| a.name != "Longsword"));
+
+    // a monk isn't proficient with martial weapons
+    let jim = Character::new(
+        "jim".to_string(),
+        &monk(),
+        &soldier(),
+        &human(),
+        Stats::from(&[12, 15, 13, 10, 14, 8]),
+    );
+    let attack = jim.hypothetical_weapon_attack(&sword, "Longsword");
+    assert_eq!(attack.attack_bonus, 1);
+}
+
+#[test]
+fn background_proficiency_choices() {
+    let skills = vec![
+        SkillType::Deception,
+        SkillType::SleightOfHand,
+        SkillType::Stealth,
+    ];
+    let charlatan = BackgroundBuilder::new("charlatan")
+        .add_proficiency(PresentedOption::Choice(skills.clone()))
+        .add_proficiency(PresentedOption::Choice(skills))
+        .add_personality_trait("I fall in and out of love easily.".to_string())
+        .add_personality_trait("I have a joke for every occasion.".to_string())
+        .add_ideal("Independence. I am a free spirit.".to_string())
+        .add_bond("I fleeced the wrong person.".to_string())
+        .add_flaw("I can't resist swindling people more powerful than me.".to_string())
+        .build()
+        .unwrap();
+
+    let fighter = fighter();
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter,
+        &charlatan,
+        &human(),
+        Stats::default(),
+    );
+    assert_eq!(john.pending_background_proficiencies(), vec![0, 1]);
+    assert!(!john.skills().get_from_type(SkillType::Stealth).proficiency);
+
+    assert!(john.choose_background_proficiency(1, 2));
+    assert!(!john.choose_background_proficiency(1, 0));
+    assert!(!john.choose_background_proficiency(0, 3));
+    assert_eq!(john.pending_background_proficiencies(), vec![0]);
+    assert!(john.skills().get_from_type(SkillType::Stealth).proficiency);
+
+    assert!(john.choose_background_proficiency(0, 0));
+    assert!(john.pending_background_proficiencies().is_empty());
+    assert!(
+        john.skills()
+            .get_from_type(SkillType::Deception)
+            .proficiency
+    );
+
+    // the soldier's proficiencies are fixed
+    assert!(character("jim")
+        .pending_background_proficiencies()
+        .is_empty());
+}
+
+#[test]
+fn choose_skills_by_type() {
+    let mut john = character("john");
+    assert!(!john.choose_class_skill(SkillType::Arcana));
+    assert!(john.choose_class_skill(SkillType::Perception));
+    // each skill can only be chosen once
+    assert!(!john.choose_class_skill(SkillType::Perception));
+    assert!(john.choose_class_skill(SkillType::Athletics));
+    // fighters only get two
+    assert!(!john.choose_class_skill(SkillType::Acrobatics));
+    assert_eq!(
+        chosen(&john.class_skill_proficiencies),
+        vec![&SkillType::Perception, &SkillType::Athletics]
+    );
+
+    // the soldier's proficiencies are fixed
+    assert!(!john.choose_background_skill(SkillType::Athletics));
+    let charlatan = BackgroundBuilder::new("charlatan")
+        .add_proficiency(PresentedOption::Base(SkillType::Deception))
+        .add_proficiency(PresentedOption::Choice(vec![
+            SkillType::Deception,
+            SkillType::Stealth,
+        ]))
+        .add_personality_trait("I fall in and out of love easily.".to_string())
+        .add_personality_trait("I have a joke for every occasion.".to_string())
+        .add_ideal("Independence. I am a free spirit.".to_string())
+        .add_bond("I fleeced the wrong person.".to_string())
+        .add_flaw("I can't resist swindling people more powerful than me.".to_string())
+        .build()
+        .unwrap();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &fighter(),
+        &charlatan,
+        &human(),
+        Stats::default(),
+    );
+    assert!(!jill.choose_background_skill(SkillType::Deception));
+    assert!(jill.choose_background_skill(SkillType::Stealth));
+    assert!(jill.pending_background_proficiencies().is_empty());
+}
+
+#[test]
+fn effect_sources() {
+    let mut john = character("john");
+    let ac_bonus = |n| Feature {
+        name: "Protection".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::ACBonus(n)],
+    };
+    john.items.push(
+        (
+            Item {
+                name: "Ring of Protection".to_string(),
+                description: None,
+                item_type: ItemType::Misc,
+                weight: None,
+                attunement: None,
+                features: vec![ac_bonus(1)],
+            },
+            1,
+            true,
+        )
+            .into(),
+    );
+    john.bonus_features.push(ac_bonus(2));
+
+    let sources = john.effect_sources(|e| matches!(e, FeatureEffect::ACBonus(_)));
+    assert_eq!(
+        sources,
+        vec![
+            (FeatureSource::Item, &FeatureEffect::ACBonus(1)),
+            (FeatureSource::Bonus, &FeatureEffect::ACBonus(2)),
+        ]
+    );
+    assert!(john
+        .effect_sources(|e| matches!(e, FeatureEffect::SpeedBonus(_)))
+        .is_empty());
+    assert_eq!(
+        john.features_with_source().len(),
+        john.total_features().len()
+    );
+
+    let owned = john.total_features_owned();
+    assert_eq!(owned.len(), john.total_features().len());
+    let by_source = john.features_by_source();
+    assert_eq!(by_source[&FeatureSource::Item], vec![ac_bonus(1)]);
+    assert_eq!(by_source[&FeatureSource::Bonus], vec![ac_bonus(2)]);
+    assert!(!by_source.contains_key(&FeatureSource::Subclass));
+    assert_eq!(by_source.values().map(Vec::len).sum::<usize>(), owned.len());
+}
+
+#[test]
+fn estimated_cr() {
+    let mut john = character("john");
+    // 12 hp and 12 ac is 1/8 defensively, and an unarmed strike is 1/2 offensively
+    assert_eq!(john.estimated_cr(), 0.3125);
+
+    // a longsword deals 9.5 damage, which is CR 1, and a +5 to hit is 2 over the +3 expected
+    john.items.push((longsword(), 1, true).into());
+    assert_eq!(john.estimated_cr(), 1.0625);
+
+    // chain mail's 16 ac bumps defense up to 1/4
+    john.items.push((chain_mail(), 1, true).into());
+    assert_eq!(john.estimated_cr(), 1.125);
+
+    assert_eq!(john.attacks_per_action(), 1);
+    john.bonus_features.push(Feature {
+        name: "Extra Attack".to_string(),
+        description: vec![],
+        effects: vec![],
+    });
+    assert_eq!(john.attacks_per_action(), 2);
+    // 55% to hit ac 15 for 9.5, plus 5% for another 4.5 on a crit, twice
+    assert!((john.expected_turn_damage(15, 0) - 10.9).abs() < 1e-9);
+    // 19 damage a round is CR 2, or 3 with the bonus
+    assert_eq!(john.estimated_cr(), 1.625);
+}
+
+#[test]
+fn long_rest_hit_dice_picks() {
+    let fighter = fighter();
+    let cleric = cleric();
+    let mut john = character("john");
+    john.level_up(&fighter).unwrap();
+    john.level_up(&cleric).unwrap();
+    john.level_up(&cleric).unwrap();
+    assert_eq!(john.available_hit_dice(), vec![(10, 2), (8, 2)]);
+
+    // fighter dice go first, since it's john's first class
+    john.damage(30);
+    assert!(john.short_rest(3, None));
+    assert_eq!(john.available_hit_dice(), vec![(10, 0), (8, 1)]);
+    assert_eq!(john.spent_hit_dice, 3);
+
+    // level 4 regains 2 hit dice
+    assert!(!john.long_rest_recover_hit_dice(&[(10, 1)]));
+    assert!(!john.long_rest_recover_hit_dice(&[(8, 2)]));
+    assert!(!john.long_rest_recover_hit_dice(&[(12, 2)]));
+    assert_eq!(john.available_hit_dice(), vec![(10, 0), (8, 1)]);
+    assert!(john.long_rest_recover_hit_dice(&[(10, 1), (8, 1)]));
+    assert_eq!(john.available_hit_dice(), vec![(10, 1), (8, 2)]);
+    assert_eq!(john.spent_hit_dice, 1);
+
+    // only 1 is spent, so only 1 can be regained
+    assert!(!john.long_rest_recover_hit_dice(&[(10, 1), (8, 1)]));
+    assert!(john.long_rest_recover_hit_dice(&[(10, 1)]));
+
+    // the default long rest regains the largest first
+    assert!(john.short_rest(4, None));
+    john.long_rest();
+    assert_eq!(john.available_hit_dice(), vec![(10, 2), (8, 0)]);
+}
+
+#[test]
+fn range_penalty() {
+    let john = character("john");
+    let shortbow = Weapon {
+        damage: DamageRoll::new(1, 6, 0, DamageType::Piercing),
+        attack_roll_bonus: 0,
+        weapon_type: WeaponType::SimpleRanged,
+        properties: WeaponProperties {
+            ammunition: true,
+            two_handed: true,
+            ..Default::default()
+        },
+        range: Some((80, 320)),
+        thrown_range: None,
+    };
+    let attack = john.hypothetical_weapon_attack(&shortbow, "Shortbow");
+    assert_eq!(attack.range_penalty_at(50), RangeStatus::Normal);
+    assert_eq!(attack.range_penalty_at(200), RangeStatus::LongRange);
+    assert_eq!(attack.range_penalty_at(400), RangeStatus::OutOfRange);
+
+    let ItemType::Weapon(sword) = longsword().item_type else {
+        panic!("longsword should be a weapon")
+    };
+    let attack = john.hypothetical_weapon_attack(&sword, "Longsword");
+    assert_eq!(attack.range_penalty_at(5), RangeStatus::Normal);
+    assert_eq!(attack.range_penalty_at(10), RangeStatus::OutOfRange);
+
+    let glaive = Weapon {
+        properties: WeaponProperties {
+            reach: true,
+            ..Default::default()
+        },
+        ..sword.clone()
+    };
+    let attack = john.hypothetical_weapon_attack(&glaive, "Glaive");
+    assert_eq!(attack.range_penalty_at(10), RangeStatus::Normal);
+
+    // a thrown dagger can be used in melee too
+    let dagger = Weapon {
+        properties: WeaponProperties {
+            thrown: true,
+            ..Default::default()
+        },
+        thrown_range: Some((20, 60)),
+        ..sword
+    };
+    let attack = john.hypothetical_weapon_attack(&dagger, "Dagger");
+    assert_eq!(attack.range_penalty_at(5), RangeStatus::Normal);
+    assert_eq!(attack.range_penalty_at(40), RangeStatus::LongRange);
+}
+
+#[test]
+fn skill_proficiency_tiers() {
+    let fighter = fighter();
+    let mut john = character("john");
+    john.level_up_to_level(&fighter, 5);
+    john.bonus_features.push(Feature {
+        name: "Jack of All Trades".to_string(),
+        description: vec![],
+        effects: vec![
+            FeatureEffect::JackOfAllTrades,
+            FeatureEffect::Expertise([Some(SkillType::Athletics), None]),
+        ],
+    });
+
+    // the soldier background gives athletics and intimidation
+    let tier = |s| john.skill_proficiency_tier(s);
+    assert_eq!(tier(SkillType::Athletics), ProficiencyTier::Expertise);
+    assert_eq!(tier(SkillType::Intimidation), ProficiencyTier::Full);
+    assert_eq!(tier(SkillType::Arcana), ProficiencyTier::Half);
+
+    // a proficiency bonus of 3 halves to 1
+    let modifiers = john.skill_modifiers();
+    assert_eq!(modifiers[SkillType::Athletics], 3 + 6);
+    assert_eq!(modifiers[SkillType::Intimidation], 3);
+    assert_eq!(modifiers[SkillType::Arcana], -1 + 1);
+    assert_eq!(modifiers[SkillType::Stealth], 2 + 1);
+    assert_eq!(john.passive_skill(SkillType::Arcana), 10);
+
+    // remarkable athlete rounds up, but only for physical skills
+    john.bonus_features.push(Feature {
+        name: "Remarkable Athlete".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::RemarkableAthlete],
+    });
+    let modifiers = john.skill_modifiers();
+    assert_eq!(modifiers[SkillType::Stealth], 2 + 2);
+    assert_eq!(modifiers[SkillType::Arcana], -1 + 1);
+
+    assert_eq!(
+        character("jim").skill_proficiency_tier(SkillType::Arcana),
+        ProficiencyTier::None
+    );
+}
+
+#[test]
+fn heavy_encumbrance() {
+    // strength 9 after the human bonus
+    let mut john = Character::new(
+        "john".to_string(),
+        &fighter(),
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 14, 13, 10, 12, 10]),
+    );
+    let stealth = john.passive_skill(SkillType::Stealth);
+    assert_eq!(john.encumbrance_level(), Encumbrance::Unencumbered);
+    // strength 8, plus 1 from being human
+    assert_eq!(john.carrying_capacity(), 135.0);
+    assert!(!john.has_disadvantage_on(RollKind::Attack(StatType::Strength)));
+
+    let anvil = |weight| Item {
+        name: "Anvil".to_string(),
+        description: None,
+        item_type: ItemType::Misc,
+        weight: Some(weight),
+        attunement: None,
+        features: vec![],
+    };
+    john.items.push((anvil(25.0), 2, false).into());
+    assert_eq!(john.carried_weight(), 50.0);
+    assert_eq!(john.encumbrance_level(), Encumbrance::Encumbered);
+    assert!(!john.has_disadvantage_on(RollKind::Save(StatType::Dexterity)));
+
+    john.items.push((anvil(41.0), 1, false).into());
+    assert_eq!(john.encumbrance_level(), Encumbrance::HeavilyEncumbered);
+    // encumbrance is an optional rule
+    assert!(!john.has_disadvantage_on(RollKind::Attack(StatType::Strength)));
+    assert_eq!(john.passive_stealth(), stealth);
+    john.variant_encumbrance = true;
+    assert!(john.has_disadvantage_on(RollKind::Attack(StatType::Strength)));
+    assert!(john.has_disadvantage_on(RollKind::Save(StatType::Constitution)));
+    assert!(john.has_disadvantage_on(RollKind::AbilityCheck(StatType::Dexterity)));
+    assert!(!john.has_disadvantage_on(RollKind::Save(StatType::Wisdom)));
+    assert!(!john.has_disadvantage_on(RollKind::Attack(StatType::Charisma)));
+    assert_eq!(john.passive_skill(SkillType::Stealth), stealth - 5);
+    assert_eq!(john.passive_stealth(), stealth - 5);
+}
+
+#[test]
+fn cantrip_scaling() {
+    let cleric = cleric();
+    let mut jill = Character::new(
+        "jill".to_string(),
+        &cleric,
+        &soldier(),
+        &human(),
+        Stats::from(&[8, 12, 13, 10, 15, 14]),
+    );
+    let cantrip = |name: &str, levels: &[usize]| Spell {
+        leveled_damage: Some(
+            levels
+                .iter()
+                .enumerate()
+                .map(|(n, level)| (*level, DamageRoll::new(n + 1, 10, 0, DamageType::Fire)))
+                .collect(),
+        ),
+        mechanic: SpellMechanic::Attack,
+        ..spell(name, 0)
+    };
+    let spells = &mut jill.classes[0].spellcasting.as_mut().unwrap().1;
+    spells.push(cantrip("Fire Bolt", &[1, 5, 11, 17]));
+    // malformed, since it has no damage before level 5
+    spells.push(cantrip("Broken Bolt", &[5]));
+
+    let fire_bolt_dice = |jill: &Character| {
+        let actions = jill.spell_actions();
+        assert!(actions.iter().all(|a| a.name == "Fire Bolt"));
+        actions.first().map(|a| a.damage_roll.number)
+    };
+    assert_eq!(fire_bolt_dice(&jill), Some(1));
+    jill.level_up_to_level(&cleric, 4);
+    assert_eq!(fire_bolt_dice(&jill), Some(1));
+    jill.level_up_to_level(&cleric, 5);
+    assert_eq!(jill.spell_actions().len(), 2);
+    assert_eq!(jill.spell_actions()[0].damage_roll.number, 2);
+    jill.level_up_to_level(&cleric, 11);
+    assert_eq!(jill.spell_actions()[0].damage_roll.number, 3);
+}
+
+#[test]
+fn recommend_spell() {
+    let john = Character::new(
+        "john".to_string(),
+        &cleric(),
+        &soldier(),
+        &human(),
+        Stats::from(&[10, 12, 14, 10, 15, 10]),
+    );
+    let fire_bolt = Spell {
+        leveled_damage: Some(vec![(1, DamageRoll::new(1, 10, 0, DamageType::Fire))]),
+        mechanic: SpellMechanic::Attack,
+        ..spell("Fire Bolt", 0)
+    };
+    let guiding_bolt = Spell {
+        damage: Some(vec![vec![DamageRoll::new(4, 6, 0, DamageType::Radiant)]]),
+        mechanic: SpellMechanic::Attack,
+        ..spell("Guiding Bolt", 1)
+    };
+    let burning_hands = Spell {
+        description: vec!["Each creature in a 15-foot cone must make a saving throw.".to_string()],
+        damage: Some(vec![vec![DamageRoll::new(3, 6, 0, DamageType::Fire)]]),
+        mechanic: SpellMechanic::Save {
+            stat: StatType::Dexterity,
+            half_on_success: true,
+        },
+        ..spell("Burning Hands", 1)
+    };
+    let bless = spell("Bless", 1);
+    // john doesn't have 2nd level slots yet
+    let shatter = Spell {
+        description: vec![
+            "Each creature in a 10-foot-radius sphere must make a saving throw.".to_string(),
+        ],
+        damage: Some(vec![vec![DamageRoll::new(3, 8, 0, DamageType::Thunder)]]),
+        mechanic: SpellMechanic::Save {
+            stat: StatType::Constitution,
+            half_on_success: true,
+        },
+        ..spell("Shatter", 2)
+    };
+    let available = [&fire_bolt, &guiding_bolt, &burning_hands, &bless, &shatter];
+
+    let ogre = [StatBlock::new(
+        "Ogre",
+        Stats::from(&[19, 8, 16, 5, 7, 7]),
+        11,
+        59,
+    )];
+    let goblin = StatBlock::new("Goblin", Stats::from(&[8, 14, 10, 10, 8, 8]), 15, 7);
+
+    // one big target takes the most from guiding bolt
+    let pick = john.recommend_spell(&ogre, &available);
+    assert_eq!(pick.map(|s| s.name.as_str()), Some("Guiding Bolt"));
+
+    // but burning hands catches a whole group
+    let goblins = vec![goblin.clone(); 4];
+    let pick = john.recommend_spell(&goblins, &available);
+    assert_eq!(pick.map(|s| s.name.as_str()), Some("Burning Hands"));
+
+    // fire won't hurt these goblins
+    let mut immune = goblin;
+    immune.defenses.immunities.push(DamageType::Fire);
+    let pick = john.recommend_spell(&vec![immune; 4], &available);
+    assert_eq!(pick.map(|s| s.name.as_str()), Some("Guiding Bolt"));
+
+    let pick = john.recommend_spell(&ogre, &[&fire_bolt, &bless]);
+    assert_eq!(pick.map(|s| s.name.as_str()), Some("Fire Bolt"));
+    assert_eq!(john.recommend_spell(&ogre, &[&bless]), None);
+
+    assert_eq!(character("jim").recommend_spell(&ogre, &available), None);
+}
+
+#[test]
+fn editable_round_trip() {
+    let (fighter, cleric) = (fighter(), cleric());
+    let mut john = character("john");
+    john.choose_feature(0, 1, 0, 1);
+    john.class_skill_proficiencies[0].choose_in_place(2);
+    john.class_skill_proficiencies[1].choose_in_place(3);
+    john.race.choose_wildcard_language(0, "Elvish");
+    john.background.choose_language_option(0, "Dwarvish");
+    john.level_up_to_level(&fighter, 4);
+    john.ability_score_increases_mut()[0].set_stat_increase(StatType::Strength, None);
+    john.level_up_multiple(&cleric, 2);
+    john.classes[1]
+        .spellcasting
+        .as_mut()
+        .unwrap()
+        .1
+        .push(spell("Bless", 1));
+    john.items.push((longsword(), 1, true).into());
+    john.items.push((chain_mail(), 1, true).into());
+    john.story.backstory = vec!["Deserted the army.".to_string()];
+    john.damage(10);
+
+    let editable = john.to_editable();
+    assert_eq!(editable.classes[0].level, 4);
+    assert_eq!(editable.classes[1].spells, vec!["Bless"]);
+
+    let json = serde_json::to_string(&editable).unwrap();
+    let editable: EditableCharacter = serde_json::from_str(&json).unwrap();
+    let provider = HomebrewProvider;
+    let loaded =
+        futures::executor::block_on(Character::from_editable(editable, &provider)).unwrap();
+
+    assert_eq!(loaded.level(), 6);
+    assert_eq!(loaded.stats(), john.stats());
+    assert_eq!(loaded.skill_modifiers(), john.skill_modifiers());
+    assert_eq!(loaded.total_languages(), john.total_languages());
+    assert_eq!(loaded.ac(), john.ac());
+    assert_eq!(loaded.items, john.items);
+    assert_eq!(loaded.unique_spells(), john.unique_spells());
+    assert!(loaded
+        .class_features()
+        .iter()
+        .any(|f| f.name == "Fighting Style: Defense"));
+    assert_eq!(loaded.story.backstory, john.story.backstory);
+    // derived state starts fresh
+    assert_eq!(loaded.hp, loaded.max_hp());
+    assert_eq!(loaded.available_spell_slots, loaded.spell_slots());
+
+    let mut editable = john.to_editable();
+    editable.classes[1].name = "Wizard".to_string();
+    let result = futures::executor::block_on(Character::from_editable(editable, &provider));
+    assert!(matches!(
+        result,
+        Err(EditableCharacterError::Provider(Missing(_)))
+    ));
+
+    let mut editable = john.to_editable();
+    editable.classes[0].subclass = Some("Champion".to_string());
+    let result = futures::executor::block_on(Character::from_editable(editable, &provider));
+    assert!(matches!(
+        result,
+        Err(EditableCharacterError::InvalidChoice(_))
+    ));
+}
@@ -62,6 +62,34 @@ impl Race {
     }
 }
 
+/// Builds a [Race] for homebrew content.
+///
+/// Races default to a speed of 30 and [Size::Medium]. An ability bonus with no stat lets the
+/// player pick which stat it goes to, like a custom lineage's "+1 to any two abilities". Those
+/// are chosen on the character through `race.ability_bonuses_unchosen()`.
+///
+/// ```
+/// use dnd_lib::rules2014::RaceBuilder;
+/// use dnd_lib::rules2014::features::{Feature, FeatureEffect, PresentedOption};
+/// use dnd_lib::rules2014::stats::Size;
+///
+/// let lineage = RaceBuilder::new("custom lineage")
+///     .size(Size::Small)
+///     .add_ability_bonus(None, 1)
+///     .add_ability_bonus(None, 1)
+///     .add_trait(PresentedOption::Base(Feature {
+///         name: "Darkvision".to_string(),
+///         description: vec![],
+///         effects: vec![FeatureEffect::Darkvision(60)],
+///     }))
+///     .add_language("Common".to_string())
+///     .add_wildcard_language()
+///     .build();
+///
+/// assert_eq!(lineage.name(), "Custom Lineage");
+/// assert_eq!(lineage.speed(), 30);
+/// assert_eq!(lineage.ability_bonuses(), &vec![(None, 1), (None, 1)]);
+/// ```
 pub struct RaceBuilder {
     pub name: String,
     pub speed: usize,
@@ -101,6 +129,8 @@ impl RaceBuilder {
         self
     }
 
+    /// Adds a bonus to an ability score. With no stat, the player chooses which stat it applies
+    /// to.
     pub fn add_ability_bonus(mut self, stat: Option<StatType>, bonus: isize) -> Self {
         self.ability_bonuses.push((stat, bonus));
         self
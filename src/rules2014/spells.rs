@@ -4,7 +4,9 @@ use super::{
     items::{Action, DamageRoll},
     stats::StatType,
 };
+use heck::ToTitleCase;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 /// A spell definition, either manually created or loaded from an API.
 ///
@@ -47,6 +49,242 @@ pub struct Spell {
     /// The first field of the vec is the class level, and the second field is the damage.
     ///
     pub leveled_damage: Option<Vec<(usize, DamageRoll)>>,
+    /// How the spell's effect is resolved against a target: by an attack roll, a saving throw,
+    /// or neither.
+    #[serde(default)]
+    pub mechanic: SpellMechanic,
+}
+
+/// How a spell's effect is resolved against its target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpellMechanic {
+    /// The caster makes a spell attack roll against the target's AC, e.g. Fire Bolt.
+    Attack,
+    /// The target makes a saving throw against the caster's spell save DC, e.g. Fireball.
+    Save {
+        /// The ability used for the saving throw.
+        stat: StatType,
+        /// If a successful save still takes half damage.
+        half_on_success: bool,
+    },
+    /// The spell always takes effect, e.g. Magic Missile.
+    #[default]
+    Automatic,
+}
+
+/// An error in building a [Spell] with a [SpellBuilder].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Error)]
+pub enum SpellBuildError {
+    #[error("Spell school is required")]
+    MissingSchool,
+    #[error("Spell level must be between 0 and 9, not {0}")]
+    InvalidLevel(usize),
+    #[error("Spell damage at level {0} is outside of the levels the spell can be cast at")]
+    InvalidDamageLevel(usize),
+    #[error("Cantrips must scale damage by character level, not spell level")]
+    CantripWithSlotDamage,
+    #[error("Leveled spells must scale damage by spell level, not character level")]
+    LeveledSpellWithCantripScaling,
+}
+
+/// Builds a [Spell] for homebrew content.
+///
+/// Leveled spells get their damage from [SpellBuilder::add_damage_at_level], with one or more
+/// rolls for each level the spell can be cast at. Cantrips get theirs from
+/// [SpellBuilder::add_cantrip_scaling], with a roll for each character level it improves at.
+///
+/// ```rust
+/// use dnd_lib::rules2014::items::{DamageRoll, DamageType};
+/// use dnd_lib::rules2014::spells::{School, SpellBuilder};
+///
+/// let fire = |number| DamageRoll::new(number, 6, 0, DamageType::Fire);
+///
+/// let flame_lance = SpellBuilder::new("flame lance")
+///     .level(2)
+///     .school(School::Evocation)
+///     .range("120 feet".to_string())
+///     .components(['V', 'S'])
+///     .add_damage_at_level(2, fire(4))
+///     .add_damage_at_level(3, fire(5))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(flame_lance.name, "Flame Lance");
+/// assert_eq!(flame_lance.damage, Some(vec![vec![fire(4)], vec![fire(5)]]));
+///
+/// let spark = SpellBuilder::new("spark")
+///     .school(School::Evocation)
+///     .add_cantrip_scaling(1, fire(1))
+///     .add_cantrip_scaling(5, fire(2))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(spark.leveled_damage, Some(vec![(1, fire(1)), (5, fire(2))]));
+/// ```
+///
+/// The school is required. Spells default to level 0, a casting time of 1 action, instantaneous
+/// duration, a range of self, and no components.
+pub struct SpellBuilder {
+    spell: Spell,
+    school: Option<School>,
+    damage: Vec<(usize, DamageRoll)>,
+    leveled_damage: Vec<(usize, DamageRoll)>,
+}
+
+impl SpellBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            spell: Spell {
+                name: name.to_title_case(),
+                description: Vec::new(),
+                higher_level: Vec::new(),
+                ritual: false,
+                concentration: false,
+                casting_time: "1 action".to_string(),
+                duration: "Instantaneous".to_string(),
+                level: 0,
+                range: "Self".to_string(),
+                school: School::Evocation,
+                components: Vec::new(),
+                material: None,
+                damage: None,
+                leveled_damage: None,
+                mechanic: SpellMechanic::default(),
+            },
+            school: None,
+            damage: Vec::new(),
+            leveled_damage: Vec::new(),
+        }
+    }
+
+    /// Sets the spell's level, 0 for cantrips.
+    pub fn level(mut self, level: usize) -> Self {
+        self.spell.level = level;
+        self
+    }
+
+    pub fn school(mut self, school: School) -> Self {
+        self.school = Some(school);
+        self
+    }
+
+    pub fn range(mut self, range: String) -> Self {
+        self.spell.range = range;
+        self
+    }
+
+    pub fn casting_time(mut self, casting_time: String) -> Self {
+        self.spell.casting_time = casting_time;
+        self
+    }
+
+    pub fn duration(mut self, duration: String) -> Self {
+        self.spell.duration = duration;
+        self
+    }
+
+    /// Sets the spell's components, e.g. `['V', 'S', 'M']`.
+    pub fn components<I>(mut self, components: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        self.spell.components = components.into_iter().collect();
+        self
+    }
+
+    /// Sets the material component's description.
+    pub fn material(mut self, material: String) -> Self {
+        self.spell.material = Some(material);
+        self
+    }
+
+    pub fn concentration(mut self, concentration: bool) -> Self {
+        self.spell.concentration = concentration;
+        self
+    }
+
+    pub fn ritual(mut self, ritual: bool) -> Self {
+        self.spell.ritual = ritual;
+        self
+    }
+
+    /// Adds a paragraph to the spell's description.
+    pub fn add_description(mut self, paragraph: String) -> Self {
+        self.spell.description.push(paragraph);
+        self
+    }
+
+    /// Adds a paragraph to the spell's description of casting it at higher levels.
+    pub fn add_higher_level(mut self, paragraph: String) -> Self {
+        self.spell.higher_level.push(paragraph);
+        self
+    }
+
+    pub fn mechanic(mut self, mechanic: SpellMechanic) -> Self {
+        self.spell.mechanic = mechanic;
+        self
+    }
+
+    /// Adds a damage roll when the spell is cast with a slot of the given level.
+    ///
+    /// Adding more than one roll at the same level gives the spell multiple damage types to pick
+    /// from, like Chromatic Orb. Levels between the spell's level and the highest given level
+    /// without any damage are left empty.
+    pub fn add_damage_at_level(mut self, level: usize, damage: DamageRoll) -> Self {
+        self.damage.push((level, damage));
+        self
+    }
+
+    /// Adds a cantrip's damage roll from the given character level onwards.
+    pub fn add_cantrip_scaling(mut self, character_level: usize, damage: DamageRoll) -> Self {
+        self.leveled_damage.push((character_level, damage));
+        self
+    }
+
+    /// Builds the spell, consuming the builder.
+    ///
+    /// # Errors
+    /// Returns a [SpellBuildError] if the school is missing, the level is over 9, or the damage
+    /// doesn't match the kind of spell.
+    pub fn build(mut self) -> Result<Spell, SpellBuildError> {
+        use SpellBuildError::*;
+        let level = self.spell.level;
+        self.spell.school = self.school.ok_or(MissingSchool)?;
+        if level > 9 {
+            return Err(InvalidLevel(level));
+        }
+
+        if level == 0 {
+            if !self.damage.is_empty() {
+                return Err(CantripWithSlotDamage);
+            }
+        } else if !self.leveled_damage.is_empty() {
+            return Err(LeveledSpellWithCantripScaling);
+        }
+
+        if let Some(&(bad_level, _)) = self
+            .damage
+            .iter()
+            .find(|(slot_level, _)| !(level..=9).contains(slot_level))
+        {
+            return Err(InvalidDamageLevel(bad_level));
+        }
+
+        if let Some(highest) = self.damage.iter().map(|(l, _)| *l).max() {
+            let mut damage = vec![Vec::new(); highest - level + 1];
+            for (slot_level, roll) in self.damage {
+                damage[slot_level - level].push(roll);
+            }
+            self.spell.damage = Some(damage);
+        }
+
+        if !self.leveled_damage.is_empty() {
+            self.leveled_damage.sort_by_key(|(l, _)| *l);
+            self.spell.leveled_damage = Some(self.leveled_damage);
+        }
+
+        Ok(self.spell)
+    }
 }
 
 /// Represents a resolved spell's damage.
@@ -66,6 +304,33 @@ pub struct SpellAction {
     pub spell_level: isize,
     pub damage_roll: DamageRoll,
     pub spell_attack_mod: isize,
+    /// The spell save DC of the caster.
+    #[serde(default)]
+    pub spell_save_dc: isize,
+    /// How the spell is resolved against the target. See [SpellMechanic].
+    #[serde(default)]
+    pub mechanic: SpellMechanic,
+    /// Which spell slots can be spent to cast this at its level. See [SlotPool].
+    #[serde(default)]
+    pub slot_pool: SlotPool,
+}
+
+/// The spell slots a [SpellAction] can be cast with.
+///
+/// When a character has both regular spell slots and warlock pact magic, a spell may only be
+/// castable at a certain level with one of them. For example, a level 3 warlock/level 1 cleric
+/// can cast a 2nd level spell with their pact slots, but not with their regular spell slots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SlotPool {
+    /// No spell slot is used, e.g. for cantrips, or racial spells cast once per long rest.
+    NoSlot,
+    /// Only regular spell slots can be used.
+    SpellSlots,
+    /// Only warlock pact magic slots can be used.
+    PactSlots,
+    /// Either regular spell slots or pact slots can be used.
+    #[default]
+    Either,
 }
 
 impl PartialEq for SpellAction {
@@ -186,6 +451,36 @@ impl From<(usize, usize)> for PactSlots {
     }
 }
 
+/// The warlock levels at which a Mystic Arcanum is gained, along with the spell level of that
+/// arcanum.
+pub const MYSTIC_ARCANUM_LEVELS: [(usize, usize); 4] = [(11, 6), (13, 7), (15, 8), (17, 9)];
+
+/// A warlock's Mystic Arcanum.
+///
+/// High level warlocks choose a single spell of each level from 6th to 9th, which they can cast
+/// once per long rest without using a pact slot.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MysticArcanum {
+    /// The chosen spell. [None] if no spell has been chosen yet.
+    pub spell: Option<Spell>,
+    /// If the arcanum has been cast since the last long rest.
+    pub used: bool,
+}
+
+/// How often a racial (or otherwise innate) spell can be cast. See [Character::racial_spells](crate::Character::racial_spells).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RacialSpellUse {
+    /// The spell can be cast whenever, like a cantrip.
+    AtWill,
+    /// The spell can be cast once without a spell slot, and then not again until a long rest.
+    OncePerLongRest {
+        /// If the spell has been cast since the last long rest.
+        used: bool,
+    },
+    /// The spell is always prepared, but needs a spell slot to cast.
+    SpellSlot,
+}
+
 /// Spellcasting data for a class, including slots, ability, and spell lists.
 ///
 /// Cantrips but not spell slots are included, since cantrips are class-wide and spell slots are
@@ -202,6 +497,19 @@ pub struct Spellcasting {
     pub spellcaster_type: SpellCasterType,
     /// If the caster knows or prepares their spells.
     pub preperation_type: SpellCastingPreperation,
+    /// How many leveled spells a caster that knows their spells knows at each level.
+    ///
+    /// This is None for casters that prepare their spells, since that's decided by their
+    /// spellcasting ability instead.
+    #[serde(default)]
+    pub spells_known_per_level: Option<[usize; 20]>,
+    /// If leveled spells are prepared from a spellbook instead of the class's spell list, like a
+    /// wizard. These can't be checked against the spell list.
+    #[serde(default)]
+    pub prepares_from_spellbook: bool,
+    /// If the class can cast its ritual spells as rituals, without spending a spell slot.
+    #[serde(default)]
+    pub ritual_casting: bool,
 }
 
 /// Type of spellcaster (full caster, half caster, quarter-caster)
@@ -285,3 +593,83 @@ pub const PACT_CASTING_SLOTS: [(usize, usize); 20] = [
     (4, 5),
     (4, 5),
 ];
+
+/// The sorcery points it costs to create a spell slot with a sorcerer's Font of Magic, for 1st
+/// to 5th level slots. Slots above 5th level can't be created.
+pub const SORCERY_POINT_SLOT_COSTS: [usize; 5] = [2, 3, 5, 6, 7];
+
+#[cfg(test)]
+mod tests {
+    use super::super::items::{DamageRoll, DamageType};
+    use super::*;
+
+    #[test]
+    fn spell_builder() {
+        let roll = |number, damage_type| DamageRoll::new(number, 8, 0, damage_type);
+        let prismatic_bolt = SpellBuilder::new("prismatic bolt")
+            .level(1)
+            .school(School::Evocation)
+            .add_damage_at_level(1, roll(3, DamageType::Fire))
+            .add_damage_at_level(1, roll(3, DamageType::Cold))
+            .add_damage_at_level(3, roll(5, DamageType::Fire))
+            .build()
+            .unwrap();
+        assert_eq!(
+            prismatic_bolt.damage,
+            Some(vec![
+                vec![roll(3, DamageType::Fire), roll(3, DamageType::Cold)],
+                vec![],
+                vec![roll(5, DamageType::Fire)],
+            ])
+        );
+        assert_eq!(prismatic_bolt.leveled_damage, None);
+
+        let zap = SpellBuilder::new("zap")
+            .school(School::Evocation)
+            .add_cantrip_scaling(11, roll(3, DamageType::Lightning))
+            .add_cantrip_scaling(1, roll(1, DamageType::Lightning))
+            .build()
+            .unwrap();
+        assert_eq!(
+            zap.leveled_damage,
+            Some(vec![
+                (1, roll(1, DamageType::Lightning)),
+                (11, roll(3, DamageType::Lightning)),
+            ])
+        );
+        assert_eq!(zap.damage, None);
+
+        let builder = || SpellBuilder::new("bad").school(School::Evocation);
+        assert_eq!(
+            SpellBuilder::new("bad").build().unwrap_err(),
+            SpellBuildError::MissingSchool
+        );
+        assert_eq!(
+            builder().level(10).build().unwrap_err(),
+            SpellBuildError::InvalidLevel(10)
+        );
+        assert_eq!(
+            builder()
+                .add_damage_at_level(1, roll(1, DamageType::Fire))
+                .build()
+                .unwrap_err(),
+            SpellBuildError::CantripWithSlotDamage
+        );
+        assert_eq!(
+            builder()
+                .level(1)
+                .add_cantrip_scaling(1, roll(1, DamageType::Fire))
+                .build()
+                .unwrap_err(),
+            SpellBuildError::LeveledSpellWithCantripScaling
+        );
+        assert_eq!(
+            builder()
+                .level(2)
+                .add_damage_at_level(1, roll(1, DamageType::Fire))
+                .build()
+                .unwrap_err(),
+            SpellBuildError::InvalidDamageLevel(1)
+        );
+    }
+}
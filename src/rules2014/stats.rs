@@ -8,12 +8,23 @@ use std::{
 };
 use strum::{Display as StrumDisplay, EnumIter, EnumString, IntoEnumIterator};
 
-use serde::{Deserialize, Serialize};
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use super::class::Class;
+use super::items::{is_proficient_with, ArmorCategory, WeaponType};
 
 // proficiency bonus values for each level
 pub const PROFICIENCY_BY_LEVEL: [isize; 20] =
     [2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6];
 
+/// The total experience points needed to reach each level.
+pub const XP_BY_LEVEL: [usize; 20] = [
+    0, 300, 900, 2700, 6500, 14000, 23000, 34000, 48000, 64000, 85000, 100000, 120000, 140000,
+    165000, 195000, 225000, 265000, 305000, 355000,
+];
+
 /// Base ability scores.
 /// These are total scores, not modifiers.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
@@ -74,6 +85,13 @@ impl Stats {
         }
     }
 
+    /// Returns stats with every score at 0.
+    ///
+    /// Useful for representing changes to stats, rather than the scores themselves.
+    pub fn zero() -> Stats {
+        Stats::from(&[0; 6])
+    }
+
     /// Returns a mutable refrence to the value of the given stat type.
     pub fn get_stat_type_mut(&mut self, stat_type: &StatType) -> &mut isize {
         &mut self[*stat_type]
@@ -83,6 +101,162 @@ impl Stats {
     pub fn get_stat_type(&self, stat_type: &StatType) -> &isize {
         &self[*stat_type]
     }
+
+    /// Suggests an assignment of six rolled (or standard array) values for a class.
+    ///
+    /// The highest value goes into the class's most important ability, the next highest into the
+    /// second, and so on. The order comes from [Class::ability_priority], followed by the class's
+    /// spellcasting ability, and then a general default of Constitution, Dexterity, Wisdom,
+    /// Strength, Charisma, and Intelligence for anything left over.
+    ///
+    /// This is just a suggestion; any assignment of the values is valid.
+    ///
+    /// ```
+    /// use dnd_lib::rules2014::class::ClassBuilder;
+    /// use dnd_lib::rules2014::features::Feature;
+    /// use dnd_lib::rules2014::stats::{SkillType, StatType, Stats};
+    ///
+    /// let brawl = Feature {
+    ///     name: "Brawl".to_string(),
+    ///     description: vec![],
+    ///     effects: vec![],
+    /// };
+    /// let class = ClassBuilder::new()
+    ///     .name("brawler".to_string())
+    ///     .add_feature_at_level(1, brawl)
+    ///     .set_hit_die(10)
+    ///     .set_skill_proficiency_choices(1, vec![SkillType::Athletics])
+    ///     .set_ability_priority([StatType::Strength, StatType::Constitution])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let stats = Stats::assign_for_class([8, 10, 12, 13, 14, 15], &class);
+    /// assert_eq!(stats.strength, 15);
+    /// assert_eq!(stats.constitution, 14);
+    /// assert_eq!(stats.dexterity, 13);
+    /// ```
+    pub fn assign_for_class(values: [isize; 6], class: &Class) -> Stats {
+        use StatType::*;
+        let default_priority = [
+            Constitution,
+            Dexterity,
+            Wisdom,
+            Strength,
+            Charisma,
+            Intelligence,
+        ];
+        let spellcasting_ability = class.spellcasting().map(|s| s.spellcasting_ability);
+
+        let mut order: Vec<StatType> = Vec::with_capacity(6);
+        for stat in class
+            .ability_priority()
+            .iter()
+            .copied()
+            .chain(spellcasting_ability)
+            .chain(default_priority)
+        {
+            if !order.contains(&stat) {
+                order.push(stat);
+            }
+        }
+
+        let mut values = values;
+        values.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut stats = Stats::default();
+        for (stat, value) in order.into_iter().zip(values) {
+            stats[stat] = value;
+        }
+        stats
+    }
+
+    /// Rolls six ability scores with the standard method: roll 4d6 and drop the lowest die.
+    ///
+    /// The scores aren't assigned to any ability. Use [Stats::assign_for_class] or [Stats::from]
+    /// to place them.
+    pub fn roll_4d6_drop_lowest<R: Rng>(rng: &mut R) -> [isize; 6] {
+        std::array::from_fn(|_| {
+            let mut dice: [u8; 4] = std::array::from_fn(|_| rng.random_range(1..=6));
+            dice.sort_unstable();
+            dice[1..].iter().map(|&v| v as isize).sum()
+        })
+    }
+
+    /// How many points these scores cost with the 27 point buy, or None if any score is outside
+    /// the 8 to 15 that point buy allows.
+    ///
+    /// Each score costs one point per step above 8, and 14 and 15 cost two points each.
+    ///
+    /// ```
+    /// use dnd_lib::rules2014::stats::Stats;
+    ///
+    /// let stats = Stats::from(&[15, 15, 15, 8, 8, 8]);
+    /// assert_eq!(stats.point_buy_cost(), Some(27));
+    /// assert_eq!(Stats::from(&[16, 8, 8, 8, 8, 8]).point_buy_cost(), None);
+    /// ```
+    pub fn point_buy_cost(&self) -> Option<usize> {
+        StatType::iter()
+            .map(|stat| match self[stat] {
+                8..=13 => Some((self[stat] - 8) as usize),
+                14 => Some(7),
+                15 => Some(9),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Rolls six ability scores with the classic method, where each is a straight 3d6.
+    ///
+    /// Like [Stats::roll_4d6_drop_lowest], the scores aren't assigned to any ability.
+    pub fn roll_3d6<R: Rng>(rng: &mut R) -> [isize; 6] {
+        std::array::from_fn(|_| (0..3).map(|_| rng.random_range(1..=6u8) as isize).sum())
+    }
+}
+
+/// Prints the scores separated by spaces, like "15 14 13 12 10 8".
+///
+/// The order is strength, dexterity, constitution, intelligence, wisdom, and charisma, the same
+/// as [Stats::from] takes them in. This can be parsed back with [str::parse].
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scores: Vec<String> = Vec::from(*self).iter().map(|v| v.to_string()).collect();
+        write!(f, "{}", scores.join(" "))
+    }
+}
+
+/// Parses six space separated scores, in the order [Stats::from] takes them.
+///
+/// ```
+/// use dnd_lib::rules2014::stats::Stats;
+///
+/// let stats: Stats = "15 14 13 12 10 8".parse().unwrap();
+/// assert_eq!(stats.dexterity, 14);
+/// assert_eq!(stats.to_string(), "15 14 13 12 10 8");
+/// ```
+impl FromStr for Stats {
+    type Err = ParseStatsError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let scores = s
+            .split_whitespace()
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| ParseStatsError::InvalidScore(v.to_string()))
+            })
+            .collect::<Result<Vec<isize>, _>>()?;
+        let scores: [isize; 6] = scores
+            .try_into()
+            .map_err(|v: Vec<isize>| ParseStatsError::WrongCount(v.len()))?;
+        Ok(Stats::from(&scores))
+    }
+}
+
+/// An error in parsing [Stats] from a string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum ParseStatsError {
+    #[error("Expected 6 scores, not {0}")]
+    WrongCount(usize),
+    #[error("{0:?} isn't a valid score")]
+    InvalidScore(String),
 }
 
 impl Index<StatType> for Stats {
@@ -175,11 +349,32 @@ impl Default for Stats {
 }
 
 // A wrapper for [Stats] where each field is a modifier instead of a base score.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+//
+// This serializes as a flat stats object, like `{"strength": 2, ...}`. It can still be
+// deserialized from the old `{"stats": {...}}` form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
 pub struct Modifiers {
     pub stats: Stats,
 }
 
+impl<'de> Deserialize<'de> for Modifiers {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ModifiersShape {
+            Flat(Stats),
+            Nested { stats: Stats },
+        }
+
+        let stats = match ModifiersShape::deserialize(deserializer)? {
+            ModifiersShape::Flat(stats) => stats,
+            ModifiersShape::Nested { stats } => stats,
+        };
+        Ok(Modifiers { stats })
+    }
+}
+
 impl AsRef<Stats> for Modifiers {
     fn as_ref(&self) -> &Stats {
         &self.stats
@@ -416,6 +611,46 @@ impl SkillType {
     pub fn from_name(name: &str) -> Option<SkillType> {
         Self::from_str(&name.to_lowercase()).ok()
     }
+
+    /// The ability score the skill uses, e.g. dexterity for stealth.
+    pub fn stat_type(&self) -> StatType {
+        match self {
+            SkillType::Athletics => StatType::Strength,
+            SkillType::Acrobatics | SkillType::SleightOfHand | SkillType::Stealth => {
+                StatType::Dexterity
+            }
+            SkillType::Arcana
+            | SkillType::History
+            | SkillType::Investigation
+            | SkillType::Nature
+            | SkillType::Religion => StatType::Intelligence,
+            SkillType::AnimalHandling
+            | SkillType::Insight
+            | SkillType::Medicine
+            | SkillType::Perception
+            | SkillType::Survival => StatType::Wisdom,
+            SkillType::Deception
+            | SkillType::Intimidation
+            | SkillType::Performance
+            | SkillType::Persuasion => StatType::Charisma,
+        }
+    }
+}
+
+/// How much of the proficiency bonus is added to a skill. See
+/// [Character::skill_proficiency_tier](crate::Character::skill_proficiency_tier).
+///
+/// Tiers are ordered, so a higher tier always beats a lower one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProficiencyTier {
+    /// Nothing is added.
+    None,
+    /// Half the proficiency bonus is added, from features like Jack of All Trades.
+    Half,
+    /// The proficiency bonus is added.
+    Full,
+    /// The proficiency bonus is added twice.
+    Expertise,
 }
 
 /// Stores the proficiency/mastery of a single skill type.
@@ -636,6 +871,31 @@ pub struct EquipmentProficiencies {
     pub other: HashSet<String>,
 }
 
+impl EquipmentProficiencies {
+    /// Returns if these proficiencies cover a weapon, either through its category (like martial
+    /// weapons) or by name in [EquipmentProficiencies::other].
+    ///
+    /// Names are matched ignoring case, and a proficiency can be plural, so a proficiency in
+    /// "Longswords" covers a weapon named "Longsword".
+    pub fn is_proficient_with_weapon(&self, weapon_type: &WeaponType, name: &str) -> bool {
+        is_proficient_with(weapon_type, self)
+            || self.other.iter().any(|p| {
+                p.eq_ignore_ascii_case(name)
+                    || p.strip_suffix('s')
+                        .is_some_and(|p| p.eq_ignore_ascii_case(name))
+            })
+    }
+
+    /// Returns if these proficiencies cover armor of the given category.
+    pub fn is_proficient_with_armor(&self, category: ArmorCategory) -> bool {
+        match category {
+            ArmorCategory::Light => self.light_armor,
+            ArmorCategory::Medium => self.medium_armor,
+            ArmorCategory::Heavy => self.heavy_armor,
+        }
+    }
+}
+
 impl Add for EquipmentProficiencies {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
@@ -692,6 +952,17 @@ impl Default for Speeds {
     }
 }
 
+/// The special senses a creature has, as ranges in feet. See
+/// [Character::senses](crate::Character::senses).
+///
+/// A [None] means the creature doesn't have that sense.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Senses {
+    pub darkvision: Option<usize>,
+    pub blindsight: Option<usize>,
+    pub tremorsense: Option<usize>,
+}
+
 #[derive(
     Clone,
     Copy,
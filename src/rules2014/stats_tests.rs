@@ -1,4 +1,5 @@
 use super::stats::*;
+use rand::{rngs::StdRng, SeedableRng};
 
 #[test]
 fn shorthands() {
@@ -67,3 +68,107 @@ fn add_stats() {
     *stats.get_stat_type_mut(&StatType::Constitution) = 16;
     assert_eq!(stats.constitution, 16);
 }
+
+#[test]
+fn assign_for_class() {
+    use super::class::ClassBuilder;
+    use super::features::Feature;
+
+    let rogue_like = ClassBuilder::new()
+        .name("sneak".to_string())
+        .add_feature_at_level(
+            1,
+            Feature {
+                name: "Sneak Attack".to_string(),
+                description: vec![],
+                effects: vec![],
+            },
+        )
+        .set_hit_die(8)
+        .set_skill_proficiency_choices(1, vec![SkillType::Stealth])
+        .set_ability_priority([StatType::Dexterity, StatType::Intelligence])
+        .build()
+        .unwrap();
+
+    // order of the input values shouldn't matter
+    let stats = Stats::assign_for_class([12, 15, 8, 14, 10, 13], &rogue_like);
+    assert_eq!(stats.dexterity, 15);
+    assert_eq!(stats.intelligence, 14);
+    // the rest fall back to the default order
+    assert_eq!(stats.constitution, 13);
+    assert_eq!(stats.wisdom, 12);
+    assert_eq!(stats.strength, 10);
+    assert_eq!(stats.charisma, 8);
+}
+
+#[test]
+fn modifiers_serialization() {
+    let modifiers = Stats::from(&[20, 10, 10, 10, 12, 14]).modifiers();
+    let json = serde_json::to_value(modifiers).unwrap();
+    assert_eq!(json["strength"], 5);
+    assert_eq!(json["charisma"], 2);
+    assert!(json.get("stats").is_none());
+
+    let round_trip: Modifiers = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(round_trip, modifiers);
+
+    // the older nested form still works
+    let nested = serde_json::json!({ "stats": json });
+    let from_nested: Modifiers = serde_json::from_value(nested).unwrap();
+    assert_eq!(from_nested, modifiers);
+}
+
+#[test]
+fn stats_string() {
+    let stats: Stats = "15 14 13 12 10 8".parse().unwrap();
+    assert_eq!(stats, Stats::from(&[15, 14, 13, 12, 10, 8]));
+    assert_eq!(stats.intelligence, 12);
+    assert_eq!(stats.to_string(), "15 14 13 12 10 8");
+    assert_eq!(stats.to_string().parse(), Ok(stats));
+
+    assert_eq!(
+        "  9 9\t9 9 9 -1 ".parse(),
+        Ok(Stats::from(&[9, 9, 9, 9, 9, -1]))
+    );
+    assert_eq!(
+        "15 14 13 12 10".parse::<Stats>(),
+        Err(ParseStatsError::WrongCount(5))
+    );
+    assert_eq!(
+        "15 14 13 12 10 8 8".parse::<Stats>(),
+        Err(ParseStatsError::WrongCount(7))
+    );
+    assert_eq!(
+        "15 14 13 twelve 10 8".parse::<Stats>(),
+        Err(ParseStatsError::InvalidScore("twelve".to_string()))
+    );
+}
+
+#[test]
+fn point_buy() {
+    // the standard array costs exactly 27
+    assert_eq!(
+        Stats::from(&[15, 14, 13, 12, 10, 8]).point_buy_cost(),
+        Some(27)
+    );
+    assert_eq!(Stats::from(&[8; 6]).point_buy_cost(), Some(0));
+    assert_eq!(Stats::from(&[13; 6]).point_buy_cost(), Some(30));
+    assert_eq!(Stats::from(&[15, 14, 13, 12, 10, 7]).point_buy_cost(), None);
+    assert_eq!(Stats::from(&[16, 14, 13, 12, 10, 8]).point_buy_cost(), None);
+}
+
+#[test]
+fn rolled_stats() {
+    let mut rng = StdRng::seed_from_u64(5);
+    for _ in 0..100 {
+        let rolled = Stats::roll_4d6_drop_lowest(&mut rng);
+        assert!(rolled.iter().all(|v| (3..=18).contains(v)));
+        let rolled = Stats::roll_3d6(&mut rng);
+        assert!(rolled.iter().all(|v| (3..=18).contains(v)));
+    }
+
+    // the same seed gives the same scores
+    let first = Stats::roll_4d6_drop_lowest(&mut StdRng::seed_from_u64(1));
+    let second = Stats::roll_4d6_drop_lowest(&mut StdRng::seed_from_u64(1));
+    assert_eq!(first, second);
+}
@@ -0,0 +1,461 @@
+//! Homebrew classes, races, backgrounds and items for tests, so they don't need the api.
+use super::background::{Background, BackgroundBuilder, LanguageOption};
+use super::class::{Class, ClassBuilder, TrackedField};
+use super::features::{AbilityScoreIncrease, Feature, FeatureEffect, PresentedOption};
+use super::items::{
+    Armor, ArmorCategory, Coins, DamageRoll, DamageType, Item, ItemType, Weapon, WeaponProperties,
+    WeaponType,
+};
+use super::player_character::Character;
+use super::spells::{
+    RacialSpellUse, School, Spell, SpellCasterType, SpellCastingPreperation, SpellMechanic,
+    Spellcasting,
+};
+use super::stats::{EquipmentProficiencies, SkillType, StatType, Stats};
+use super::{Race, RaceBuilder};
+use crate::getter::DataProvider;
+use strum::IntoEnumIterator;
+
+pub(crate) fn fighter() -> Class {
+    let mut features: [Vec<PresentedOption<Feature>>; 20] = Default::default();
+    features[0].push(PresentedOption::Choice(
+        ["Archery", "Defense", "Dueling"]
+            .into_iter()
+            .map(|name| Feature {
+                name: format!("Fighting Style: {name}"),
+                description: vec![],
+                effects: vec![],
+            })
+            .collect(),
+    ));
+    for level in [4, 6, 8, 12, 14, 16, 19] {
+        features[level - 1].push(PresentedOption::Base(Feature {
+            name: "Ability Score Improvement".to_string(),
+            description: vec![],
+            effects: vec![FeatureEffect::AbilityScoreIncrease(
+                AbilityScoreIncrease::Unchosen,
+            )],
+        }));
+    }
+
+    let action_surges: [String; 20] = std::array::from_fn(|i| {
+        match i + 1 {
+            1 => "0",
+            2..=16 => "1",
+            _ => "2",
+        }
+        .to_string()
+    });
+
+    ClassBuilder::new()
+        .name("fighter".to_string())
+        .set_features(features)
+        .set_hit_die(10)
+        .add_multiple_save_proficiencies([StatType::Strength, StatType::Constitution])
+        .set_skill_proficiency_choices(
+            2,
+            vec![
+                SkillType::Acrobatics,
+                SkillType::Athletics,
+                SkillType::Intimidation,
+                SkillType::Perception,
+            ],
+        )
+        .add_equipment_proficiencies(EquipmentProficiencies {
+            simple_weapons: true,
+            martial_weapons: true,
+            light_armor: true,
+            medium_armor: true,
+            heavy_armor: true,
+            shields: true,
+            ..Default::default()
+        })
+        .set_ability_priority([StatType::Strength, StatType::Constitution])
+        .add_class_specific_field("action surges".to_string(), action_surges)
+        .add_tracked_field(TrackedField {
+            name: "Second Wind".to_string(),
+            long_rest: true,
+            short_rest: true,
+            level_up: false,
+            class_specific_max: None,
+            hard_max: Some(1),
+            class_specific_die: None,
+        })
+        .add_tracked_field(TrackedField {
+            name: "Action Surge".to_string(),
+            long_rest: true,
+            short_rest: true,
+            level_up: false,
+            class_specific_max: Some("Action Surges".to_string()),
+            hard_max: None,
+            class_specific_die: None,
+        })
+        .build()
+        .unwrap()
+}
+
+// a spell list with just cantrips and first level spells
+fn spell_list(cantrips: &[&str], first_level: &[&str]) -> [Vec<String>; 10] {
+    let mut list: [Vec<String>; 10] = Default::default();
+    list[0] = cantrips.iter().map(|s| s.to_string()).collect();
+    list[1] = first_level.iter().map(|s| s.to_string()).collect();
+    list
+}
+
+pub(crate) fn warlock() -> Class {
+    ClassBuilder::new()
+        .name("warlock".to_string())
+        .add_feature_at_level(
+            1,
+            Feature {
+                name: "Pact Magic".to_string(),
+                description: vec![],
+                effects: vec![],
+            },
+        )
+        .set_hit_die(8)
+        .add_multiple_save_proficiencies([StatType::Wisdom, StatType::Charisma])
+        .set_skill_proficiency_choices(
+            2,
+            vec![
+                SkillType::Arcana,
+                SkillType::Deception,
+                SkillType::Intimidation,
+                SkillType::Investigation,
+            ],
+        )
+        .add_equipment_proficiencies(EquipmentProficiencies {
+            simple_weapons: true,
+            light_armor: true,
+            ..Default::default()
+        })
+        .set_spellcasting(Some(Spellcasting {
+            cantrips_per_level: std::array::from_fn(|i| match i + 1 {
+                1..=3 => 2,
+                4..=9 => 3,
+                _ => 4,
+            }),
+            spellcasting_ability: StatType::Charisma,
+            spell_list: {
+                let mut list = spell_list(
+                    &["Eldritch Blast", "Mage Hand", "Minor Illusion"],
+                    &["Armor of Agathys", "Hellish Rebuke", "Hex", "Witch Bolt"],
+                );
+                list[6] = vec!["Eyebite".to_string(), "Mass Suggestion".to_string()];
+                list[7] = vec!["Etherealness".to_string()];
+                list
+            },
+            spellcaster_type: SpellCasterType::Warlock,
+            preperation_type: SpellCastingPreperation::Known,
+            spells_known_per_level: Some([
+                2, 3, 4, 5, 6, 7, 8, 9, 10, 10, 11, 11, 12, 12, 13, 13, 14, 14, 15, 15,
+            ]),
+            prepares_from_spellbook: false,
+            ritual_casting: false,
+        }))
+        .set_ability_priority([StatType::Charisma, StatType::Constitution])
+        .build()
+        .unwrap()
+}
+
+pub(crate) fn cleric() -> Class {
+    ClassBuilder::new()
+        .name("cleric".to_string())
+        .add_feature_at_level(
+            1,
+            Feature {
+                name: "Spellcasting".to_string(),
+                description: vec![],
+                effects: vec![],
+            },
+        )
+        .set_hit_die(8)
+        .add_multiple_save_proficiencies([StatType::Wisdom, StatType::Charisma])
+        .set_skill_proficiency_choices(
+            2,
+            vec![
+                SkillType::History,
+                SkillType::Insight,
+                SkillType::Medicine,
+                SkillType::Religion,
+            ],
+        )
+        .add_equipment_proficiencies(EquipmentProficiencies {
+            simple_weapons: true,
+            light_armor: true,
+            medium_armor: true,
+            shields: true,
+            ..Default::default()
+        })
+        .set_spellcasting(Some(Spellcasting {
+            cantrips_per_level: std::array::from_fn(|i| match i + 1 {
+                1..=3 => 3,
+                4..=9 => 4,
+                _ => 5,
+            }),
+            spellcasting_ability: StatType::Wisdom,
+            spell_list: spell_list(
+                &["Guidance", "Light", "Sacred Flame", "Thaumaturgy"],
+                &[
+                    "Bless",
+                    "Cure Wounds",
+                    "Guiding Bolt",
+                    "Healing Word",
+                    "Sanctuary",
+                ],
+            ),
+            spellcaster_type: SpellCasterType::Full,
+            preperation_type: SpellCastingPreperation::Prepared,
+            spells_known_per_level: None,
+            prepares_from_spellbook: false,
+            ritual_casting: true,
+        }))
+        .set_ability_priority([StatType::Wisdom, StatType::Constitution])
+        .build()
+        .unwrap()
+}
+
+pub(crate) fn monk() -> Class {
+    let mut features: [Vec<PresentedOption<Feature>>; 20] = Default::default();
+    features[0].push(PresentedOption::Base(Feature {
+        name: "Unarmored Defense".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::UnarmoredDefense(
+            10,
+            StatType::Dexterity,
+            Some(StatType::Wisdom),
+        )],
+    }));
+    features[1].push(PresentedOption::Base(Feature {
+        name: "Unarmored Movement".to_string(),
+        description: vec![],
+        effects: vec![FeatureEffect::UnarmoredMovement],
+    }));
+    // ki points equal the monk level, from 2nd level on
+    let ki_points: [String; 20] =
+        std::array::from_fn(|i| if i == 0 { 0 } else { i + 1 }.to_string());
+
+    ClassBuilder::new()
+        .name("Monk".to_string())
+        .set_features(features)
+        .set_hit_die(8)
+        .add_multiple_save_proficiencies([StatType::Strength, StatType::Dexterity])
+        .set_skill_proficiency_choices(
+            2,
+            vec![
+                SkillType::Acrobatics,
+                SkillType::Athletics,
+                SkillType::Insight,
+                SkillType::Stealth,
+            ],
+        )
+        .add_equipment_proficiencies(EquipmentProficiencies {
+            simple_weapons: true,
+            ..Default::default()
+        })
+        .set_ability_priority([StatType::Dexterity, StatType::Wisdom])
+        .add_class_specific_field("ki points".to_string(), ki_points)
+        .add_tracked_field(TrackedField {
+            name: "Ki Points".to_string(),
+            long_rest: true,
+            short_rest: true,
+            level_up: false,
+            class_specific_max: Some("Ki Points".to_string()),
+            hard_max: None,
+            class_specific_die: None,
+        })
+        .build()
+        .unwrap()
+}
+
+pub(crate) fn shield() -> Item {
+    Item {
+        name: "Shield".to_string(),
+        description: None,
+        item_type: ItemType::Shield,
+        weight: None,
+        attunement: None,
+        features: vec![],
+    }
+}
+
+/// A simple spell with no damage.
+pub(crate) fn spell(name: &str, level: usize) -> Spell {
+    Spell {
+        name: name.to_string(),
+        description: vec![],
+        higher_level: vec![],
+        ritual: false,
+        concentration: false,
+        casting_time: "1 action".to_string(),
+        duration: "Instantaneous".to_string(),
+        level,
+        range: "60 feet".to_string(),
+        school: School::Evocation,
+        components: vec!['V', 'S'],
+        material: None,
+        damage: None,
+        leveled_damage: None,
+        mechanic: SpellMechanic::Automatic,
+    }
+}
+
+pub(crate) fn human() -> Race {
+    RaceBuilder::new("human")
+        .add_ability_bonuses(StatType::iter().map(|s| (Some(s), 1)))
+        .add_language("Common".to_string())
+        .add_wildcard_language()
+        .build()
+}
+
+pub(crate) fn tiefling() -> Race {
+    let mut hellish_rebuke = spell("Hellish Rebuke", 1);
+    hellish_rebuke.damage = Some(vec![vec![DamageRoll::new(2, 10, 0, DamageType::Fire)]]);
+    hellish_rebuke.mechanic = SpellMechanic::Save {
+        stat: StatType::Dexterity,
+        half_on_success: true,
+    };
+    let once = RacialSpellUse::OncePerLongRest { used: false };
+    let effects = [
+        (spell("Thaumaturgy", 0), RacialSpellUse::AtWill, 1),
+        (hellish_rebuke, once, 3),
+        (spell("Darkness", 2), once, 5),
+    ]
+    .into_iter()
+    .map(|(spell, usage, level)| FeatureEffect::RacialSpell {
+        spell: Box::new(spell),
+        ability: StatType::Charisma,
+        usage,
+        level,
+    })
+    .collect();
+
+    RaceBuilder::new("tiefling")
+        .add_ability_bonus(Some(StatType::Charisma), 2)
+        .add_ability_bonus(Some(StatType::Intelligence), 1)
+        .add_language("Common".to_string())
+        .add_language("Infernal".to_string())
+        .add_trait(PresentedOption::Base(Feature {
+            name: "Darkvision".to_string(),
+            description: vec![],
+            effects: vec![FeatureEffect::Darkvision(60)],
+        }))
+        .add_trait(PresentedOption::Base(Feature {
+            name: "Infernal Legacy".to_string(),
+            description: vec![],
+            effects,
+        }))
+        .build()
+}
+
+pub(crate) fn soldier() -> Background {
+    BackgroundBuilder::new("soldier")
+        .add_proficiency(PresentedOption::Base(SkillType::Athletics))
+        .add_proficiency(PresentedOption::Base(SkillType::Intimidation))
+        .add_language_option(LanguageOption::UnnamedChoice)
+        .add_personality_trait("I'm always polite and respectful.".to_string())
+        .add_personality_trait("I can stare down a hell hound without flinching.".to_string())
+        .add_ideal("Responsibility. I do what I must and obey just authority.".to_string())
+        .add_bond("I fight for those who cannot fight for themselves.".to_string())
+        .add_flaw("I obey the law, even if the law causes misery.".to_string())
+        .set_starting_coins(Coins {
+            gp: 10,
+            ..Default::default()
+        })
+        .build()
+        .unwrap()
+}
+
+pub(crate) fn longsword() -> Item {
+    Item {
+        name: "Longsword".to_string(),
+        description: None,
+        item_type: ItemType::Weapon(Weapon {
+            damage: DamageRoll::new(1, 8, 0, DamageType::Slashing),
+            attack_roll_bonus: 0,
+            weapon_type: WeaponType::Martial,
+            properties: WeaponProperties {
+                versatile: Some(DamageRoll::new(1, 10, 0, DamageType::Slashing)),
+                ..Default::default()
+            },
+            range: None,
+            thrown_range: None,
+        }),
+        weight: None,
+        attunement: None,
+        features: vec![],
+    }
+}
+
+pub(crate) fn chain_mail() -> Item {
+    Item {
+        name: "Chain Mail".to_string(),
+        description: None,
+        item_type: ItemType::Armor(Armor {
+            ac: 16,
+            category: ArmorCategory::Heavy,
+            strength_minimum: Some(13),
+            stealth_disadvantage: true,
+        }),
+        weight: None,
+        attunement: None,
+        features: vec![],
+    }
+}
+
+/// A level 1 human fighter with 15 14 13 12 10 8 stats (before racial bonuses).
+pub(crate) fn character(name: &str) -> Character {
+    Character::new(
+        name.to_string(),
+        &fighter(),
+        &soldier(),
+        &human(),
+        Stats::from(&[15, 13, 14, 8, 12, 10]),
+    )
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("no homebrew {0}")]
+pub(crate) struct Missing(pub(crate) String);
+
+impl crate::LookupError for Missing {
+    fn is_not_found(&self) -> bool {
+        true
+    }
+}
+
+// serves the homebrew parts above by name
+pub(crate) struct HomebrewProvider;
+
+#[async_trait::async_trait]
+impl DataProvider<Missing> for HomebrewProvider {
+    async fn get_race(&self, name: &str) -> Result<Race, Missing> {
+        match name {
+            "Human" => Ok(human()),
+            _ => Err(Missing(name.to_string())),
+        }
+    }
+    async fn get_background(&self, name: &str) -> Result<Background, Missing> {
+        match name {
+            "Soldier" => Ok(soldier()),
+            _ => Err(Missing(name.to_string())),
+        }
+    }
+    async fn get_item(&self, name: &str) -> Result<Item, Missing> {
+        match name {
+            "Longsword" => Ok(longsword()),
+            "Chain Mail" => Ok(chain_mail()),
+            _ => Err(Missing(name.to_string())),
+        }
+    }
+    async fn get_class(&self, name: &str) -> Result<Class, Missing> {
+        match name {
+            "Fighter" => Ok(fighter()),
+            "Cleric" => Ok(cleric()),
+            _ => Err(Missing(name.to_string())),
+        }
+    }
+    async fn get_spell(&self, name: &str) -> Result<Spell, Missing> {
+        Ok(spell(name, 1))
+    }
+}
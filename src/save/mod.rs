@@ -28,13 +28,70 @@
 //! }
 //! # }
 //! ```
+//!
+//! Characters should be saved with [save_character] and read back with [load_character]
+//! instead. These tag the file with [CHARACTER_SAVE_VERSION], so a save from an incompatible
+//! version of the library gives a [SaveError::VersionMismatch] rather than a confusing parse
+//! error. Characters saved before versioning, with [save_serialized], can still be loaded.
 
+use crate::rules2014::player_character::Character;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fs::{self, File};
-use std::io::BufReader;
+use std::io::{self, BufReader};
 use std::path::Path;
+use thiserror::Error;
+
+/// The current version of the [Character] save format.
+///
+/// This is bumped whenever a change to [Character] means older saves can't be read anymore.
+pub const CHARACTER_SAVE_VERSION: u32 = 1;
+
+/// A [Character] tagged with the version of the save format it was written in.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedCharacter {
+    pub version: u32,
+    pub character: Character,
+}
+
+impl From<Character> for SavedCharacter {
+    fn from(character: Character) -> Self {
+        SavedCharacter {
+            version: CHARACTER_SAVE_VERSION,
+            character,
+        }
+    }
+}
+
+// Serializes the same as SavedCharacter, without needing to clone the character.
+#[derive(Serialize)]
+struct SavedCharacterRef<'a> {
+    version: u32,
+    character: &'a Character,
+}
+
+// Read before the rest of the save, so a version mismatch is caught before parsing the character.
+// Saves from before versioning don't have the field, and are treated as version 0.
+#[derive(Deserialize)]
+struct SaveVersion {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Errors from saving or loading a [Character].
+#[derive(Debug, Error)]
+pub enum SaveError {
+    /// The file couldn't be read or written.
+    #[error("failed to access save file: {0}")]
+    Io(#[from] io::Error),
+    /// The file isn't a valid save.
+    #[error("failed to parse save file: {0}")]
+    Parse(#[from] serde_json::Error),
+    /// The save was written by a different version of the save format.
+    #[error("save file is version {found}, but version {expected} was expected")]
+    VersionMismatch { found: u32, expected: u32 },
+}
 
 /// Save the serializable datastructure to the given path.
 pub fn save_serialized<T: Serialize>(path: &Path, t: &T) -> Result<(), Box<dyn Error>> {
@@ -50,3 +107,88 @@ pub fn get_serialized<T: DeserializeOwned>(path: &Path) -> Result<T, Box<dyn Err
 
     Ok(serde_json::from_reader(reader)?)
 }
+
+/// Saves a character to the given path, tagged with the current [CHARACTER_SAVE_VERSION].
+pub fn save_character(path: &Path, character: &Character) -> Result<(), SaveError> {
+    let saved = SavedCharacterRef {
+        version: CHARACTER_SAVE_VERSION,
+        character,
+    };
+    fs::write(path, serde_json::to_string(&saved)?)?;
+    Ok(())
+}
+
+/// Loads a character saved with [save_character].
+///
+/// Saves without a version are treated as version 0, and are read as a bare [Character], like
+/// one saved with [save_serialized].
+///
+/// # Errors
+/// Returns [SaveError::VersionMismatch] if the save is from a version that can't be read, like
+/// a newer [CHARACTER_SAVE_VERSION], and [SaveError::Parse] if a version 0 save isn't a
+/// character.
+pub fn load_character(path: &Path) -> Result<Character, SaveError> {
+    let contents = fs::read_to_string(path)?;
+
+    let SaveVersion { version } = serde_json::from_str(&contents)?;
+    match version {
+        0 => Ok(serde_json::from_str(&contents)?),
+        CHARACTER_SAVE_VERSION => {
+            let saved: SavedCharacter = serde_json::from_str(&contents)?;
+            Ok(saved.character)
+        }
+        found => Err(SaveError::VersionMismatch {
+            found,
+            expected: CHARACTER_SAVE_VERSION,
+        }),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "disk-tests")]
+mod tests {
+    use super::{load_character, save_character, save_serialized, SaveError};
+    use crate::rules2014::test_fixtures::character;
+
+    #[test]
+    fn character_round_trip() {
+        let dir = std::env::temp_dir().join("dnd_lib_character_round_trip");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let john = character("john");
+        let path = dir.join("john.json");
+        save_character(&path, &john).unwrap();
+        let loaded = load_character(&path).unwrap();
+        assert_eq!(loaded.name, "john");
+        assert_eq!(loaded.stats(), john.stats());
+
+        // a character saved without a version still loads
+        let old_path = dir.join("old.json");
+        save_serialized(&old_path, &john).unwrap();
+        let loaded = load_character(&old_path).unwrap();
+        assert_eq!(loaded.name, "john");
+        assert_eq!(loaded.stats(), john.stats());
+
+        let not_character = dir.join("not_character.json");
+        std::fs::write(&not_character, r#"{"name": 5}"#).unwrap();
+        assert!(matches!(
+            load_character(&not_character),
+            Err(SaveError::Parse(_))
+        ));
+
+        let future_path = dir.join("future.json");
+        std::fs::write(&future_path, r#"{"version": 99, "character": {}}"#).unwrap();
+        assert!(matches!(
+            load_character(&future_path),
+            Err(SaveError::VersionMismatch { found: 99, .. })
+        ));
+
+        assert!(matches!(
+            load_character(&dir.join("missing.json")),
+            Err(SaveError::Io(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
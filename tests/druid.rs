@@ -70,6 +70,15 @@ async fn level_3_druid() {
     );
     assert_eq!(boopo.available_pact_slots, None);
 
+    let spells = try_join_all(spells).await.expect("Couldn't get spells");
+    for spell in spells.iter() {
+        assert!(
+            boopo.can_prepare(0, spell),
+            "{} should be on the druid spell list",
+            spell.name
+        );
+    }
+
     let v = boopo.prepare_spells_multiple();
     assert_eq!(
         v.len(),
@@ -85,7 +94,7 @@ async fn level_3_druid() {
         spell_amounts.num_cantrips, 2,
         "incorrect number of cantrips to prepare"
     );
-    *prepped_spell_list = try_join_all(spells).await.expect("Couldn't get spells");
+    *prepped_spell_list = spells;
 
     let spells = boopo.classes[0]
         .spellcasting
@@ -141,18 +150,36 @@ async fn level_3_druid() {
         .get(3)
         .expect("Couldn't get moonbeam spell attack");
 
-    boopo.cast(poison_spray, None);
+    boopo.cast(poison_spray, None, None);
     assert_eq!(
         boopo.available_spell_slots,
         Some(SpellSlots([4, 2, 0, 0, 0, 0, 0, 0, 0])),
         "Spell slots after casting poison spray did not match expected value"
     );
-    boopo.cast(moonbeam, None);
+    boopo.cast(moonbeam, None, None);
     assert_eq!(
         boopo.available_spell_slots,
         Some(SpellSlots([4, 1, 0, 0, 0, 0, 0, 0, 0])),
         "Spell slots after casting moonbeam did not match expected value"
     );
+    // no 3rd level slots to upcast moonbeam with, and it can't be downcast
+    assert!(!boopo.cast(moonbeam, Some(3), None));
+    assert!(!boopo.cast(moonbeam, Some(1), None));
+    let thunderwave = spell_attacks
+        .get(1)
+        .expect("Couldn't get thunderwave spell attack");
+    assert!(boopo.cast(thunderwave, Some(2), None));
+    assert_eq!(
+        boopo.available_spell_slots,
+        Some(SpellSlots([4, 0, 0, 0, 0, 0, 0, 0, 0])),
+        "Spell slots after upcasting thunderwave did not match expected value"
+    );
+    boopo.restore_slot(2);
+    assert_eq!(
+        boopo.available_spell_slots,
+        Some(SpellSlots([4, 1, 0, 0, 0, 0, 0, 0, 0])),
+        "Spell slots after restoring a slot did not match expected value"
+    );
 
     boopo.long_rest();
     assert_eq!(
@@ -191,7 +191,7 @@ async fn level_10_warlock() {
         let eldritch_blast = spell_actions
             .first()
             .expect("Couldn't get eldritch blast spell attack");
-        baroopa.cast(eldritch_blast, None);
+        baroopa.cast(eldritch_blast, None, None);
     }
     assert_eq!(
         baroopa.available_pact_slots,
@@ -208,7 +208,7 @@ async fn level_10_warlock() {
         "6d10 Fire",
         "Hellish rebuke damage roll when cast at 5th level did not match expected value"
     );
-    baroopa.cast(hellish_rebuke, None);
+    baroopa.cast(hellish_rebuke, None, None);
     assert_eq!(
         baroopa.available_pact_slots,
         Some(PactSlots { num: 1, level: 5 }),
@@ -224,7 +224,7 @@ async fn level_10_warlock() {
         "9d8 Necrotic",
         "Blight damage roll when cast at 5th level did not match expected value"
     );
-    baroopa.cast(blight, None);
+    baroopa.cast(blight, None, None);
     assert_eq!(
         baroopa.available_pact_slots,
         Some(PactSlots { num: 0, level: 5 }),
@@ -240,7 +240,7 @@ async fn level_10_warlock() {
     );
 
     // long rest should restore pact slots
-    baroopa.cast(hellish_rebuke, None);
+    baroopa.cast(hellish_rebuke, None, None);
     assert_eq!(
         baroopa.available_pact_slots,
         Some(PactSlots { num: 1, level: 5 }),